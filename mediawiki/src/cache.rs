@@ -0,0 +1,432 @@
+//! Downloading the content of many pages at once, with an on-disk cache.
+//!
+//! Pages are fetched in batches of [`MAX_TITLES`] on several threads. A [`Cache`] keeps the
+//! content of each page under its latest revision ID, so that a later run only downloads the pages
+//! that were edited since.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::query::{self, Page, MAX_TITLES};
+use crate::{Result, Source};
+
+/// The extension of the files in the cache directory.
+const CACHE_EXTENSION: &str = "wiki";
+
+/// An on-disk cache of page contents, keyed by revision ID.
+///
+/// Since a revision never changes, a cached page is up to date as long as its latest revision ID
+/// is the one it was cached under.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    directory: PathBuf,
+}
+
+impl Cache {
+    /// Opens a cache in the given directory, creating the directory if it does not exist.
+    pub fn open<P: AsRef<Path>>(directory: P) -> io::Result<Cache> {
+        let directory = directory.as_ref().to_owned();
+        fs::create_dir_all(&directory)?;
+        Ok(Cache { directory })
+    }
+
+    fn path(&self, revision: u64) -> PathBuf {
+        self.directory
+            .join(revision.to_string())
+            .with_extension(CACHE_EXTENSION)
+    }
+
+    /// Returns the content of a revision, if it is cached.
+    pub fn get(&self, revision: u64) -> Option<String> {
+        fs::read_to_string(self.path(revision)).ok()
+    }
+
+    /// Saves the content of a revision.
+    pub fn insert(&self, revision: u64, content: &str) -> io::Result<()> {
+        fs::write(self.path(revision), content)
+    }
+
+    /// Removes every cached revision that isn't in `keep`, so that the cache only holds the
+    /// latest revision of each page. Files that the cache didn't write are left alone.
+    pub fn retain(&self, keep: &HashSet<u64>) -> io::Result<()> {
+        for entry in fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            if path.extension() != Some(CACHE_EXTENSION.as_ref()) {
+                continue;
+            }
+
+            let revision = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse().ok());
+
+            match revision {
+                Some(revision) if !keep.contains(&revision) => fs::remove_file(path)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The content of the latest revision of a page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageContent {
+    /// The title of the page.
+    pub title: String,
+
+    /// The wiki markup of the page.
+    pub content: String,
+}
+
+/// Statistics about a run of [`fetch_contents`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// The number of pages.
+    pub pages: usize,
+
+    /// The number of pages whose content was found in the cache.
+    pub cache_hits: usize,
+
+    /// The number of pages that were downloaded.
+    pub fetched: usize,
+
+    /// How long the run took.
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} pages in {:.2}s: {} cache hits, {} fetched",
+            self.pages,
+            self.elapsed.as_secs_f64(),
+            self.cache_hits,
+            self.fetched,
+        )
+    }
+}
+
+/// Fetches the latest revisions of the given pages, `MAX_TITLES` at a time, using up to `workers`
+/// threads. Returns the first error any of the threads ran into.
+fn fetch_revisions(
+    source: &(dyn Source + Sync),
+    titles: &[&str],
+    workers: usize,
+) -> Result<Vec<Page>> {
+    let batches = titles.chunks(MAX_TITLES).collect::<Vec<_>>();
+    let next_batch = AtomicUsize::new(0);
+    let results = Mutex::new(vec![]);
+
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, batches.len().max(1)) {
+            scope.spawn(|| {
+                while let Some(batch) = batches.get(next_batch.fetch_add(1, Ordering::SeqCst)) {
+                    let result = query::revisions(source, batch);
+                    let failed = result.is_err();
+                    results.lock().unwrap().push(result);
+
+                    if failed {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    let mut pages = vec![];
+    for result in results.into_inner().unwrap() {
+        pages.extend(result?.pages);
+    }
+    Ok(pages)
+}
+
+/// Fetches the content of the pages with the given titles, using up to `workers` threads.
+///
+/// If a cache is given, the latest revision ID of each page is looked up first, and only pages
+/// whose latest revision isn't cached are downloaded. Every other revision is removed from the
+/// cache, including those of pages that weren't requested, so a cache should only be used for one
+/// set of pages. Pages are returned sorted by title, under the titles that the wiki resolved the
+/// given titles to after normalization and redirects.
+pub fn fetch_contents(
+    source: &(dyn Source + Sync),
+    titles: &[&str],
+    cache: Option<&Cache>,
+    workers: usize,
+) -> Result<(Vec<PageContent>, Summary)> {
+    let start = Instant::now();
+
+    let mut pages = vec![];
+    let mut stale_titles = titles.to_vec();
+
+    if let Some(cache) = cache {
+        let info = query::info(source, titles)?;
+        let latest_revisions = info
+            .pages
+            .iter()
+            .filter_map(|page| page.last_revision_id)
+            .collect();
+        cache.retain(&latest_revisions)?;
+
+        // Pages are titled as the wiki resolved them, the same as fetched pages are, and a page
+        // that several titles resolve to is only returned once.
+        let mut hits = HashSet::new();
+        stale_titles.clear();
+        for &title in titles {
+            let page = info.get(title);
+            let revision = page.and_then(|page| page.last_revision_id);
+            match (page, revision.and_then(|revision| cache.get(revision))) {
+                (Some(page), Some(content)) => {
+                    if hits.insert(&page.title) {
+                        pages.push(PageContent {
+                            title: page.title.clone(),
+                            content,
+                        });
+                    }
+                }
+                _ => stale_titles.push(title),
+            }
+        }
+    }
+
+    let cache_hits = pages.len();
+
+    for page in fetch_revisions(source, &stale_titles, workers)? {
+        let revision = match page.revisions.into_iter().next() {
+            Some(revision) => revision,
+            None => continue,
+        };
+
+        if let (Some(cache), Some(id)) = (cache, revision.id) {
+            cache.insert(id, &revision.content)?;
+        }
+
+        pages.push(PageContent {
+            title: page.title,
+            content: revision.content,
+        });
+    }
+
+    let fetched = pages.len() - cache_hits;
+    pages.sort_by(|a, b| a.title.cmp(&b.title));
+
+    Ok((
+        pages,
+        Summary {
+            pages: titles.len(),
+            cache_hits,
+            fetched,
+            elapsed: start.elapsed(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::sync::Mutex;
+
+    use serde_json::json;
+
+    use super::{fetch_contents, Cache};
+    use crate::{Result, Source};
+
+    /// The number of pages on the fake wiki, enough to need several batches of revisions.
+    const PAGES: u64 = 120;
+
+    /// A wiki on which each page has its own revisions and content, and pages can be edited.
+    struct FakeWiki {
+        /// The number of times each page has been edited.
+        edits: Mutex<Vec<u64>>,
+
+        /// The titles of the pages whose content was requested, in every query so far.
+        fetched: Mutex<Vec<String>>,
+    }
+
+    impl FakeWiki {
+        fn new() -> FakeWiki {
+            FakeWiki {
+                edits: Mutex::new(vec![0; PAGES as usize]),
+                fetched: Mutex::new(vec![]),
+            }
+        }
+
+        fn edit(&self, id: u64) {
+            self.edits.lock().unwrap()[id as usize] += 1;
+        }
+
+        /// The latest revision ID of a page, which is different for every page and edit.
+        fn revision(&self, id: u64) -> u64 {
+            id * 1000 + self.edits.lock().unwrap()[id as usize]
+        }
+
+        /// The content of the latest revision of a page.
+        fn content(&self, id: u64) -> String {
+            format!("Page {} revision {}", id, self.revision(id))
+        }
+    }
+
+    impl Source for FakeWiki {
+        fn query(&self, params: &[(&str, &str)]) -> Result<String> {
+            let param = |name| params.iter().find(|(k, _)| *k == name).map(|(_, v)| *v);
+            let titles = param("titles").unwrap().split('|');
+
+            // "Alias N" redirects to "Page N".
+            let redirects = titles
+                .clone()
+                .filter(|title| title.starts_with("Alias "))
+                .map(|title| json!({ "from": title, "to": title.replace("Alias", "Page") }))
+                .collect::<Vec<_>>();
+            let pages = |page: &dyn Fn(&str, u64) -> serde_json::Value| {
+                titles
+                    .clone()
+                    .map(|title| {
+                        let id: u64 = title[title.find(' ').unwrap() + 1..].parse().unwrap();
+                        (id.to_string(), page(&format!("Page {}", id), id))
+                    })
+                    .collect::<serde_json::Map<_, _>>()
+            };
+
+            let response = match param("prop") {
+                Some("info") => json!({ "query": {
+                    "redirects": redirects,
+                    "pages": pages(&|title, id| json!({
+                        "title": title, "lastrevid": self.revision(id),
+                    })),
+                }}),
+                _ => {
+                    self.fetched
+                        .lock()
+                        .unwrap()
+                        .extend(titles.clone().map(String::from));
+                    json!({ "query": {
+                        "redirects": redirects,
+                        "pages": pages(&|title, id| json!({
+                            "title": title,
+                            "revisions": [{ "revid": self.revision(id), "*": self.content(id) }],
+                        })),
+                    }})
+                }
+            };
+
+            Ok(response.to_string())
+        }
+    }
+
+    fn titles() -> Vec<String> {
+        (0..PAGES).map(|id| format!("Page {}", id)).collect()
+    }
+
+    #[test]
+    fn reruns_only_fetch_changed_pages() {
+        let directory = env::temp_dir().join(format!("mediawiki-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let cache = Cache::open(&directory).unwrap();
+        let wiki = FakeWiki::new();
+        let titles = titles();
+        let titles = titles.iter().map(String::as_str).collect::<Vec<_>>();
+
+        let (pages, summary) = fetch_contents(&wiki, &titles, Some(&cache), 2).unwrap();
+        assert_eq!(pages.len(), PAGES as usize);
+        assert_eq!((summary.cache_hits, summary.fetched), (0, PAGES as usize));
+        assert_eq!(wiki.fetched.lock().unwrap().len(), PAGES as usize);
+        for page in &pages {
+            assert_eq!(page.content, wiki.content(page.title[5..].parse().unwrap()));
+        }
+
+        // Each page comes back from the cache with its own content.
+        wiki.fetched.lock().unwrap().clear();
+        let (cached_pages, summary) = fetch_contents(&wiki, &titles, Some(&cache), 2).unwrap();
+        assert_eq!(cached_pages, pages);
+        assert_eq!((summary.cache_hits, summary.fetched), (PAGES as usize, 0));
+        assert!(wiki.fetched.lock().unwrap().is_empty());
+
+        // Only the edited page is downloaded again, and its old revision leaves the cache.
+        let old_revision = wiki.revision(7);
+        wiki.edit(7);
+        let (edited_pages, summary) = fetch_contents(&wiki, &titles, Some(&cache), 2).unwrap();
+        assert_eq!(
+            (summary.cache_hits, summary.fetched),
+            (PAGES as usize - 1, 1)
+        );
+        assert_eq!(*wiki.fetched.lock().unwrap(), ["Page 7"]);
+        for (edited, page) in edited_pages.iter().zip(&pages) {
+            if edited.title == "Page 7" {
+                assert_eq!(edited.content, wiki.content(7));
+                assert_ne!(edited.content, page.content);
+            } else {
+                assert_eq!(edited, page);
+            }
+        }
+        assert!(cache.get(old_revision).is_none());
+        assert_eq!(cache.get(wiki.revision(7)), Some(wiki.content(7)));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn redirects_are_titled_alike_from_the_cache() {
+        let directory = env::temp_dir().join(format!("mediawiki-redirects-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let cache = Cache::open(&directory).unwrap();
+        let wiki = FakeWiki::new();
+        let titles = ["Alias 3", "Page 4"];
+
+        let (fetched, summary) = fetch_contents(&wiki, &titles, Some(&cache), 1).unwrap();
+        assert_eq!(summary.fetched, 2);
+        let (cached, summary) = fetch_contents(&wiki, &titles, Some(&cache), 1).unwrap();
+        assert_eq!(summary.cache_hits, 2);
+
+        assert_eq!(cached, fetched);
+        assert_eq!(
+            cached
+                .iter()
+                .map(|page| &page.title[..])
+                .collect::<Vec<_>>(),
+            ["Page 3", "Page 4"]
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn retain_only_removes_cached_revisions() {
+        let directory = env::temp_dir().join(format!("mediawiki-retain-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let cache = Cache::open(&directory).unwrap();
+        cache.insert(1, "one").unwrap();
+        cache.insert(2, "two").unwrap();
+        fs::write(directory.join("3.txt"), "not a revision").unwrap();
+        fs::write(directory.join("4"), "not a revision").unwrap();
+
+        cache.retain(&vec![2].into_iter().collect()).unwrap();
+
+        assert_eq!(cache.get(1), None);
+        assert_eq!(cache.get(2), Some("two".to_owned()));
+        assert!(directory.join("3.txt").exists());
+        assert!(directory.join("4").exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn without_cache() {
+        let wiki = FakeWiki::new();
+        let titles = titles();
+        let titles = titles.iter().map(String::as_str).collect::<Vec<_>>();
+
+        let (pages, summary) = fetch_contents(&wiki, &titles, None, 8).unwrap();
+        assert_eq!(pages.len(), PAGES as usize);
+        assert_eq!(summary.cache_hits, 0);
+        assert_eq!(wiki.fetched.lock().unwrap().len(), PAGES as usize);
+    }
+}
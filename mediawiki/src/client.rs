@@ -0,0 +1,202 @@
+//! Queries the live wiki over HTTP.
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use reqwest::blocking::{Client as HttpClient, Response};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{StatusCode, Url};
+
+use crate::{Result, Source, API_URL};
+
+/// Queries a MediaWiki API over HTTP.
+///
+/// Requests that fail because of a network error, a server error or rate limiting on the server
+/// are retried with exponential backoff. The client can also limit how often it sends requests, to
+/// be polite to the wiki. A client can be shared between threads.
+#[derive(Debug)]
+pub struct Client {
+    http: HttpClient,
+    api_url: Url,
+    retries: u32,
+    backoff: Duration,
+    min_interval: Duration,
+
+    /// When the last request was sent, for rate limiting.
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl Client {
+    /// Creates a client for the MediaWiki API at the given URL, such as
+    /// `https://en.wikipedia.org/w/api.php`.
+    ///
+    /// By default, failed requests are retried 3 times, starting with a one second delay, and
+    /// requests are not rate limited.
+    pub fn new(api_url: Url) -> Client {
+        Client {
+            http: HttpClient::new(),
+            api_url,
+            retries: 3,
+            backoff: Duration::from_secs(1),
+            min_interval: Duration::ZERO,
+            last_request: Mutex::new(None),
+        }
+    }
+
+    /// Sets how many times a failed request is retried before giving up.
+    pub fn retries(mut self, retries: u32) -> Client {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets the delay before the first retry of a failed request. The delay doubles with each
+    /// retry, unless the server says how long to wait.
+    pub fn backoff(mut self, backoff: Duration) -> Client {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Sets the minimum time between the start of two requests.
+    pub fn rate_limit(mut self, min_interval: Duration) -> Client {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// Returns the URL of the API that the client queries.
+    pub fn api_url(&self) -> &Url {
+        &self.api_url
+    }
+
+    /// Blocks until enough time has passed since the last request.
+    fn wait_for_turn(&self) {
+        let mut last_request = self.last_request.lock().unwrap();
+
+        if let Some(last) = *last_request {
+            let elapsed = last.elapsed();
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+
+        *last_request = Some(Instant::now());
+    }
+
+    /// Returns how long to wait before retrying a request that got the given response, or `None`
+    /// if the request shouldn't be retried.
+    fn retry_delay(&self, response: &Response, attempt: u32) -> Option<Duration> {
+        let status = response.status();
+        if status != StatusCode::TOO_MANY_REQUESTS && !status.is_server_error() {
+            return None;
+        }
+
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs);
+
+        Some(retry_after.unwrap_or(self.backoff * 2u32.pow(attempt)))
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::new(Url::parse(API_URL).unwrap())
+    }
+}
+
+impl Source for Client {
+    fn query(&self, params: &[(&str, &str)]) -> Result<String> {
+        let mut url = self.api_url.clone();
+        url.query_pairs_mut().extend_pairs(params);
+
+        let mut attempt = 0;
+        loop {
+            self.wait_for_turn();
+            let can_retry = attempt < self.retries;
+
+            match self.http.get(url.clone()).send() {
+                Ok(response) => match self.retry_delay(&response, attempt) {
+                    Some(delay) if can_retry => thread::sleep(delay),
+                    _ => return Ok(response.error_for_status()?.text()?),
+                },
+                Err(err) if can_retry && (err.is_connect() || err.is_timeout()) => {
+                    thread::sleep(self.backoff * 2u32.pow(attempt));
+                }
+                Err(err) => return Err(err.into()),
+            }
+
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use reqwest::Url;
+
+    use super::Client;
+    use crate::Source;
+
+    /// Serves each of the given HTTP responses in turn on a local port, and returns the URL of
+    /// the server.
+    fn serve(responses: Vec<&'static str>) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api.php", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+
+                // Read the request headers before responding.
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        Url::parse(&url).unwrap()
+    }
+
+    const UNAVAILABLE: &str =
+        "HTTP/1.1 503 Service Unavailable\r\nConnection: close\r\nContent-Length: 0\r\n\r\n";
+    const OK: &str = "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 2\r\n\r\n{}";
+
+    #[test]
+    fn retries_server_errors() {
+        let client = Client::new(serve(vec![UNAVAILABLE, UNAVAILABLE, OK]))
+            .backoff(Duration::from_millis(1));
+
+        assert_eq!(client.query(&[("action", "query")]).unwrap(), "{}");
+    }
+
+    #[test]
+    fn gives_up_after_retries() {
+        let client = Client::new(serve(vec![UNAVAILABLE, UNAVAILABLE]))
+            .retries(1)
+            .backoff(Duration::from_millis(1));
+
+        assert!(client.query(&[("action", "query")]).is_err());
+    }
+
+    #[test]
+    fn rate_limit() {
+        let client = Client::new(serve(vec![OK, OK])).rate_limit(Duration::from_millis(200));
+
+        let start = Instant::now();
+        client.query(&[]).unwrap();
+        client.query(&[]).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
@@ -0,0 +1,128 @@
+//! A small client for the [MediaWiki API] used by Rosetta Code.
+//!
+//! Everything that talks to the wiki goes through the [`Source`] trait. The live wiki is queried
+//! through a [`Client`], which retries failed requests and can be rate limited. A
+//! [`RecordingSource`] saves the responses of another source to a directory, so that they can be
+//! replayed later by a [`SnapshotSource`] without network access.
+//!
+//! The [`query`] module builds typed queries on top of any source, following continuations so
+//! that callers always see complete results. [`fetch_contents`] downloads many pages at once, and
+//! can keep them in a [`Cache`] so that only edited pages are downloaded again.
+//!
+//! [MediaWiki API]: https://www.mediawiki.org/wiki/API:Main_page
+
+#![warn(missing_docs)]
+
+use std::error;
+use std::fmt;
+use std::io;
+
+pub use reqwest::Url;
+
+pub use cache::{fetch_contents, Cache, PageContent, Summary};
+pub use client::Client;
+pub use query::{
+    category_members, info, revisions, CategoryMember, Page, Pages, Redirect, Revision,
+};
+pub use snapshot::{RecordingSource, SnapshotSource};
+
+mod cache;
+mod client;
+pub mod query;
+mod snapshot;
+
+/// The URL of the MediaWiki API on Rosetta Code.
+pub const API_URL: &str = "http://rosettacode.org/mw/api.php";
+
+/// The result type of every fallible operation in this crate.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Something that can answer MediaWiki API queries.
+pub trait Source {
+    /// Performs a query with the given parameters and returns the raw JSON response body.
+    ///
+    /// The parameters are passed through as-is, so they should include `action` and `format`.
+    fn query(&self, params: &[(&str, &str)]) -> Result<String>;
+}
+
+impl<S: Source + ?Sized> Source for &S {
+    fn query(&self, params: &[(&str, &str)]) -> Result<String> {
+        (**self).query(params)
+    }
+}
+
+impl<S: Source + ?Sized> Source for Box<S> {
+    fn query(&self, params: &[(&str, &str)]) -> Result<String> {
+        (**self).query(params)
+    }
+}
+
+/// The errors that can occur while querying the wiki.
+#[derive(Debug)]
+pub enum Error {
+    /// Something went wrong with the HTTP request to the API.
+    Http(reqwest::Error),
+
+    /// There was a problem parsing the API response into JSON.
+    Json(serde_json::Error),
+
+    /// A snapshot of responses or a cache of pages could not be read or written.
+    Io(io::Error),
+
+    /// The API reported an error, such as an invalid parameter.
+    Api {
+        /// The machine-readable error code.
+        code: String,
+
+        /// A human-readable description of the error.
+        info: String,
+    },
+
+    /// The response JSON contained unexpected keys or values.
+    UnexpectedFormat(String),
+
+    /// A snapshot has no recorded response for the query.
+    NotRecorded(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Http(err) => write!(f, "HTTP request failed: {}", err),
+            Error::Json(err) => write!(f, "invalid JSON in response: {}", err),
+            Error::Io(err) => write!(f, "could not access snapshot or cache: {}", err),
+            Error::Api { code, info } => write!(f, "API error ({}): {}", code, info),
+            Error::UnexpectedFormat(what) => write!(f, "unexpected response format: {}", what),
+            Error::NotRecorded(query) => write!(f, "no recorded response for query: {}", query),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Http(err) => Some(err),
+            Error::Json(err) => Some(err),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Http(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
@@ -0,0 +1,388 @@
+//! Typed queries against the MediaWiki API.
+//!
+//! Large results are split across several responses by the API. [`Continuation`] follows the
+//! `continue` parameters of each response to fetch the rest, and the typed queries built on it
+//! merge the responses back together.
+
+use serde::de::{Deserializer, IgnoredAny};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{Error, Result, Source};
+
+/// The maximum number of titles that can be requested in a single query.
+pub const MAX_TITLES: usize = 50;
+
+/// Parses a raw response, turning errors reported by the API into [`Error::Api`].
+pub fn parse_response(response: &str) -> Result<Value> {
+    let value: Value = serde_json::from_str(response)?;
+
+    if let Some(error) = value.get("error") {
+        let field = |name| {
+            error
+                .get(name)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_owned()
+        };
+        return Err(Error::Api {
+            code: field("code"),
+            info: field("info"),
+        });
+    }
+
+    Ok(value)
+}
+
+/// An iterator over the responses to a query, following continuations until the API has returned
+/// every result.
+pub struct Continuation<'a> {
+    source: &'a dyn Source,
+    params: Vec<(String, String)>,
+
+    /// The parameters to continue from, or `None` once the last response has been returned.
+    continue_params: Option<Vec<(String, String)>>,
+}
+
+impl<'a> Continuation<'a> {
+    /// Starts a query with the given parameters. The parameters should include `action` and
+    /// `format=json`, but not `continue`.
+    pub fn new(source: &'a dyn Source, params: &[(&str, &str)]) -> Continuation<'a> {
+        Continuation {
+            source,
+            params: params
+                .iter()
+                .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+            continue_params: Some(vec![(String::from("continue"), String::new())]),
+        }
+    }
+}
+
+impl Iterator for Continuation<'_> {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let continue_params = self.continue_params.take()?;
+
+        let params = self
+            .params
+            .iter()
+            .chain(&continue_params)
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect::<Vec<_>>();
+
+        let response = match self.source.query(&params).and_then(|r| parse_response(&r)) {
+            Ok(response) => response,
+            Err(err) => return Some(Err(err)),
+        };
+
+        // If there are more results to request, save the parameters for the next iteration.
+        self.continue_params = response
+            .get("continue")
+            .and_then(Value::as_object)
+            .map(|params| {
+                params
+                    .iter()
+                    .map(|(key, value)| {
+                        let value = match value {
+                            Value::String(value) => value.clone(),
+                            value => value.to_string(),
+                        };
+                        (key.clone(), value)
+                    })
+                    .collect()
+            });
+
+        Some(Ok(response))
+    }
+}
+
+/// A page in a category.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub struct CategoryMember {
+    /// The ID of the page.
+    #[serde(rename = "pageid")]
+    pub id: u64,
+
+    /// The title of the page.
+    pub title: String,
+}
+
+/// Fetches every page in a category, such as "Programming Tasks". The category may be given with
+/// or without the `Category:` prefix.
+pub fn category_members(source: &dyn Source, category: &str) -> Result<Vec<CategoryMember>> {
+    let cmtitle = if category.starts_with("Category:") {
+        category.to_owned()
+    } else {
+        format!("Category:{}", category)
+    };
+
+    let params = [
+        ("action", "query"),
+        ("format", "json"),
+        ("list", "categorymembers"),
+        ("cmtitle", &cmtitle),
+        ("cmlimit", "500"),
+    ];
+
+    let mut members = vec![];
+    for response in Continuation::new(source, &params) {
+        let response = response?;
+        let batch = response
+            .pointer("/query/categorymembers")
+            .ok_or_else(|| Error::UnexpectedFormat(format!("no members listed for {}", cmtitle)))?;
+        members.extend(Vec::<CategoryMember>::deserialize(batch)?);
+    }
+
+    Ok(members)
+}
+
+/// A mapping from a requested title to the title of the page that was actually returned.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Redirect {
+    /// The title that was requested.
+    pub from: String,
+
+    /// The title that the request was resolved to.
+    pub to: String,
+}
+
+/// A revision of a page.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Revision {
+    /// The ID of the revision.
+    #[serde(rename = "revid")]
+    pub id: Option<u64>,
+
+    /// The wikitext of the revision.
+    #[serde(rename = "*", default)]
+    pub content: String,
+}
+
+/// A page returned by a query.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Page {
+    /// The ID of the page. Missing pages don't have one.
+    #[serde(rename = "pageid")]
+    pub id: Option<u64>,
+
+    /// The title of the page.
+    pub title: String,
+
+    /// True if there is no page with the requested title.
+    #[serde(default, deserialize_with = "present")]
+    pub missing: bool,
+
+    /// The ID of the latest revision of the page, if page info was requested.
+    #[serde(rename = "lastrevid")]
+    pub last_revision_id: Option<u64>,
+
+    /// The revisions of the page, if revisions were requested.
+    #[serde(default)]
+    pub revisions: Vec<Revision>,
+}
+
+impl Page {
+    /// Returns the wikitext of the latest revision of the page, if it was requested.
+    pub fn content(&self) -> Option<&str> {
+        self.revisions
+            .first()
+            .map(|revision| revision.content.as_str())
+    }
+}
+
+/// The pages returned by a query, along with how the requested titles were resolved.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct Pages {
+    /// The pages, sorted by title.
+    #[serde(default, deserialize_with = "page_list")]
+    pub pages: Vec<Page>,
+
+    /// Titles that the wiki normalized before looking them up, such as by capitalizing them.
+    #[serde(default)]
+    pub normalized: Vec<Redirect>,
+
+    /// Titles that redirect to another page.
+    #[serde(default)]
+    pub redirects: Vec<Redirect>,
+}
+
+impl Pages {
+    /// Returns the title of the page that a requested title resolved to, after normalization and
+    /// redirects.
+    pub fn resolve<'a>(&'a self, title: &'a str) -> &'a str {
+        let follow = |redirects: &'a [Redirect], title: &'a str| {
+            redirects
+                .iter()
+                .find(|redirect| redirect.from == title)
+                .map_or(title, |redirect| redirect.to.as_str())
+        };
+
+        follow(&self.redirects, follow(&self.normalized, title))
+    }
+
+    /// Returns the page that a requested title resolved to.
+    pub fn get(&self, title: &str) -> Option<&Page> {
+        let title = self.resolve(title);
+        self.pages.iter().find(|page| page.title == title)
+    }
+
+    /// Merges the pages of another response into this one.
+    fn extend(&mut self, other: Pages) {
+        for page in other.pages {
+            match self.pages.iter_mut().find(|p| p.title == page.title) {
+                Some(existing) => existing.revisions.extend(page.revisions),
+                None => self.pages.push(page),
+            }
+        }
+        self.pages.sort_by(|a, b| a.title.cmp(&b.title));
+
+        self.normalized.extend(other.normalized);
+        self.redirects.extend(other.redirects);
+    }
+}
+
+/// Deserializes a flag that is indicated by the presence of a key, whatever its value.
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<bool, D::Error> {
+    IgnoredAny::deserialize(deserializer).map(|_| true)
+}
+
+/// Deserializes the map of pages in a response, keyed by page ID, into a list sorted by title.
+fn page_list<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<Page>, D::Error> {
+    let pages = serde_json::Map::deserialize(deserializer)?;
+
+    let mut pages = pages
+        .into_iter()
+        .map(|(_, page)| Page::deserialize(page))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(serde::de::Error::custom)?;
+    pages.sort_by(|a, b| a.title.cmp(&b.title));
+
+    Ok(pages)
+}
+
+/// Queries some property of the pages with the given titles, following redirects, in batches of
+/// [`MAX_TITLES`].
+fn query_pages(source: &dyn Source, titles: &[&str], params: &[(&str, &str)]) -> Result<Pages> {
+    let mut pages = Pages::default();
+
+    for batch in titles.chunks(MAX_TITLES) {
+        let titles = batch.join("|");
+        let mut params = params.to_vec();
+        params.extend(&[
+            ("action", "query"),
+            ("format", "json"),
+            ("redirects", "1"),
+            ("titles", &titles),
+        ]);
+
+        for response in Continuation::new(source, &params) {
+            if let Some(query) = response?.get("query") {
+                pages.extend(Pages::deserialize(query)?);
+            }
+        }
+    }
+
+    Ok(pages)
+}
+
+/// Fetches the content and ID of the latest revision of each page.
+pub fn revisions(source: &dyn Source, titles: &[&str]) -> Result<Pages> {
+    query_pages(
+        source,
+        titles,
+        &[("prop", "revisions"), ("rvprop", "ids|content")],
+    )
+}
+
+/// Fetches basic information about each page, such as whether it exists and the ID of its latest
+/// revision.
+pub fn info(source: &dyn Source, titles: &[&str]) -> Result<Pages> {
+    query_pages(source, titles, &[("prop", "info")])
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{info, revisions, MAX_TITLES};
+    use crate::{Error, Result, Source};
+
+    /// A wiki where "Foo" redirects to "Bar", "bar" is normalized to "Bar", and the revisions of
+    /// "Bar" are split across two responses.
+    struct FakeWiki;
+
+    impl Source for FakeWiki {
+        fn query(&self, params: &[(&str, &str)]) -> Result<String> {
+            let param = |name| params.iter().find(|(k, _)| *k == name).map(|(_, v)| *v);
+
+            let response = match (param("prop"), param("rvcontinue")) {
+                (Some("info"), _) => {
+                    let titles = param("titles").unwrap().split('|').count();
+                    assert!(titles <= MAX_TITLES);
+                    json!({ "query": { "pages": {
+                        "-1": { "ns": 0, "title": "Missing", "missing": "" },
+                        "2": { "pageid": 2, "ns": 0, "title": "Bar", "lastrevid": 20 },
+                    }}})
+                }
+                (Some("revisions"), None) => json!({
+                    "continue": { "rvcontinue": "2|19", "continue": "||" },
+                    "query": {
+                        "normalized": [{ "from": "bar", "to": "Bar" }],
+                        "redirects": [{ "from": "Foo", "to": "Bar" }],
+                        "pages": { "2": { "pageid": 2, "title": "Bar", "revisions": [
+                            { "revid": 20, "*": "new" },
+                        ]}},
+                    },
+                }),
+                (Some("revisions"), Some("2|19")) => json!({ "query": {
+                    "pages": { "2": { "pageid": 2, "title": "Bar", "revisions": [
+                        { "revid": 19, "*": "old" },
+                    ]}},
+                }}),
+                _ => json!({ "error": { "code": "badvalue", "info": "Unrecognized value" } }),
+            };
+
+            Ok(response.to_string())
+        }
+    }
+
+    #[test]
+    fn follows_continuations_and_redirects() {
+        let pages = revisions(&FakeWiki, &["Foo", "bar"]).unwrap();
+
+        assert_eq!(pages.resolve("Foo"), "Bar");
+        assert_eq!(pages.resolve("bar"), "Bar");
+
+        let page = pages.get("Foo").unwrap();
+        assert_eq!(page.id, Some(2));
+        assert_eq!(page.content(), Some("new"));
+        assert_eq!(
+            page.revisions.iter().map(|r| r.id).collect::<Vec<_>>(),
+            [Some(20), Some(19)]
+        );
+    }
+
+    #[test]
+    fn missing_pages_and_batches() {
+        let titles = vec!["Bar"; MAX_TITLES + 1];
+        let pages = info(&FakeWiki, &titles).unwrap();
+
+        assert!(pages.get("Missing").unwrap().missing);
+        assert!(!pages.get("Bar").unwrap().missing);
+        assert_eq!(pages.get("Bar").unwrap().last_revision_id, Some(20));
+    }
+
+    #[test]
+    fn api_errors() {
+        let err = super::Continuation::new(&FakeWiki, &[("action", "parse")])
+            .next()
+            .unwrap()
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Api { ref code, .. } if code == "badvalue"));
+    }
+}
@@ -0,0 +1,153 @@
+//! Recording and replaying API responses.
+//!
+//! A snapshot directory contains one JSON file per response, and an `index.json` file mapping the
+//! query string of each request to the file holding its response. Snapshots make it possible to
+//! run tools and tests without network access, and to get the same results every time.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use reqwest::Url;
+
+use crate::{Error, Result, Source, API_URL};
+
+/// The name of the file that maps queries to recorded responses in a snapshot directory.
+const INDEX_FILE: &str = "index.json";
+
+/// Serializes query parameters into a stable string that identifies the query.
+fn query_key(params: &[(&str, &str)]) -> String {
+    let mut url = Url::parse(API_URL).unwrap();
+    url.query_pairs_mut().extend_pairs(params);
+    url.query().unwrap_or_default().to_owned()
+}
+
+/// Replays responses from a directory recorded by a [`RecordingSource`]. Queries that were not
+/// recorded result in an error.
+#[derive(Debug, Clone)]
+pub struct SnapshotSource {
+    directory: PathBuf,
+    index: BTreeMap<String, String>,
+}
+
+impl SnapshotSource {
+    /// Opens a snapshot directory.
+    pub fn open<P: AsRef<Path>>(directory: P) -> Result<SnapshotSource> {
+        let directory = directory.as_ref().to_owned();
+        let index = serde_json::from_str(&fs::read_to_string(directory.join(INDEX_FILE))?)?;
+
+        Ok(SnapshotSource { directory, index })
+    }
+}
+
+impl Source for SnapshotSource {
+    fn query(&self, params: &[(&str, &str)]) -> Result<String> {
+        let key = query_key(params);
+        let file = self.index.get(&key).ok_or(Error::NotRecorded(key))?;
+
+        Ok(fs::read_to_string(self.directory.join(file))?)
+    }
+}
+
+/// Wraps another source, writing every response it returns into a snapshot directory that can
+/// later be replayed by a [`SnapshotSource`].
+#[derive(Debug)]
+pub struct RecordingSource<S> {
+    inner: S,
+    directory: PathBuf,
+    index: Mutex<BTreeMap<String, String>>,
+}
+
+impl<S: Source> RecordingSource<S> {
+    /// Creates a recording source that saves responses into the given directory. The directory is
+    /// created if it does not exist. Responses already recorded in the directory are kept.
+    pub fn new<P: AsRef<Path>>(inner: S, directory: P) -> Result<RecordingSource<S>> {
+        let directory = directory.as_ref().to_owned();
+        fs::create_dir_all(&directory)?;
+
+        let index = match fs::read_to_string(directory.join(INDEX_FILE)) {
+            Ok(index) => serde_json::from_str(&index)?,
+            Err(_) => BTreeMap::new(),
+        };
+
+        Ok(RecordingSource {
+            inner,
+            directory,
+            index: Mutex::new(index),
+        })
+    }
+}
+
+impl<S: Source> Source for RecordingSource<S> {
+    fn query(&self, params: &[(&str, &str)]) -> Result<String> {
+        let response = self.inner.query(params)?;
+
+        let mut index = self.index.lock().unwrap();
+        let next_file = format!("{:05}.json", index.len());
+        let file = index.entry(query_key(params)).or_insert(next_file);
+        fs::write(self.directory.join(file), &response)?;
+
+        // Rewrite the index after every response, so an interrupted recording is still usable.
+        fs::write(
+            self.directory.join(INDEX_FILE),
+            serde_json::to_string_pretty(&*index)?,
+        )?;
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::{RecordingSource, SnapshotSource};
+    use crate::{category_members, Error, Result, Source};
+
+    /// A source that serves two pages of category members.
+    struct FakeSource {
+        requests: AtomicUsize,
+    }
+
+    impl Source for FakeSource {
+        fn query(&self, params: &[(&str, &str)]) -> Result<String> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+
+            if params.contains(&("cmcontinue", "page|B")) {
+                Ok(r#"{"query":{"categorymembers":[{"pageid":2,"title":"B"}]}}"#.to_owned())
+            } else {
+                Ok(r#"{"continue":{"cmcontinue":"page|B","continue":"-||"},
+                       "query":{"categorymembers":[{"pageid":1,"title":"A"}]}}"#
+                    .to_owned())
+            }
+        }
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let directory = env::temp_dir().join(format!("mediawiki-snapshot-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+
+        let fake = FakeSource {
+            requests: AtomicUsize::new(0),
+        };
+        let recorder = RecordingSource::new(fake, &directory).unwrap();
+        let recorded = category_members(&recorder, "Programming Tasks").unwrap();
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(recorder.inner.requests.load(Ordering::SeqCst), 2);
+
+        let snapshot = SnapshotSource::open(&directory).unwrap();
+        let replayed = category_members(&snapshot, "Programming Tasks").unwrap();
+        assert_eq!(recorded, replayed);
+
+        assert!(matches!(
+            snapshot.query(&[("action", "parse")]),
+            Err(Error::NotRecorded(_))
+        ));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -8,24 +8,23 @@
 pub extern crate rand;
 
 use std::cmp;
-use std::collections::{HashSet, VecDeque};
-use std::fs::File;
-use std::io::prelude::*;
-use std::ops::Sub;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 
 use anyhow::Error;
 use lazy_static::lazy_static;
+use mediawiki::{Client, Source, Url};
 use regex::Regex;
-use reqwest::blocking::Client;
-use reqwest::Url;
 
-use local::LocalTask;
-use remote::{RemoteTask, Response};
+use local::{LocalSource, LocalTask};
+
+pub use remote::{decode_title, encode_title, CodeBlock, RemoteTask};
 
 pub mod local;
+pub mod normalize;
 mod remote;
 pub mod test_utils;
+pub mod workspace;
 
 /// Crate-specific error type.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -36,12 +35,73 @@ lazy_static! {
         Regex::new(r"^https?://rosettacode\.org/wiki/([^#]+)$").unwrap();
 }
 
+/// A local source file paired with the remote code block that most closely resembles it.
+#[derive(Debug, Clone)]
+pub struct SourceMatch {
+    /// The path to the local source file.
+    pub path: PathBuf,
+
+    /// The best-matching code block on the wiki, if any block shares code with the file.
+    pub block: Option<CodeBlock>,
+
+    /// How similar the file is to the block, from `0.0` (nothing in common) to `1.0`, as computed
+    /// by [`normalize::similarity`].
+    pub similarity: f64,
+}
+
+/// Returns true if any of the headings of a block mention the name of a source file, such as a
+/// `===Triples===` heading for `src/bin/triples.rs`.
+fn headings_mention(block: &CodeBlock, path: &Path) -> bool {
+    let stem = match path.file_stem().and_then(|stem| stem.to_str()) {
+        Some("main") | Some("lib") | None => return false,
+        Some(stem) => stem.to_lowercase().replace(['_', '-'], " "),
+    };
+
+    block
+        .headings
+        .iter()
+        .any(|heading| heading.to_lowercase().contains(&stem))
+}
+
+/// The status of a task title on the Rosetta Code wiki.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskStatus {
+    /// A task in the category of programming tasks.
+    Task,
+
+    /// A draft task, or a page that exists on the wiki but isn't categorized as a task.
+    Draft,
+
+    /// No page with the title exists on the wiki.
+    Missing,
+
+    /// The title redirects to another page, probably because the task was renamed.
+    Renamed {
+        /// The title of the page that the title redirects to.
+        to: String,
+    },
+}
+
+/// A local task whose title doesn't match a task on the wiki.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The name of the package implementing the task.
+    pub package_name: String,
+
+    /// The title of the task, as parsed from the crate metadata.
+    pub title: String,
+
+    /// Either [`TaskStatus::Missing`], or [`TaskStatus::Renamed`] with a suggested title.
+    pub status: TaskStatus,
+}
+
 /// A representation of a Rosetta Code task. Contains information about the implementation on both
 /// the local repository and the wiki.
 #[derive(Debug, Clone)]
 pub struct Task {
     local: Option<LocalTask>,
     remote: RemoteTask,
+    status: TaskStatus,
 }
 
 impl Task {
@@ -51,18 +111,21 @@ impl Task {
     }
 
     /// Returns the implementation of the task in the local repository, if it exists.
+    ///
+    /// If the task has multiple source files, their contents are concatenated in the order given
+    /// by [`local::LocalSource::files`].
     pub fn local_code(&self) -> Option<String> {
-        self.local.clone().map(|task| {
-            // FIXME: Too simple for the multiple source file case.
-            let mut code = String::new();
+        self.local.as_ref().map(|task| task.source.code().unwrap())
+    }
 
-            for source in &task.source {
-                let mut file = File::open(source).unwrap();
-                file.read_to_string(&mut code).unwrap();
-            }
+    /// Returns the implementation of the task in the local repository, if it exists.
+    pub fn local(&self) -> Option<&LocalTask> {
+        self.local.as_ref()
+    }
 
-            code
-        })
+    /// Returns the source files of the task in the local repository, if it exists.
+    pub fn local_source(&self) -> Option<&LocalSource> {
+        self.local.as_ref().map(|task| &task.source)
     }
 
     /// Returns the implementation of the task on the wiki, if it exists.
@@ -70,6 +133,62 @@ impl Task {
         self.remote.source()
     }
 
+    /// Returns every Rust code block of the task on the wiki, in the order they appear on the page.
+    pub fn remote_code_blocks(&self) -> &[CodeBlock] {
+        self.remote.blocks()
+    }
+
+    /// Pairs each local source file of the task with the remote code block that most closely
+    /// resembles it. Files are returned in the same order as [`local::LocalSource::files`]. If the task is not implemented locally,
+    /// the list is empty.
+    pub fn matched_sources(&self) -> Result<Vec<SourceMatch>> {
+        let local = match self.local {
+            Some(ref local) => local,
+            None => return Ok(vec![]),
+        };
+
+        local
+            .source
+            .files()
+            .iter()
+            .map(|file| {
+                let path = &file.path;
+                let code = std::fs::read_to_string(path)?;
+
+                // Prefer the most similar block. Between equally similar blocks, prefer one whose
+                // headings mention the file, and then the one that appears first on the page.
+                let best = self
+                    .remote
+                    .blocks()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, block)| {
+                        let score = normalize::similarity(&code, &block.code);
+                        (score, headings_mention(block, path), cmp::Reverse(i), block)
+                    })
+                    .max_by(|a, b| {
+                        a.0.partial_cmp(&b.0)
+                            .unwrap()
+                            .then(a.1.cmp(&b.1))
+                            .then(a.2.cmp(&b.2))
+                    });
+
+                let (block, similarity) = match best {
+                    Some((score, mentioned, _, block)) if score > 0.0 || mentioned => {
+                        (Some(block.clone()), score)
+                    }
+                    _ => (None, 0.0),
+                };
+
+                Ok(SourceMatch {
+                    path: path.clone(),
+                    block,
+                    similarity,
+                })
+            })
+            .collect()
+    }
+
     /// True if and only if the task is only implemented on the Rosetta Code wiki.
     pub fn is_remote_only(&self) -> bool {
         self.local.is_none() && self.remote.source().is_some()
@@ -86,6 +205,17 @@ impl Task {
         self.local.is_none() && self.remote.source().is_none()
     }
 
+    /// Returns the status of the task on the Rosetta Code wiki. This is always either
+    /// [`TaskStatus::Task`] or [`TaskStatus::Draft`].
+    pub fn status(&self) -> &TaskStatus {
+        &self.status
+    }
+
+    /// True if and only if the task is a draft task on the Rosetta Code wiki.
+    pub fn is_draft(&self) -> bool {
+        self.status == TaskStatus::Draft
+    }
+
     /// Returns the URL of the task on the Rosetta Code wiki.
     pub fn url(&self) -> Url {
         self.remote.url()
@@ -102,101 +232,166 @@ impl Task {
 
 /// The index of all tasks implemented locally and remotely.
 pub struct TaskIndex {
-    all_task_titles: HashSet<String>,
+    /// The status of every task on the wiki, keyed by title.
+    statuses: HashMap<String, TaskStatus>,
+
+    /// Maps the titles of local tasks that were renamed on the wiki to their new titles.
+    renames: HashMap<String, String>,
+
+    mismatches: Vec<Mismatch>,
     local_tasks: Vec<LocalTask>,
-    client: Client,
+    source: Box<dyn Source>,
 }
 
 impl TaskIndex {
     /// Generate the task index by parsing tasks in the repository and requesting task titles from
     /// the wiki.
     pub fn create<P: AsRef<Path>>(workspace_root: P) -> Result<TaskIndex> {
-        let local_tasks = local::parse_tasks(workspace_root.as_ref().join("Cargo.toml")).unwrap();
-        let local_task_titles = local_tasks.iter().map(|task| task.title.clone()).collect();
+        TaskIndex::with_source(workspace_root, Box::new(Client::default()))
+    }
 
-        let client = Client::new();
-        let all_task_titles = TaskIndex::all_task_titles(&client, &local_task_titles)?;
+    /// Generate the task index like [`TaskIndex::create`], but retrieve wiki data from the given
+    /// source instead of the live wiki.
+    pub fn with_source<P: AsRef<Path>>(
+        workspace_root: P,
+        source: Box<dyn Source>,
+    ) -> Result<TaskIndex> {
+        let local_tasks = local::parse_tasks(workspace_root.as_ref().join("Cargo.toml")).unwrap();
 
-        Ok(TaskIndex {
-            all_task_titles,
+        let mut task_index = TaskIndex {
+            statuses: HashMap::new(),
+            renames: HashMap::new(),
+            mismatches: vec![],
             local_tasks,
-            client,
-        })
+            source,
+        };
+        task_index.classify_titles()?;
+
+        Ok(task_index)
+    }
+
+    /// Returns the local tasks that couldn't be matched to a task on the wiki, sorted by package
+    /// name.
+    ///
+    /// Renamed tasks are still included when iterating over tasks, under their new title.
+    pub fn mismatches(&self) -> &[Mismatch] {
+        &self.mismatches
     }
 
     /// Retrieves data for every task on Rosetta Code.
-    pub fn fetch_all_tasks(&self) -> TaskIterator {
+    pub fn fetch_all_tasks(&self) -> TaskIterator<'_> {
         Self::fetch_tasks(self, &[])
     }
 
     /// Parses both local (implemented in this repository) and remote (implemented on the wiki)
     /// tasks, and returns the code of each.
-    pub fn fetch_tasks(&self, tasks: &[String]) -> TaskIterator {
+    pub fn fetch_tasks(&self, tasks: &[String]) -> TaskIterator<'_> {
         TaskIterator::new(self, tasks)
     }
 
-    fn all_task_titles(
-        client: &Client,
-        local_task_titles: &HashSet<String>,
-    ) -> Result<HashSet<String>> {
-        let mut all_task_titles = find_unimplemented_tasks::all_tasks()
-            .into_iter()
-            .map(|task| task.title)
-            .collect::<HashSet<_>>();
+    /// Returns the titles of every page in a category on the wiki, such as "Sorting Algorithms".
+    pub fn category_members(&self, category: &str) -> Result<Vec<String>> {
+        let members = mediawiki::category_members(&*self.source, category)?;
+        Ok(members.into_iter().map(|member| member.title).collect())
+    }
 
-        let all_decoded_task_titles = all_task_titles
-            .iter()
+    /// Determines the status of every task on the wiki, and of every local task.
+    ///
+    /// Local tasks that aren't listed in either category of tasks are looked up individually.
+    /// They might be pages that aren't categorized yet, or they might have been renamed or
+    /// deleted.
+    fn classify_titles(&mut self) -> Result<()> {
+        for (category, status) in &[
+            ("Programming Tasks", TaskStatus::Task),
+            ("Draft Programming Tasks", TaskStatus::Draft),
+        ] {
+            for member in mediawiki::category_members(&*self.source, category)? {
+                self.statuses
+                    .entry(member.title)
+                    .or_insert_with(|| status.clone());
+            }
+        }
+
+        let known_titles = self
+            .statuses
+            .keys()
             .map(|title| remote::decode_title(title))
             .collect::<HashSet<_>>();
 
-        if !local_task_titles.is_subset(&all_decoded_task_titles) {
-            // If there are tasks that can't be matched on the wiki, it's possible that they are
-            // just draft tasks at the moment. Perform a query to see if we get any missing pages.
-            let mut possible_draft_titles = (local_task_titles.sub(&all_decoded_task_titles))
-                .into_iter()
-                .collect::<Vec<_>>();
-            possible_draft_titles.sort();
-
-            // FIXME: We might have to loop here if we get too many draft tasks.
-            let mut request = Url::parse("http://rosettacode.org/mw/api.php").unwrap();
-            request
-                .query_pairs_mut()
-                .append_pair("action", "query")
-                .append_pair("format", "json")
-                .append_pair("prop", "info")
-                .append_pair("titles", &possible_draft_titles.join("|"));
-
-            let response: Response = client.get(request.as_str()).send()?.json()?;
-
-            // In the response, any missing pages will have a negative ID.
-            let bad_titles = response
-                .query
-                .pages
-                .into_iter()
-                .flat_map(|(id, page)| if id < 0 { Some(page.title) } else { None })
+        let mut unknown_tasks = self
+            .local_tasks
+            .iter()
+            .filter(|task| !known_titles.contains(&task.title))
+            .map(|task| (task.title.clone(), task.package_name.clone()))
+            .collect::<Vec<_>>();
+        unknown_tasks.sort();
+
+        if !unknown_tasks.is_empty() {
+            let titles = unknown_tasks
+                .iter()
+                .map(|(title, _)| title.as_str())
                 .collect::<Vec<_>>();
-
-            if !bad_titles.is_empty() {
-                panic!(
-                    "Could not match some local tasks to tasks on the wiki: {:?}",
-                    bad_titles
-                );
+            let pages = mediawiki::info(&*self.source, &titles)?;
+
+            for (title, package_name) in &unknown_tasks {
+                let resolved = pages.resolve(title);
+                let missing = pages.get(title).is_none_or(|page| page.missing);
+
+                let status = if missing {
+                    TaskStatus::Missing
+                } else if resolved != title {
+                    TaskStatus::Renamed {
+                        to: resolved.to_owned(),
+                    }
+                } else {
+                    TaskStatus::Draft
+                };
+
+                match status {
+                    TaskStatus::Draft => {
+                        self.statuses.insert(title.clone(), TaskStatus::Draft);
+                    }
+                    TaskStatus::Renamed { ref to } => {
+                        self.statuses.entry(to.clone()).or_insert(TaskStatus::Draft);
+                        self.renames.insert(title.clone(), to.clone());
+                    }
+                    _ => {}
+                }
+
+                if status != TaskStatus::Draft {
+                    self.mismatches.push(Mismatch {
+                        package_name: package_name.clone(),
+                        title: title.clone(),
+                        status,
+                    });
+                }
             }
-
-            all_task_titles.extend(possible_draft_titles);
         }
 
-        Ok(all_task_titles)
+        self.mismatches
+            .sort_by(|a, b| a.package_name.cmp(&b.package_name));
+
+        Ok(())
     }
 }
 
+/// Fetches a single task from the wiki, following redirects. Returns `None` if the wiki has no page
+/// with the given title.
+pub fn fetch_remote_task(source: &dyn Source, title: &str) -> Result<Option<RemoteTask>> {
+    let pages = mediawiki::revisions(source, &[title])?;
+
+    Ok(remote::parse_tasks(&pages).into_iter().next())
+}
+
 /// An iterator over tasks. Parses information from both the rust-rosetta repository and the
 /// Rosetta Code wiki.
 pub struct TaskIterator<'a> {
-    client: &'a Client,
+    source: &'a dyn Source,
     fetched_remote_tasks: VecDeque<RemoteTask>,
     requested_task_titles: VecDeque<String>,
     local_tasks: Vec<LocalTask>,
+    statuses: &'a HashMap<String, TaskStatus>,
+    renames: &'a HashMap<String, String>,
 }
 
 impl<'a> TaskIterator<'a> {
@@ -204,41 +399,40 @@ impl<'a> TaskIterator<'a> {
     /// all tasks.
     fn new(task_index: &'a TaskIndex, titles: &[String]) -> TaskIterator<'a> {
         let mut requested_task_titles: Vec<_> = if titles.is_empty() {
-            task_index.all_task_titles.iter().cloned().collect()
+            task_index.statuses.keys().cloned().collect()
         } else {
-            task_index
-                .all_task_titles
-                .intersection(&titles.iter().cloned().collect::<HashSet<_>>())
+            titles
+                .iter()
+                .filter(|title| task_index.statuses.contains_key(*title))
                 .cloned()
+                .collect::<HashSet<_>>()
+                .into_iter()
                 .collect()
         };
 
         requested_task_titles.sort();
 
         TaskIterator {
-            client: &task_index.client,
+            source: &*task_index.source,
             fetched_remote_tasks: Default::default(),
             requested_task_titles: requested_task_titles.into_iter().collect(),
             local_tasks: task_index.local_tasks.clone(),
+            statuses: &task_index.statuses,
+            renames: &task_index.renames,
         }
     }
 
     /// Fetch the next set of remote tasks.
     fn fetch_task_batch(&mut self) -> Result<()> {
-        let end = cmp::min(self.requested_task_titles.len(), 50);
-        let next_batch_titles = self.requested_task_titles.drain(..end).collect::<Vec<_>>();
-        let mut request = Url::parse("http://rosettacode.org/mw/api.php").unwrap();
-        request
-            .query_pairs_mut()
-            .append_pair("action", "query")
-            .append_pair("format", "json")
-            .append_pair("prop", "revisions")
-            .append_pair("rvprop", "content")
-            .append_pair("titles", &next_batch_titles.join("|"));
-
-        let response: Response = self.client.get(request.as_str()).send()?.json()?;
-
-        let mut remote_tasks = remote::parse_tasks(&response);
+        let end = cmp::min(
+            self.requested_task_titles.len(),
+            mediawiki::query::MAX_TITLES,
+        );
+        let titles = self.requested_task_titles.drain(..end).collect::<Vec<_>>();
+        let titles = titles.iter().map(String::as_str).collect::<Vec<_>>();
+        let pages = mediawiki::revisions(self.source, &titles)?;
+
+        let mut remote_tasks = remote::parse_tasks(&pages);
         remote_tasks.sort_by_key(|task| task.title());
         self.fetched_remote_tasks = remote_tasks.into_iter().collect();
 
@@ -261,13 +455,19 @@ impl<'a> Iterator for TaskIterator<'a> {
         }
 
         let remote_task = self.fetched_remote_tasks.pop_front().unwrap();
+        let title = remote_task.title();
         let local_task = self
             .local_tasks
             .iter()
-            .cloned()
-            .find(|task| task.title == remote_task.title());
+            .find(|task| task.title == title || self.renames.get(&task.title) == Some(&title))
+            .cloned();
 
         let task = Task {
+            status: self
+                .statuses
+                .get(&title)
+                .cloned()
+                .unwrap_or(TaskStatus::Task),
             local: local_task,
             remote: remote_task,
         };
@@ -275,3 +475,21 @@ impl<'a> Iterator for TaskIterator<'a> {
         Some(Ok(task))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{headings_mention, CodeBlock};
+
+    #[test]
+    fn headings_mention_file() {
+        let block = CodeBlock {
+            headings: vec!["Pythagorean triples".to_owned()],
+            code: String::new(),
+        };
+
+        assert!(headings_mention(&block, Path::new("src/bin/triples.rs")));
+        assert!(!headings_mention(&block, Path::new("src/main.rs")));
+    }
+}
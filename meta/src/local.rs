@@ -1,12 +1,15 @@
 //! Utilities for interacting with tasks implemented in the rust-rosetta repository.
 
-use std::collections::HashSet;
+use std::collections::HashMap;
+use std::fmt::Write;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Context, Error};
-use reqwest::Url;
+use mediawiki::Url;
 use toml::Value;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
 use walkdir::WalkDir;
 
 use crate::remote;
@@ -21,8 +24,8 @@ pub struct LocalTask {
     /// The path to the crate manifest for the task.
     pub manifest_path: PathBuf,
 
-    /// A set of filenames containing the Rust source code of the tasks.
-    pub source: HashSet<PathBuf>,
+    /// The Rust source code of the task.
+    pub source: LocalSource,
 
     /// The URL of the task on the Rosetta Code wiki.
     ///
@@ -35,10 +38,175 @@ pub struct LocalTask {
     pub title: String,
 }
 
+/// The role of a source file within a task crate.
+///
+/// The variants are declared in the order that files should be presented to a reader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SourceKind {
+    /// The root of the library target.
+    Lib,
+
+    /// The root of the binary target at `src/main.rs`.
+    Main,
+
+    /// The root of any other binary target, such as `src/bin/*.rs`.
+    Bin,
+
+    /// A module that is not the root of any target.
+    Module,
+
+    /// The root of an example target.
+    Example,
+
+    /// The root of an integration test target.
+    Test,
+
+    /// The root of a benchmark target.
+    Bench,
+
+    /// A build script.
+    Build,
+}
+
+impl SourceKind {
+    /// Determines the kind of a target root from the target kinds reported by cargo.
+    fn from_target(target: &cargo_metadata::Target) -> SourceKind {
+        let is = |kind: &str| target.kind.iter().any(|k| k == kind);
+
+        if is("bin") {
+            if target.src_path.ends_with("src/main.rs") {
+                SourceKind::Main
+            } else {
+                SourceKind::Bin
+            }
+        } else if is("example") {
+            SourceKind::Example
+        } else if is("test") {
+            SourceKind::Test
+        } else if is("bench") {
+            SourceKind::Bench
+        } else if is("custom-build") {
+            SourceKind::Build
+        } else {
+            SourceKind::Lib
+        }
+    }
+}
+
+/// A single Rust source file of a task crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    /// The absolute path to the file.
+    pub path: PathBuf,
+
+    /// The path to the file relative to the crate root, such as `src/main.rs`.
+    pub relative_path: PathBuf,
+
+    /// The role of the file in the crate.
+    pub kind: SourceKind,
+
+    /// The name of the target that the file is the root of, if any.
+    pub target: Option<String>,
+}
+
+/// The Rust source files of a task crate, in a stable order: library first, then the main binary,
+/// other binaries, modules, examples, tests and benchmarks. Files of the same kind are sorted by
+/// path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalSource {
+    files: Vec<SourceFile>,
+}
+
+impl LocalSource {
+    /// Collects the source files of a package, classifying them by the package's targets.
+    fn from_package(package: &cargo_metadata::Package) -> Result<LocalSource, Error> {
+        let crate_root = package.manifest_path.parent().unwrap().as_std_path();
+
+        let targets = package
+            .targets
+            .iter()
+            .map(|target| (target.src_path.as_std_path(), target))
+            .collect::<HashMap<_, _>>();
+
+        let mut files = vec![];
+
+        // Don't descend into build output or the directories of other crates nested inside this
+        // one (such as `tasks/bitmap/read-a-ppm-file` inside `tasks/bitmap`).
+        let walker = WalkDir::new(crate_root).into_iter().filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || (entry.file_name() != "target" && !entry.path().join("Cargo.toml").exists())
+        });
+
+        for entry in walker {
+            let entry = entry?;
+            let path = entry.path();
+
+            if let Some("rs") = path.extension().and_then(|s| s.to_str()) {
+                let target = targets.get(path);
+
+                files.push(SourceFile {
+                    path: path.to_owned(),
+                    relative_path: path.strip_prefix(crate_root)?.to_owned(),
+                    kind: target.map_or(SourceKind::Module, |t| SourceKind::from_target(t)),
+                    target: target.map(|t| t.name.clone()),
+                });
+            }
+        }
+
+        files.sort_by(|a, b| (a.kind, &a.path).cmp(&(b.kind, &b.path)));
+
+        Ok(LocalSource { files })
+    }
+
+    /// Returns the source files in order.
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// Returns the files of a given kind.
+    pub fn files_of_kind(&self, kind: SourceKind) -> impl Iterator<Item = &SourceFile> {
+        self.files.iter().filter(move |file| file.kind == kind)
+    }
+
+    /// Concatenates the contents of every source file, in order.
+    pub fn code(&self) -> Result<String, Error> {
+        let mut code = String::new();
+
+        for file in &self.files {
+            code.push_str(&fs::read_to_string(&file.path)?);
+        }
+
+        Ok(code)
+    }
+
+    /// Renders the source files as MediaWiki markup, ready to be placed in the Rust section of a
+    /// task.
+    ///
+    /// A crate with a single file is rendered as a single code block. Otherwise, each file gets a
+    /// heading with its path relative to the crate root, followed by its code.
+    pub fn to_wiki(&self) -> Result<String, Error> {
+        let mut wiki = String::new();
+
+        for file in &self.files {
+            if self.files.len() > 1 {
+                writeln!(wiki, "==={}===", file.relative_path.display()).unwrap();
+            }
+
+            let code = fs::read_to_string(&file.path)?;
+            writeln!(wiki, "<lang rust>{}</lang>", code.trim_end()).unwrap();
+        }
+
+        Ok(wiki)
+    }
+}
+
 /// Check if the target of a package is of kind dylib or proc-macro.
 fn is_dylib_or_proc_macro(target: &cargo_metadata::Target) -> bool {
-    target.kind.contains(&String::from("dylib"))
-        || target.kind.contains(&String::from("proc-macro"))
+    target
+        .kind
+        .iter()
+        .any(|kind| kind == "dylib" || kind == "proc-macro")
 }
 
 /// Given a path to the root `Cargo.toml`, returns a list of tasks implemented in the rust-rosetta
@@ -47,56 +215,113 @@ pub fn parse_tasks<P>(manifest_path: P) -> Result<Vec<LocalTask>, Error>
 where
     P: AsRef<Path>,
 {
-    let metadata = cargo_metadata::metadata(Some(manifest_path.as_ref())).unwrap();
-    let packages = &metadata.packages;
+    try_parse_tasks(manifest_path)?
+        .into_iter()
+        .map(|(_, task)| task)
+        .collect()
+}
+
+/// The path to the manifest of a task crate, along with the result of parsing it.
+pub type ParsedTask = (PathBuf, Result<LocalTask, Error>);
+
+/// Like [`parse_tasks`], but doesn't stop at the first task crate that can't be parsed.
+pub fn try_parse_tasks<P>(manifest_path: P) -> Result<Vec<ParsedTask>, Error>
+where
+    P: AsRef<Path>,
+{
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .manifest_path(manifest_path.as_ref())
+        .no_deps()
+        .exec()?;
 
     let mut tasks = vec![];
 
-    for member in &metadata.workspace_members {
+    for package in metadata.workspace_packages() {
         // Skip if we encounter known non-task crates.
-        if member.name() == "rust-rosetta" || member.name() == "meta" {
+        if ["rust-rosetta", "meta", "mediawiki", "sorting-algorithms"]
+            .contains(&package.name.as_str())
+        {
             continue;
         }
 
-        let package = packages.iter().find(|p| p.name == member.name()).unwrap();
-
         // If the package has a proc-macro or dylib target, it's probably just a dependency of
         // another task. Skip it.
         if package.targets.iter().any(is_dylib_or_proc_macro) {
             continue;
         }
 
-        let manifest_path = Path::new(&package.manifest_path);
-
-        let rosetta_url = parse_rosetta_url(manifest_path).context(format!(
-            "could not parse rosetta code URL from {}",
-            manifest_path.display()
-        ))?;
-
-        let title = {
-            let caps = TASK_URL_RE.captures(rosetta_url.as_str()).ok_or_else(|| {
-                anyhow!(
-                    "task URL does not match rosetta code regex: {}",
-                    rosetta_url
-                )
-            })?;
-            remote::decode_title(&caps[1])
-        };
-
-        tasks.push(LocalTask {
-            package_name: member.name().to_owned(),
-            manifest_path: manifest_path.to_owned(),
-            source: find_sources(manifest_path.parent().unwrap())?,
-            url: rosetta_url,
-            title,
-        });
+        let manifest_path = package.manifest_path.as_std_path();
+        tasks.push((manifest_path.to_owned(), parse_task(package)));
     }
 
     Ok(tasks)
 }
 
+/// Parses a single task crate.
+fn parse_task(package: &cargo_metadata::Package) -> Result<LocalTask, Error> {
+    let manifest_path = package.manifest_path.as_std_path();
+
+    let rosetta_url = parse_rosetta_url(manifest_path).context(format!(
+        "could not parse rosetta code URL from {}",
+        manifest_path.display()
+    ))?;
+
+    Ok(LocalTask {
+        package_name: package.name.clone(),
+        manifest_path: manifest_path.to_owned(),
+        source: LocalSource::from_package(package)?,
+        title: task_title(&rosetta_url)?,
+        url: rosetta_url,
+    })
+}
+
+/// Returns the normalized title of the task at a Rosetta Code URL.
+pub fn task_title(url: &Url) -> Result<String, Error> {
+    let caps = TASK_URL_RE
+        .captures(url.as_str())
+        .ok_or_else(|| anyhow!("task URL does not match rosetta code regex: {}", url))?;
+
+    Ok(remote::decode_title(&caps[1]))
+}
+
+/// Returns the conventional directory for a task, relative to the `tasks` directory.
+///
+/// Each part of the title (separated by `/`) becomes a directory. The words of each part are
+/// lowercased and joined with hyphens, `+` is spelled out as `plus`, accents are removed, and
+/// names starting with a digit are prefixed with an underscore, since crate names can't start
+/// with a digit. For example, "Hello world/Text" becomes `hello-world/text`, "Vigenère cipher"
+/// becomes `vigenere-cipher`, and "100 doors" becomes `_100-doors`.
+pub fn task_directory(title: &str) -> String {
+    title
+        .split('/')
+        .map(|part| {
+            // Decomposing splits accented letters into a base letter and combining marks, and the
+            // marks are dropped. Anything else that isn't ASCII separates words.
+            let part = part
+                .to_lowercase()
+                .replace('+', " plus ")
+                .replace('\'', "")
+                .nfd()
+                .filter(|&c| !is_combining_mark(c))
+                .collect::<String>();
+            let name = part
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+                .join("-");
+
+            if name.starts_with(|c: char| c.is_ascii_digit()) {
+                format!("_{}", name)
+            } else {
+                name
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
 /// Parses the Rosetta Code URL from the package metadata in a task's crate manifest.
-fn parse_rosetta_url<P>(manifest_path: P) -> Result<Url, Error>
+pub fn parse_rosetta_url<P>(manifest_path: P) -> Result<Url, Error>
 where
     P: AsRef<Path>,
 {
@@ -113,20 +338,18 @@ where
     Ok(Url::parse(url)?)
 }
 
-/// Collect the paths to the source files of a given package.
-fn find_sources<P>(directory: P) -> Result<HashSet<PathBuf>, Error>
-where
-    P: AsRef<Path>,
-{
-    let mut sources = HashSet::new();
-
-    for entry in WalkDir::new(directory) {
-        let entry = entry?;
+#[cfg(test)]
+mod tests {
+    use super::task_directory;
 
-        if let Some("rs") = entry.path().extension().and_then(|s| s.to_str()) {
-            sources.insert(entry.path().to_owned());
-        }
+    #[test]
+    fn directories() {
+        assert_eq!(task_directory("FizzBuzz"), "fizzbuzz");
+        assert_eq!(task_directory("Hello world/Text"), "hello-world/text");
+        assert_eq!(task_directory("100 doors"), "_100-doors");
+        assert_eq!(task_directory("A+B"), "a-plus-b");
+        assert_eq!(task_directory("Penney's game"), "penneys-game");
+        assert_eq!(task_directory("Abbreviations, easy"), "abbreviations-easy");
+        assert_eq!(task_directory("Vigenère cipher"), "vigenere-cipher");
     }
-
-    Ok(sources)
 }
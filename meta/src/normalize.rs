@@ -0,0 +1,226 @@
+//! Normalization of Rust code, so that two versions of a task can be compared without being
+//! distracted by formatting.
+//!
+//! Code that parses as a Rust file is pretty-printed in a canonical style, with each run of
+//! consecutive `use` items sorted. Comments are dropped, since they don't affect what the code
+//! does. Code that doesn't parse (for example, a snippet on the wiki that isn't a full program) is
+//! normalized line by line instead.
+
+use std::cmp;
+use std::str::FromStr;
+
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::visit_mut::{self, VisitMut};
+use syn::{Item, Macro};
+
+/// Returns a canonical form of some Rust code.
+///
+/// Two pieces of code that differ only in whitespace, comments, trailing commas or the order of
+/// their imports normalize to the same string.
+pub fn normalize(code: &str) -> String {
+    match syn::parse_file(code) {
+        Ok(mut file) => {
+            sort_imports(&mut file.items);
+            MacroCommas.visit_file_mut(&mut file);
+            prettyplease::unparse(&file)
+        }
+        Err(_) => normalize_lines(code),
+    }
+}
+
+/// Sorts each run of consecutive `use` items, recursing into inline modules.
+fn sort_imports(items: &mut [Item]) {
+    for item in items.iter_mut() {
+        if let Item::Mod(module) = item {
+            if let Some((_, ref mut items)) = module.content {
+                sort_imports(items);
+            }
+        }
+    }
+
+    for run in items.split_mut(|item| !matches!(item, Item::Use(_))) {
+        run.sort_by_cached_key(|item| item.to_token_stream().to_string());
+    }
+}
+
+/// Removes trailing commas from macro invocations, such as `vec![1, 2, 3,]`.
+///
+/// The pretty-printer removes trailing commas everywhere else, but it can't know whether a comma
+/// is significant to a macro, so it leaves macro arguments alone. The trailing comma of a
+/// parenthesized group with a single element is kept, since it makes `(x,)` a tuple rather than
+/// `x` in parentheses.
+struct MacroCommas;
+
+impl VisitMut for MacroCommas {
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        mac.tokens = strip_trailing_commas(mac.tokens.clone(), true);
+        visit_mut::visit_macro_mut(self, mac);
+    }
+}
+
+/// Strips the trailing comma of a token stream if `strip` is true, and of the groups inside it
+/// that are lists.
+fn strip_trailing_commas(stream: TokenStream, strip: bool) -> TokenStream {
+    let mut trees = stream
+        .into_iter()
+        .map(|tree| match tree {
+            TokenTree::Group(group) => {
+                let is_list = group.delimiter() != Delimiter::Parenthesis
+                    || group
+                        .stream()
+                        .into_iter()
+                        .filter(|tree| is_comma(Some(tree)))
+                        .count()
+                        > 1;
+                let mut stripped = Group::new(
+                    group.delimiter(),
+                    strip_trailing_commas(group.stream(), is_list),
+                );
+                stripped.set_span(group.span());
+                TokenTree::Group(stripped)
+            }
+            tree => tree,
+        })
+        .collect::<Vec<_>>();
+
+    if strip && is_comma(trees.last()) {
+        trees.pop();
+    }
+
+    trees.into_iter().collect()
+}
+
+fn is_comma(tree: Option<&TokenTree>) -> bool {
+    matches!(tree, Some(TokenTree::Punct(punct)) if punct.as_char() == ',')
+}
+
+/// Fallback normalization for code that can't be parsed: trims each line and drops blank lines
+/// and line comments.
+fn normalize_lines(code: &str) -> String {
+    code.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+/// Splits code into a flat list of tokens. Falls back to splitting on whitespace if the code
+/// can't be tokenized, such as when it has unbalanced delimiters.
+fn tokens(code: &str) -> Vec<String> {
+    fn flatten(stream: TokenStream, tokens: &mut Vec<String>) {
+        for tree in stream {
+            match tree {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::None => ("", ""),
+                    };
+                    tokens.push(open.to_owned());
+                    flatten(group.stream(), tokens);
+                    tokens.push(close.to_owned());
+                }
+                tree => tokens.push(tree.to_string()),
+            }
+        }
+    }
+
+    match TokenStream::from_str(code) {
+        Ok(stream) => {
+            let mut tokens = vec![];
+            flatten(stream, &mut tokens);
+            tokens.retain(|token| !token.is_empty());
+            tokens
+        }
+        Err(_) => code.split_whitespace().map(String::from).collect(),
+    }
+}
+
+/// Computes the length of the longest common subsequence of two sequences.
+fn longest_common_subsequence<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    let mut previous = vec![0; b.len() + 1];
+    let mut current = vec![0; b.len() + 1];
+
+    for x in a {
+        for (j, y) in b.iter().enumerate() {
+            current[j + 1] = if x == y {
+                previous[j] + 1
+            } else {
+                cmp::max(previous[j + 1], current[j])
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Computes how similar two pieces of code are after normalization, from `0.0` (nothing in
+/// common) to `1.0` (equivalent).
+///
+/// The score is the proportion of tokens that the normalized code has in common, in order.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a = tokens(&normalize(a));
+    let b = tokens(&normalize(b));
+
+    if a.is_empty() && b.is_empty() {
+        return 0.0;
+    }
+
+    2.0 * longest_common_subsequence(&a, &b) as f64 / (a.len() + b.len()) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize, similarity};
+
+    #[test]
+    fn formatting_is_ignored() {
+        let a = "use std::io;\nuse std::fmt;\n\nfn main() {\n    let v = vec![1, 2, 3,];\n}\n";
+        let b = "use std::fmt; use std::io;\n// A comment.\nfn main() { let v = vec![1, 2, 3]; }";
+
+        assert_eq!(normalize(a), normalize(b));
+        assert_eq!(similarity(a, b), 1.0);
+    }
+
+    #[test]
+    fn one_element_tuples_keep_their_comma() {
+        let tuple = "fn main() { let v = vec![(1,), (2, 3,)]; }";
+        let parenthesized = "fn main() { let v = vec![(1), (2, 3)]; }";
+
+        assert_ne!(normalize(tuple), normalize(parenthesized));
+        assert_eq!(
+            normalize(tuple),
+            normalize("fn main() { let v = vec![(1,), (2, 3)]; }")
+        );
+    }
+
+    #[test]
+    fn imports_are_sorted_per_run() {
+        let code = "use b::B;\nuse a::A;\nfn f() {}\nuse d::D;\nuse c::C;\n";
+
+        assert_eq!(
+            normalize(code),
+            "use a::A;\nuse b::B;\nfn f() {}\nuse c::C;\nuse d::D;\n"
+        );
+    }
+
+    #[test]
+    fn unparseable_code() {
+        let code = "  let x = 1;\n\n  // comment\n  x + 1";
+
+        assert_eq!(normalize(code), "let x = 1;\nx + 1\n");
+    }
+
+    #[test]
+    fn similarity_of_different_code() {
+        let a = "fn main() { println!(\"a\"); }";
+        let b = "fn main() { eprintln!(\"b\"); }";
+
+        let score = similarity(a, b);
+        assert!(score > 0.5 && score < 1.0, "unexpected score: {}", score);
+        assert_eq!(similarity("", ""), 0.0);
+    }
+}
@@ -1,17 +1,26 @@
 //! Utilities for interacting with tasks implemented on the Rosetta Code wiki.
 
-use std::collections::HashMap;
-
 use lazy_static::lazy_static;
+use mediawiki::{Pages, Url};
 use percent_encoding::{self, define_encode_set, QUERY_ENCODE_SET};
 use regex::Regex;
-use reqwest::Url;
-use serde::Deserialize;
 
 lazy_static! {
-    /// Extracts code from the first Rust section from Rosetta Code wiki markup.
-    static ref RUST_WIKI_SECTION_RE: Regex =
-        Regex::new(r"==\{\{header\|[Rr]ust\}\}==(?s:.*?)<lang [Rr]ust>((?s:.*?))</lang>").unwrap();
+    /// Matches the header that starts the Rust section in Rosetta Code wiki markup.
+    static ref RUST_HEADER_RE: Regex = Regex::new(r"==\{\{header\|[Rr]ust\}\}==").unwrap();
+
+    /// Matches the start of the next top-level section, which ends the Rust section.
+    static ref SECTION_END_RE: Regex = Regex::new(r"(?m)^==[^=]").unwrap();
+
+    /// Matches either a sub-heading or a Rust code block within a section. Code blocks are either
+    /// `<lang rust>` or `<syntaxhighlight lang="rust">`, which newer pages use.
+    static ref BLOCK_RE: Regex = Regex::new(concat!(
+        r"(?m)^(?P<level>={3,6})\s*(?P<heading>[^=\n].*?)\s*={3,6}\s*$",
+        r"|<lang [Rr]ust>(?P<code>(?s:.*?))</lang>",
+        r#"|<syntaxhighlight\b[^>]*?\blang\s*=\s*["']?[Rr]ust["']?[^>]*>"#,
+        r"(?P<highlighted>(?s:.*?))</syntaxhighlight>",
+    ))
+    .unwrap();
 }
 
 define_encode_set! {
@@ -22,12 +31,25 @@ define_encode_set! {
     pub ROSETTA_ENCODE_SET = [QUERY_ENCODE_SET] | { '+' }
 }
 
+/// A block of Rust code in the Rust section of a task on the wiki.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// The sub-headings enclosing the block, outermost first.
+    ///
+    /// For example, a block under `===Iterative===` nested in `====Using a Vec====` would have
+    /// the headings `["Iterative", "Using a Vec"]`.
+    pub headings: Vec<String>,
+
+    /// The code contained in the block.
+    pub code: String,
+}
+
 /// Represents a task implemented on the Rosetta Code wiki.
 #[derive(Debug, Clone)]
 pub struct RemoteTask {
     title: String,
     url: Url,
-    source: Option<String>,
+    blocks: Vec<CodeBlock>,
 }
 
 impl RemoteTask {
@@ -44,37 +66,14 @@ impl RemoteTask {
     /// If the task contains a Rust implementation on the wiki, this field will contain the first
     /// Rust code block of that section. Otherwise, returns `None`.
     pub fn source(&self) -> Option<String> {
-        self.source.clone()
+        self.blocks.first().map(|block| block.code.clone())
     }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct Response {
-    pub query: Pages,
-}
 
-/// Intermediate container in the query API response.
-#[derive(Debug, Deserialize)]
-pub struct Pages {
-    pub pages: HashMap<i32, Page>,
-}
-
-/// Individual page in the query API response.
-#[derive(Debug, Deserialize)]
-pub struct Page {
-    #[serde(rename = "pageid")]
-    pub id: i32,
-
-    pub title: String,
-
-    #[serde(default)]
-    pub revisions: Vec<Revision>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct Revision {
-    #[serde(rename = "*")]
-    pub content: String,
+    /// Returns every Rust code block of the task on the wiki, in the order they appear on the
+    /// page.
+    pub fn blocks(&self) -> &[CodeBlock] {
+        &self.blocks
+    }
 }
 
 /// Transforms a URL-encoded task title from the wiki to a human-readable task title.
@@ -91,20 +90,53 @@ pub fn encode_title(title: &str) -> String {
     encoded.collect()
 }
 
-pub fn parse_tasks(response: &Response) -> Vec<RemoteTask> {
-    response
-        .query
+/// Extracts every Rust code block from the Rust section of a page, along with the sub-headings
+/// that enclose each block.
+pub fn parse_code_blocks(content: &str) -> Vec<CodeBlock> {
+    let section = match RUST_HEADER_RE.find(content) {
+        Some(header) => {
+            let rest = &content[header.end()..];
+            let end = SECTION_END_RE.find(rest).map_or(rest.len(), |m| m.start());
+            &rest[..end]
+        }
+        None => return vec![],
+    };
+
+    // Stack of (level, heading) pairs for the sub-headings enclosing the current position.
+    let mut headings: Vec<(usize, String)> = vec![];
+    let mut blocks = vec![];
+
+    for captures in BLOCK_RE.captures_iter(section) {
+        if let Some(code) = captures
+            .name("code")
+            .or_else(|| captures.name("highlighted"))
+        {
+            blocks.push(CodeBlock {
+                headings: headings
+                    .iter()
+                    .map(|(_, heading)| heading.clone())
+                    .collect(),
+                code: code.as_str().to_owned(),
+            });
+        } else {
+            let level = captures["level"].len();
+            headings.retain(|&(l, _)| l < level);
+            headings.push((level, captures["heading"].to_owned()));
+        }
+    }
+
+    blocks
+}
+
+/// Parses the tasks in the pages returned by a [`mediawiki::revisions`] query. Missing pages are
+/// skipped.
+pub fn parse_tasks(pages: &Pages) -> Vec<RemoteTask> {
+    pages
         .pages
-        .values()
+        .iter()
+        .filter(|page| !page.missing)
         .map(|page| {
-            let source = if !page.revisions.is_empty() {
-                RUST_WIKI_SECTION_RE
-                    .captures(&page.revisions[0].content)
-                    .map(|captures| captures.get(1).unwrap())
-                    .map(|m| m.as_str().to_owned())
-            } else {
-                None
-            };
+            let blocks = page.content().map(parse_code_blocks).unwrap_or_default();
 
             let title = page.title.clone();
             let url = Url::parse(&format!(
@@ -113,7 +145,7 @@ pub fn parse_tasks(response: &Response) -> Vec<RemoteTask> {
             ))
             .unwrap();
 
-            RemoteTask { title, url, source }
+            RemoteTask { title, url, blocks }
         })
         .collect()
 }
@@ -144,4 +176,75 @@ mod tests {
 
         assert_eq!(super::decode_title(&super::encode_title(TITLE)), TITLE);
     }
+
+    #[test]
+    fn code_blocks() {
+        use super::{parse_code_blocks, CodeBlock};
+
+        let content = r#"
+=={{header|Ruby}}==
+<lang ruby>puts 1</lang>
+=={{header|Rust}}==
+<lang rust>fn first() {}</lang>
+===Recursive===
+<lang rust>fn recursive() {}</lang>
+====Memoized====
+<lang rust>
+fn memoized() {}
+</lang>
+===Iterative===
+<lang Rust>fn iterative() {}</lang>
+=={{header|Scala}}==
+<lang scala>println(1)</lang>
+"#;
+
+        let block = |headings: &[&str], code: &str| CodeBlock {
+            headings: headings.iter().map(|&h| h.to_owned()).collect(),
+            code: code.to_owned(),
+        };
+
+        assert_eq!(
+            parse_code_blocks(content),
+            vec![
+                block(&[], "fn first() {}"),
+                block(&["Recursive"], "fn recursive() {}"),
+                block(&["Recursive", "Memoized"], "\nfn memoized() {}\n"),
+                block(&["Iterative"], "fn iterative() {}"),
+            ]
+        );
+
+        assert!(parse_code_blocks("=={{header|C}}==\n<lang c>int x;</lang>").is_empty());
+    }
+
+    #[test]
+    fn syntaxhighlight_blocks() {
+        use super::{parse_code_blocks, CodeBlock};
+
+        let content = r#"
+=={{header|Rust}}==
+<syntaxhighlight lang="rust">fn first() {}</syntaxhighlight>
+===Iterative===
+<syntaxhighlight lang=rust line>
+fn iterative() {}
+</syntaxhighlight>
+<syntaxhighlight lang="text">not rust</syntaxhighlight>
+<lang rust>fn old() {}</lang>
+=={{header|Scala}}==
+<syntaxhighlight lang="scala">println(1)</syntaxhighlight>
+"#;
+
+        let block = |headings: &[&str], code: &str| CodeBlock {
+            headings: headings.iter().map(|&h| h.to_owned()).collect(),
+            code: code.to_owned(),
+        };
+
+        assert_eq!(
+            parse_code_blocks(content),
+            vec![
+                block(&[], "fn first() {}"),
+                block(&["Iterative"], "\nfn iterative() {}\n"),
+                block(&["Iterative"], "fn old() {}"),
+            ]
+        );
+    }
 }
@@ -1,11 +1,118 @@
 //! Utilities to assist in testing tasks in this repository.
+//!
+//! Each macro generates a suite of tests for a common kind of task: [sorting
+//! algorithms](crate::test_sort), [hash functions](crate::test_hash), [ciphers](crate::test_cipher)
+//! and [encoders](crate::test_codec).
+//!
+//! Randomized tests draw their inputs from [`test_rng`], which prints the seed it uses. To
+//! reproduce a failure, run the test again with the seed in the `ROSETTA_TEST_SEED` environment
+//! variable.
 
+use std::cmp::Ordering;
+use std::env;
 use std::fmt::Debug;
 
-/// Generates a comprehensive test suite for a sorting algorithm.
+use rand::distributions::{Distribution, Standard};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// The environment variable that sets the seed of [`test_rng`].
+pub const SEED_VAR: &str = "ROSETTA_TEST_SEED";
+
+/// The number of elements in the large inputs given to sorting algorithms.
+pub const LARGE_INPUT_LEN: usize = 1000;
+
+/// The number of random inputs used to check that encoding and decoding round trip.
+pub const ROUND_TRIPS: usize = 100;
+
+/// The random number generator used by randomized tests.
+pub type TestRng = StdRng;
+
+/// Creates a random number generator for a test.
+///
+/// The seed is taken from the `ROSETTA_TEST_SEED` environment variable if it is set, and chosen at
+/// random otherwise. Either way, it is printed so that it shows up in the output of a failing test.
+pub fn test_rng() -> TestRng {
+    let seed = match env::var(SEED_VAR) {
+        Ok(seed) => seed
+            .parse()
+            .unwrap_or_else(|_| panic!("{} must be an unsigned integer", SEED_VAR)),
+        Err(_) => rand::thread_rng().gen(),
+    };
+
+    println!("random seed: {} (set {} to reproduce)", seed, SEED_VAR);
+    StdRng::seed_from_u64(seed)
+}
+
+/// Generates a vector of random values.
+pub fn random_vec<T>(rng: &mut TestRng, len: usize) -> Vec<T>
+where
+    Standard: Distribution<T>,
+{
+    rng.sample_iter(Standard).take(len).collect()
+}
+
+/// Generates a random string of up to `max_len` characters from an alphabet.
+pub fn random_string(rng: &mut TestRng, alphabet: &str, max_len: usize) -> String {
+    let chars = alphabet.chars().collect::<Vec<_>>();
+    let len = rng.gen_range(0..=max_len);
+
+    (0..len)
+        .map(|_| chars[rng.gen_range(0..chars.len())])
+        .collect()
+}
+
+/// An element that is ordered only by its key, and remembers its position in the input so that
+/// the stability of a sort can be checked.
+#[derive(Debug, Clone, Copy)]
+pub struct Keyed {
+    /// The key that the element is sorted by.
+    pub key: u8,
+
+    /// The position of the element before sorting.
+    pub index: usize,
+}
+
+impl PartialEq for Keyed {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Keyed {}
+
+impl PartialOrd for Keyed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Keyed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// Numbers each key with its position, ready to be sorted.
+pub fn keyed(keys: &[u8]) -> Vec<Keyed> {
+    keys.iter()
+        .enumerate()
+        .map(|(index, &key)| Keyed { key, index })
+        .collect()
+}
+
+/// Generates a suite of tests for a sorting algorithm.
 ///
 /// This macro can be used to test any sort function that sorts a mutable slice of objects that
-/// implement the `Ord` or `PartialOrd` traits.
+/// implement the `Ord` or `PartialOrd` traits. Attributes given after the function, such as
+/// `#[ignore]`, are applied to every test.
+///
+/// Some tests sort [`LARGE_INPUT_LEN`] elements. Algorithms that are too slow for that can set a
+/// smaller length, as in `test_sort!(super::stooge_sort, large_len = 100)`. The other sorting
+/// macros accept the same options.
+///
+/// See also [`test_stable_sort`](crate::test_stable_sort), [`test_sort_floats`](crate::test_sort_floats)
+/// and [`test_sort_by`](crate::test_sort_by) for additional properties of sorts.
 ///
 /// # Example
 ///
@@ -24,16 +131,18 @@ use std::fmt::Debug;
 #[macro_export]
 macro_rules! test_sort {
     ( $function:path $(, #[$attr:meta])* ) => {
-        use $crate::rand::Rng;
-
+        $crate::test_sort!($function, large_len = $crate::test_utils::LARGE_INPUT_LEN $(, #[$attr])*);
+    };
+    ( $function:path, large_len = $large_len:expr $(, #[$attr:meta])* ) => {
         macro_rules! test_case {
            ($name:ident => $values:expr) => {
                 $(#[$attr])*
                 #[test]
                 fn $name() {
                     let mut values = $values;
+                    let original = values.to_vec();
                     $function(&mut values);
-                    $crate::test_utils::check_sorted(&values);
+                    $crate::test_utils::check_sort(&original, &values);
                 }
             }
         }
@@ -43,16 +152,415 @@ macro_rules! test_sort {
         test_case!(empty_vector => Vec::<i32>::new());
         test_case!(one_element_vector => vec![0_i32]);
         test_case!(random_numbers => {
-            let mut rng = $crate::rand::thread_rng();
-            let mut numbers = [0i32; 10];
-            rng.fill(&mut numbers);
-            numbers
+            $crate::test_utils::random_vec::<i32>(&mut $crate::test_utils::test_rng(), 10)
         });
         test_case!(reverse_sorted_array => [20_i32, 10, 0, -1, -5]);
         test_case!(unsorted_array => [4_i32, 65, 2, -31, 0, 99, 2, 83, 782, 1]);
         test_case!(unsorted_array_positive => [12_i32, 54, 2, 93, 13, 43, 15, 299, 234]);
         test_case!(unsorted_vector_positive => vec![1_i32, 9, 4, 7, 6, 5, 3, 2, 8]);
         test_case!(vector_with_repeated_elements => vec![1_i32, 1, 1, 1, 1]);
+        test_case!(large_random_numbers => {
+            let mut rng = $crate::test_utils::test_rng();
+            $crate::test_utils::random_vec::<i32>(&mut rng, $large_len)
+        });
+        test_case!(large_few_unique_values => {
+            let mut rng = $crate::test_utils::test_rng();
+            $crate::test_utils::random_vec::<u8>(&mut rng, $large_len)
+                .into_iter()
+                .map(|n| n % 4)
+                .collect::<Vec<_>>()
+        });
+    }
+}
+
+/// Generates tests that check that a sorting algorithm is stable: elements that compare equal keep
+/// the order they had in the input.
+///
+/// The sort function must accept a mutable slice of [`Keyed`], which implements `Ord` and `Copy`.
+///
+/// # Example
+///
+/// ```
+/// fn sort<E>(elements: &mut [E]) where E: Ord {
+///     elements.sort();
+/// }
+///
+/// #[cfg(test)]
+/// mod tests {
+///     use meta::test_stable_sort;
+///
+///     test_stable_sort!(super::sort);
+/// }
+/// ```
+#[macro_export]
+macro_rules! test_stable_sort {
+    ( $function:path $(, #[$attr:meta])* ) => {
+        $crate::test_stable_sort!($function, large_len = $crate::test_utils::LARGE_INPUT_LEN $(, #[$attr])*);
+    };
+    ( $function:path, large_len = $large_len:expr $(, #[$attr:meta])* ) => {
+        macro_rules! stable_test_case {
+           ($name:ident => $keys:expr) => {
+                $(#[$attr])*
+                #[test]
+                fn $name() {
+                    let mut values = $crate::test_utils::keyed(&$keys);
+                    let original = values.clone();
+                    $function(&mut values);
+                    $crate::test_utils::check_sort(&original, &values);
+                    $crate::test_utils::check_stable(&values);
+                }
+            }
+        }
+
+        stable_test_case!(stable_all_equal => [7; 10]);
+        stable_test_case!(stable_repeated_keys => [3, 1, 2, 3, 1, 2, 3, 1, 2]);
+        stable_test_case!(stable_reverse_sorted => [5, 5, 4, 4, 3, 3, 2, 2, 1, 1]);
+        stable_test_case!(stable_large_random => {
+            let mut rng = $crate::test_utils::test_rng();
+            $crate::test_utils::random_vec::<u8>(&mut rng, $large_len)
+                .into_iter()
+                .map(|key| key % 8)
+                .collect::<Vec<_>>()
+        });
+    }
+}
+
+/// Generates tests for a sorting algorithm that accepts `PartialOrd` elements, using `f64`.
+///
+/// Without NaN, the floats must be sorted, with negative zero and positive zero treated as equal.
+/// NaN is not ordered relative to any other value, so when it is present, the only requirements are
+/// that the sort finishes and that no element is lost or duplicated.
+///
+/// # Example
+///
+/// ```
+/// fn sort<E>(elements: &mut [E]) where E: PartialOrd {
+///     elements.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+/// }
+///
+/// #[cfg(test)]
+/// mod tests {
+///     use meta::test_sort_floats;
+///
+///     test_sort_floats!(super::sort);
+/// }
+/// ```
+#[macro_export]
+macro_rules! test_sort_floats {
+    ( $function:path $(, #[$attr:meta])* ) => {
+        $crate::test_sort_floats!($function, large_len = $crate::test_utils::LARGE_INPUT_LEN $(, #[$attr])*);
+    };
+    ( $function:path, large_len = $large_len:expr $(, #[$attr:meta])* ) => {
+        macro_rules! float_test_case {
+           ($name:ident => $values:expr) => {
+                $(#[$attr])*
+                #[test]
+                fn $name() {
+                    let mut values: Vec<f64> = $values;
+                    let original = values.clone();
+                    $function(&mut values);
+                    $crate::test_utils::check_sort_floats(&original, &values);
+                }
+            }
+        }
+
+        float_test_case!(floats_unsorted => vec![2.5, -1.0, 0.1, 1e10, -3.75, 0.1]);
+        float_test_case!(floats_special_values => vec![
+            f64::INFINITY, 0.0, f64::MIN_POSITIVE, -0.0, f64::NEG_INFINITY, f64::MAX, f64::MIN,
+        ]);
+        float_test_case!(floats_with_nan => vec![3.0, f64::NAN, 1.0, -2.0, f64::NAN, 0.5]);
+        float_test_case!(floats_all_nan => vec![f64::NAN; 5]);
+        float_test_case!(floats_large_random => {
+            let mut rng = $crate::test_utils::test_rng();
+            $crate::test_utils::random_vec::<f64>(&mut rng, $large_len)
+                .into_iter()
+                .map(|x| (x - 0.5) * 1e6)
+                .collect()
+        });
+    }
+}
+
+/// Generates tests for a sorting algorithm that takes a custom comparator, with a signature like
+/// [`slice::sort_by`].
+///
+/// # Example
+///
+/// ```
+/// use std::cmp::Ordering;
+///
+/// fn sort_by<E, F>(elements: &mut [E], compare: F)
+/// where
+///     F: FnMut(&E, &E) -> Ordering,
+/// {
+///     elements.sort_by(compare);
+/// }
+///
+/// #[cfg(test)]
+/// mod tests {
+///     use meta::test_sort_by;
+///
+///     test_sort_by!(super::sort_by);
+/// }
+/// ```
+#[macro_export]
+macro_rules! test_sort_by {
+    ( $function:path $(, #[$attr:meta])* ) => {
+        $crate::test_sort_by!($function, large_len = $crate::test_utils::LARGE_INPUT_LEN $(, #[$attr])*);
+    };
+    ( $function:path, large_len = $large_len:expr $(, #[$attr:meta])* ) => {
+        macro_rules! by_test_case {
+           ($name:ident => $values:expr, $compare:expr) => {
+                $(#[$attr])*
+                #[test]
+                fn $name() {
+                    let mut values = $values;
+                    let original = values.to_vec();
+                    $function(&mut values, $compare);
+                    $crate::test_utils::check_sort_by(&original, &values, $compare);
+                }
+            }
+        }
+
+        by_test_case!(by_descending => [4_i32, 65, 2, -31, 0, 99, 2, 83], |a: &i32, b: &i32| b.cmp(a));
+        by_test_case!(by_string_length => ["beach", "car", "airplane", "art", "house"],
+            |a: &&str, b: &&str| a.len().cmp(&b.len()));
+        by_test_case!(by_absolute_value => vec![-5_i64, 3, -1, 0, 4, -2],
+            |a: &i64, b: &i64| a.abs().cmp(&b.abs()));
+        by_test_case!(by_large_random => {
+            let mut rng = $crate::test_utils::test_rng();
+            $crate::test_utils::random_vec::<u16>(&mut rng, $large_len)
+        }, |a: &u16, b: &u16| (a % 100).cmp(&(b % 100)));
+    }
+}
+
+/// A value that can be formatted as the hexadecimal digest of a hash function.
+pub trait Digest {
+    /// Formats the digest as lowercase hexadecimal.
+    fn to_hex(&self) -> String;
+}
+
+impl Digest for str {
+    fn to_hex(&self) -> String {
+        self.to_lowercase()
+    }
+}
+
+impl Digest for String {
+    fn to_hex(&self) -> String {
+        self.as_str().to_hex()
+    }
+}
+
+impl Digest for [u8] {
+    fn to_hex(&self) -> String {
+        self.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+impl<const N: usize> Digest for [u8; N] {
+    fn to_hex(&self) -> String {
+        self[..].to_hex()
+    }
+}
+
+impl Digest for Vec<u8> {
+    fn to_hex(&self) -> String {
+        self[..].to_hex()
+    }
+}
+
+impl Digest for u32 {
+    fn to_hex(&self) -> String {
+        format!("{:08x}", self)
+    }
+}
+
+impl Digest for u64 {
+    fn to_hex(&self) -> String {
+        format!("{:016x}", self)
+    }
+}
+
+/// Generates tests for a hash function from known-answer vectors.
+///
+/// Each vector is an input, which is passed to the function as-is, and the expected digest in
+/// hexadecimal. The function may return any type that implements [`Digest`].
+///
+/// # Example
+///
+/// ```
+/// fn checksum(bytes: &[u8]) -> u32 {
+///     bytes.iter().map(|&b| u32::from(b)).sum()
+/// }
+///
+/// #[cfg(test)]
+/// mod tests {
+///     use meta::test_hash;
+///
+///     test_hash! {
+///         super::checksum,
+///         b"" => "00000000",
+///         b"abc" => "00000126",
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! test_hash {
+    ( $function:path, $($input:expr => $digest:expr),+ $(,)? ) => {
+        #[test]
+        fn hash_known_answers() {
+            $(
+                assert_eq!(
+                    $crate::test_utils::Digest::to_hex(&$function($input)),
+                    $digest.to_lowercase(),
+                    "wrong digest for {:?}",
+                    $input,
+                );
+            )+
+        }
+
+        #[test]
+        fn hash_is_deterministic() {
+            $(
+                assert_eq!(
+                    $crate::test_utils::Digest::to_hex(&$function($input)),
+                    $crate::test_utils::Digest::to_hex(&$function($input)),
+                );
+            )+
+        }
+    }
+}
+
+/// Generates tests for a cipher: decrypting the encryption of any text from the alphabet must give
+/// back the original text, and each vector of plaintext and ciphertext must match in both
+/// directions.
+///
+/// The cipher is given as a pair of closures from `&str` to `String`, which can supply keys or
+/// other parameters.
+///
+/// # Example
+///
+/// ```
+/// fn rot13(text: &str) -> String {
+///     text.chars()
+///         .map(|c| match c {
+///             'A'..='M' | 'a'..='m' => (c as u8 + 13) as char,
+///             'N'..='Z' | 'n'..='z' => (c as u8 - 13) as char,
+///             _ => c,
+///         })
+///         .collect()
+/// }
+///
+/// #[cfg(test)]
+/// mod tests {
+///     use meta::test_cipher;
+///
+///     test_cipher! {
+///         encrypt: |text| super::rot13(text),
+///         decrypt: |text| super::rot13(text),
+///         alphabet: "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz .,!",
+///         vectors: ["Hello, world!" => "Uryyb, jbeyq!"],
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! test_cipher {
+    (
+        encrypt: |$plaintext:ident| $encrypt:expr,
+        decrypt: |$ciphertext:ident| $decrypt:expr,
+        alphabet: $alphabet:expr
+        $(, vectors: [$($plain:expr => $cipher:expr),* $(,)?])? $(,)?
+    ) => {
+        #[test]
+        fn cipher_known_answers() {
+            let encrypt = |$plaintext: &str| -> String { $encrypt };
+            let decrypt = |$ciphertext: &str| -> String { $decrypt };
+
+            $($(
+                assert_eq!(encrypt($plain), $cipher);
+                assert_eq!(decrypt($cipher), $plain);
+            )*)?
+        }
+
+        #[test]
+        fn cipher_round_trip() {
+            let encrypt = |$plaintext: &str| -> String { $encrypt };
+            let decrypt = |$ciphertext: &str| -> String { $decrypt };
+
+            let mut rng = $crate::test_utils::test_rng();
+            for _ in 0..$crate::test_utils::ROUND_TRIPS {
+                let text = $crate::test_utils::random_string(&mut rng, $alphabet, 64);
+                assert_eq!(decrypt(&encrypt(&text)), text);
+            }
+            assert_eq!(decrypt(&encrypt("")), "");
+        }
+    }
+}
+
+/// Generates tests for an encoder and its decoder: decoding the encoding of any input must give back
+/// the input, and each vector of input and encoding must match in both directions.
+///
+/// The encoder and decoder are given as closures with typed arguments, and random inputs are
+/// produced by the `arbitrary` closure from a [`TestRng`].
+///
+/// # Example
+///
+/// ```
+/// fn encode(bytes: &[u8]) -> String {
+///     bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+/// }
+///
+/// fn decode(hex: &str) -> Vec<u8> {
+///     (0..hex.len())
+///         .step_by(2)
+///         .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+///         .collect()
+/// }
+///
+/// #[cfg(test)]
+/// mod tests {
+///     use meta::test_codec;
+///
+///     test_codec! {
+///         encode: |bytes: &[u8]| super::encode(bytes),
+///         decode: |hex: &str| super::decode(hex),
+///         arbitrary: |rng| meta::test_utils::random_vec::<u8>(rng, 32),
+///         vectors: [b"\x00\xff" => "00ff"],
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! test_codec {
+    (
+        encode: |$input:ident: $input_ty:ty| $encode:expr,
+        decode: |$encoded:ident: $encoded_ty:ty| $decode:expr,
+        arbitrary: |$rng:ident| $arbitrary:expr
+        $(, vectors: [$($decoded:expr => $encoding:expr),* $(,)?])? $(,)?
+    ) => {
+        #[test]
+        fn codec_known_answers() {
+            let encode = |$input: $input_ty| $encode;
+            let decode = |$encoded: $encoded_ty| $decode;
+
+            $($(
+                assert_eq!(encode($decoded), $encoding);
+                assert_eq!(&decode($encoding), $decoded);
+            )*)?
+        }
+
+        #[test]
+        fn codec_round_trip() {
+            let encode = |$input: $input_ty| $encode;
+            let decode = |$encoded: $encoded_ty| $decode;
+            let arbitrary = |$rng: &mut $crate::test_utils::TestRng| $arbitrary;
+
+            let mut rng = $crate::test_utils::test_rng();
+            for _ in 0..$crate::test_utils::ROUND_TRIPS {
+                let input = arbitrary(&mut rng);
+                let encoded = encode(&input);
+                assert_eq!(decode(&encoded), input, "encoded as {:?}", encoded);
+            }
+        }
     }
 }
 
@@ -67,8 +575,86 @@ where
     assert_eq!(sorted, candidate);
 }
 
+/// Check that a slice is a sorted permutation of the original input.
+pub fn check_sort<E>(original: &[E], candidate: &[E])
+where
+    E: Ord + Clone + Debug,
+{
+    let mut sorted = original.to_vec();
+    sorted.sort();
+
+    assert_eq!(sorted, candidate);
+}
+
+/// Check that elements with equal keys are in the same order as they were in the input.
+pub fn check_stable(candidate: &[Keyed]) {
+    for pair in candidate.windows(2) {
+        assert!(
+            pair[0].key != pair[1].key || pair[0].index < pair[1].index,
+            "sort is not stable: {:?} was moved after {:?}",
+            pair[1],
+            pair[0],
+        );
+    }
+}
+
+/// Check that a slice of floats is a permutation of the original input, and that it is sorted if
+/// the input contains no NaN.
+pub fn check_sort_floats(original: &[f64], candidate: &[f64]) {
+    let bits = |values: &[f64]| {
+        let mut values = values.to_vec();
+        values.sort_by(f64::total_cmp);
+        values.into_iter().map(f64::to_bits).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        bits(original),
+        bits(candidate),
+        "{:?} is not a permutation of {:?}",
+        candidate,
+        original
+    );
+
+    if !original.iter().any(|x| x.is_nan()) {
+        assert!(
+            candidate.windows(2).all(|pair| pair[0] <= pair[1]),
+            "{:?} is not sorted",
+            candidate
+        );
+    }
+}
+
+/// Check that a slice is a permutation of the original input that is sorted according to a
+/// comparator.
+pub fn check_sort_by<E, F>(original: &[E], candidate: &[E], mut compare: F)
+where
+    E: Ord + Clone + Debug,
+    F: FnMut(&E, &E) -> Ordering,
+{
+    let mut expected = original.to_vec();
+    let mut actual = candidate.to_vec();
+    expected.sort();
+    actual.sort();
+    assert_eq!(
+        expected, actual,
+        "{:?} is not a permutation of the input",
+        candidate
+    );
+
+    assert!(
+        candidate
+            .windows(2)
+            .all(|pair| compare(&pair[0], &pair[1]) != Ordering::Greater),
+        "{:?} is not sorted",
+        candidate
+    );
+}
+
 #[cfg(test)]
 mod tests {
+    use std::cmp::Ordering;
+
+    use super::{check_sort_floats, check_stable, keyed};
+
     #[test]
     fn check_sorted() {
         let sorted = [1, 2, 3, 4, 5];
@@ -83,4 +669,84 @@ mod tests {
 
         super::check_sorted(&unsorted);
     }
+
+    #[test]
+    #[should_panic]
+    fn check_lost_element() {
+        super::check_sort(&[3, 1, 2], &[1, 2, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not stable")]
+    fn check_unstable() {
+        let mut values = keyed(&[2, 1, 2, 1]);
+        values.sort_by_key(|value| (value.key, usize::MAX - value.index));
+
+        check_stable(&values);
+    }
+
+    #[test]
+    fn check_floats_with_nan() {
+        check_sort_floats(&[2.0, f64::NAN, 1.0], &[2.0, 1.0, f64::NAN]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a permutation")]
+    fn check_floats_lost_nan() {
+        check_sort_floats(&[f64::NAN, 1.0], &[1.0, 1.0]);
+    }
+
+    fn sort<E: Ord>(elements: &mut [E]) {
+        elements.sort();
+    }
+
+    fn sort_floats<E: PartialOrd>(elements: &mut [E]) {
+        elements.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    }
+
+    fn sort_by<E, F: FnMut(&E, &E) -> Ordering>(elements: &mut [E], compare: F) {
+        elements.sort_by(compare);
+    }
+
+    fn caesar(text: &str, shift: u8) -> String {
+        text.bytes()
+            .map(|b| match b {
+                b'a'..=b'z' => ((b - b'a' + shift) % 26 + b'a') as char,
+                _ => b as char,
+            })
+            .collect()
+    }
+
+    fn fletcher16(bytes: &[u8]) -> [u8; 2] {
+        let (a, b) = bytes.iter().fold((0u16, 0u16), |(a, b), &byte| {
+            let a = (a + u16::from(byte)) % 255;
+            (a, (b + a) % 255)
+        });
+        [b as u8, a as u8]
+    }
+
+    crate::test_sort!(sort);
+    crate::test_stable_sort!(sort);
+    crate::test_sort_floats!(sort_floats);
+    crate::test_sort_by!(sort_by);
+
+    crate::test_hash! {
+        fletcher16,
+        b"abcde" => "C8F0",
+        b"abcdef" => "2057",
+    }
+
+    crate::test_cipher! {
+        encrypt: |text| caesar(text, 3),
+        decrypt: |text| caesar(text, 23),
+        alphabet: "abcdefghijklmnopqrstuvwxyz .",
+        vectors: ["veni vidi vici." => "yhql ylgl ylfl."],
+    }
+
+    crate::test_codec! {
+        encode: |n: &u32| n.to_string(),
+        decode: |s: &str| s.parse::<u32>().unwrap(),
+        arbitrary: |rng| rand::Rng::gen(rng),
+        vectors: [&42 => "42"],
+    }
 }
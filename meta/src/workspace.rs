@@ -0,0 +1,321 @@
+//! Utilities for reading the workspace manifest (the root `Cargo.toml`) of the repository.
+//!
+//! The list of workspace members is annotated with comments linking each task to the wiki. These
+//! comments are lost when the manifest is parsed as TOML, so the member list is parsed by hand.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Error};
+
+/// A member of the workspace, as listed in the root `Cargo.toml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    /// The path to the member crate, relative to the workspace root, such as `tasks/fizzbuzz`.
+    pub path: String,
+
+    /// The URL in the comment above the member, if there is one. Several members may share the
+    /// same comment.
+    pub comment_url: Option<String>,
+
+    /// The line of the manifest that the member is listed on, starting from 1.
+    pub line: usize,
+}
+
+/// Parses the members of the workspace, and the comments above them, from the text of the root
+/// `Cargo.toml`.
+pub fn parse_members(manifest: &str) -> Result<Vec<Member>, Error> {
+    let mut lines = manifest.lines().enumerate();
+
+    lines
+        .by_ref()
+        .find(|(_, line)| line.trim() == "[workspace]")
+        .ok_or_else(|| anyhow!("manifest has no [workspace] section"))?;
+    lines
+        .by_ref()
+        .find(|(_, line)| line.trim_start().starts_with("members = ["))
+        .ok_or_else(|| anyhow!("workspace has no members"))?;
+
+    let mut members = vec![];
+    let mut comment_url = None;
+
+    for (i, line) in lines {
+        let line = line.trim();
+
+        if line == "]" {
+            return Ok(members);
+        } else if line.is_empty() {
+            // A blank line ends the group of members that a comment applies to.
+            comment_url = None;
+        } else if let Some(comment) = line.strip_prefix('#') {
+            comment_url = Some(comment.trim().to_owned());
+        } else {
+            let path = line.trim_end_matches(',').trim_matches('"');
+            members.push(Member {
+                path: path.to_owned(),
+                comment_url: comment_url.clone(),
+                line: i + 1,
+            });
+        }
+    }
+
+    Err(anyhow!("unterminated list of workspace members"))
+}
+
+/// Reads the root `Cargo.toml` at the given path and parses its members.
+pub fn read_members<P: AsRef<Path>>(manifest_path: P) -> Result<Vec<Member>, Error> {
+    parse_members(&fs::read_to_string(manifest_path)?)
+}
+
+/// Adds a member to the workspace, with a comment linking it to the wiki, and returns the new text
+/// of the root `Cargo.toml`.
+///
+/// Members are kept in lexicographical order. A member next to a group with the same comment joins
+/// that group. Otherwise it is inserted as its own group, splitting the group it sorts inside of,
+/// if any, and repeating that group's comment for the members after it.
+pub fn insert_member(manifest: &str, path: &str, url: &str) -> Result<String, Error> {
+    let members = parse_members(manifest)?;
+
+    if members.is_empty() {
+        return Err(anyhow!("workspace has no members"));
+    } else if members.iter().any(|member| member.path == path) {
+        return Err(anyhow!("{} is already a member of the workspace", path));
+    }
+
+    let mut lines = manifest.lines().map(String::from).collect::<Vec<_>>();
+    let comment = format!("    # {}", url);
+    let entry = format!("    \"{}\",", path);
+
+    // Lines are indexed from 0 here, but `Member::line` starts from 1.
+    let is_group_boundary = |line: &str| {
+        let line = line.trim();
+        line.is_empty() || line.ends_with('[') || line == "]"
+    };
+    let group_start = |index: usize| {
+        (0..index)
+            .rev()
+            .find(|&i| is_group_boundary(&lines[i]))
+            .map_or(0, |i| i + 1)
+    };
+    let group_end = |index: usize| {
+        (index..lines.len())
+            .find(|&i| is_group_boundary(&lines[i]))
+            .unwrap_or(lines.len())
+    };
+
+    let next = members
+        .iter()
+        .position(|member| member.path.as_str() > path);
+    let previous = next
+        .unwrap_or(members.len())
+        .checked_sub(1)
+        .map(|i| &members[i]);
+    let next = next.map(|i| &members[i]);
+    let has_url = |member: &Member| member.comment_url.as_deref() == Some(url);
+
+    match (previous, next) {
+        (Some(previous), _) if has_url(previous) => {
+            lines.insert(previous.line, entry);
+        }
+        (_, Some(next)) if has_url(next) => {
+            lines.insert(next.line - 1, entry);
+        }
+        // The new member sorts inside a group with another comment, so the group is split around
+        // it, and the members after it keep their comment.
+        (Some(previous), Some(next)) if group_start(next.line - 1) < previous.line => {
+            let mut at = next.line - 1;
+            while lines[at - 1].trim_start().starts_with('#') {
+                at -= 1;
+            }
+            let mut split = vec![String::new(), comment, entry, String::new()];
+            if at == next.line - 1 {
+                if let Some(ref next_url) = next.comment_url {
+                    split.push(format!("    # {}", next_url));
+                }
+            }
+            lines.splice(at..at, split);
+        }
+        (_, Some(next)) => {
+            let start = group_start(next.line - 1);
+            lines.splice(start..start, [comment, entry, String::new()]);
+        }
+        (Some(previous), None) => {
+            let end = group_end(previous.line);
+            lines.splice(end..end, [String::new(), comment, entry]);
+        }
+        (None, None) => unreachable!("workspace has members"),
+    }
+
+    let mut manifest_with_member = lines.join("\n");
+    if manifest.ends_with('\n') {
+        manifest_with_member.push('\n');
+    }
+    Ok(manifest_with_member)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{insert_member, parse_members, Member};
+
+    #[test]
+    fn members_with_comments() {
+        let manifest = r#"
+[package]
+name = "rust-rosetta"
+
+[workspace]
+members = [
+    # http://rosettacode.org/wiki/100_doors
+    "tasks/_100-doors",
+
+    # http://rosettacode.org/wiki/Additive_primes
+    "tasks/additive-primes/addit_primes-flat",
+    "tasks/additive-primes/addit_primes-primal",
+
+    "tasks/uncommented",
+]
+"#;
+
+        let member = |path: &str, comment_url: Option<&str>, line| Member {
+            path: path.to_owned(),
+            comment_url: comment_url.map(String::from),
+            line,
+        };
+        let additive_primes = Some("http://rosettacode.org/wiki/Additive_primes");
+
+        assert_eq!(
+            parse_members(manifest).unwrap(),
+            [
+                member(
+                    "tasks/_100-doors",
+                    Some("http://rosettacode.org/wiki/100_doors"),
+                    8
+                ),
+                member(
+                    "tasks/additive-primes/addit_primes-flat",
+                    additive_primes,
+                    11
+                ),
+                member(
+                    "tasks/additive-primes/addit_primes-primal",
+                    additive_primes,
+                    12
+                ),
+                member("tasks/uncommented", None, 14),
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_members() {
+        assert!(parse_members("[workspace]\nmembers = [\n    \"tasks/a\",\n").is_err());
+    }
+
+    #[test]
+    fn insert_members() {
+        let manifest = r#"[workspace]
+members = [
+    # http://rosettacode.org/wiki/Additive_primes
+    "tasks/additive-primes/addit_primes-flat",
+    "tasks/additive-primes/addit_primes-primal",
+
+    # http://rosettacode.org/wiki/FizzBuzz
+    "tasks/fizzbuzz",
+]
+"#;
+
+        let manifest = insert_member(
+            manifest,
+            "tasks/additive-primes/addit_primes-other",
+            "http://rosettacode.org/wiki/Additive_primes",
+        )
+        .unwrap();
+        let manifest = insert_member(
+            &manifest,
+            "tasks/_100-doors",
+            "http://rosettacode.org/wiki/100_doors",
+        )
+        .unwrap();
+        let manifest = insert_member(
+            &manifest,
+            "tasks/fizz-buzz",
+            "http://rosettacode.org/wiki/Fizz_buzz",
+        )
+        .unwrap();
+        let manifest = insert_member(
+            &manifest,
+            "tasks/k-d-tree",
+            "http://rosettacode.org/wiki/K-d_tree",
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest,
+            r#"[workspace]
+members = [
+    # http://rosettacode.org/wiki/100_doors
+    "tasks/_100-doors",
+
+    # http://rosettacode.org/wiki/Additive_primes
+    "tasks/additive-primes/addit_primes-flat",
+    "tasks/additive-primes/addit_primes-other",
+    "tasks/additive-primes/addit_primes-primal",
+
+    # http://rosettacode.org/wiki/Fizz_buzz
+    "tasks/fizz-buzz",
+
+    # http://rosettacode.org/wiki/FizzBuzz
+    "tasks/fizzbuzz",
+
+    # http://rosettacode.org/wiki/K-d_tree
+    "tasks/k-d-tree",
+]
+"#
+        );
+
+        assert!(insert_member(&manifest, "tasks/fizzbuzz", "").is_err());
+    }
+
+    #[test]
+    fn insert_member_splitting_group() {
+        let manifest = r#"[workspace]
+members = [
+    # http://rosettacode.org/wiki/Additive_primes
+    "tasks/additive-primes/addit_primes-flat",
+    "tasks/additive-primes/addit_primes-primal",
+]
+"#;
+
+        let manifest = insert_member(
+            manifest,
+            "tasks/additive-primes/addit_primes-other",
+            "http://rosettacode.org/wiki/Other",
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest,
+            r#"[workspace]
+members = [
+    # http://rosettacode.org/wiki/Additive_primes
+    "tasks/additive-primes/addit_primes-flat",
+
+    # http://rosettacode.org/wiki/Other
+    "tasks/additive-primes/addit_primes-other",
+
+    # http://rosettacode.org/wiki/Additive_primes
+    "tasks/additive-primes/addit_primes-primal",
+]
+"#
+        );
+
+        let paths = parse_members(&manifest)
+            .unwrap()
+            .into_iter()
+            .map(|member| member.path)
+            .collect::<Vec<_>>();
+        let mut sorted = paths.clone();
+        sorted.sort();
+        assert_eq!(paths, sorted);
+    }
+}
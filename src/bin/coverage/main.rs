@@ -0,0 +1,285 @@
+use std::fs::{self, File};
+use std::io;
+use std::io::prelude::*;
+use std::path::PathBuf;
+
+use difference::{Changeset, Difference};
+use serde_json::json;
+use structopt::clap::arg_enum;
+use structopt::StructOpt;
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+use mediawiki::{Client, RecordingSource, SnapshotSource, Source};
+use meta::normalize::normalize;
+use meta::{Task, TaskIndex, TaskStatus};
+
+use render::RenderOpt;
+use report::ReportOpt;
+
+mod render;
+mod report;
+
+/// Prints a colored diff of two strings to the terminal.
+fn print_diff(t: &mut impl WriteColor, s1: &str, s2: &str) -> io::Result<()> {
+    let changeset = Changeset::new(s1, s2, "\n");
+
+    for change in changeset.diffs {
+        match change {
+            Difference::Same(ref x) => {
+                t.reset()?;
+                writeln!(t, " {}", x)?;
+            }
+            Difference::Add(ref x) => {
+                t.set_color(ColorSpec::new().set_fg(Some(Color::Green)))?;
+                for line in x.split('\n') {
+                    writeln!(t, "+{}", line)?;
+                }
+            }
+            Difference::Rem(ref x) => {
+                t.set_color(ColorSpec::new().set_fg(Some(Color::Red)))?;
+                for line in x.split('\n') {
+                    writeln!(t, "-{}", line)?;
+                }
+            }
+        }
+    }
+    t.reset()?;
+    t.flush()?;
+    Ok(())
+}
+
+/// Prints a task in a human-readable format.
+///
+/// If `semantic` is true, code is normalized before it is diffed, and files that are equivalent
+/// after normalization are not diffed at all.
+fn print_task(
+    t: &mut impl WriteColor,
+    task: &Task,
+    diff: bool,
+    semantic: bool,
+) -> meta::Result<()> {
+    t.set_color(ColorSpec::new().set_bold(true))?;
+    writeln!(t, "{}", task.title())?;
+    t.reset()?;
+
+    write!(t, "Local:")?;
+    write_status(t, task.local_code().is_some())?;
+
+    write!(t, "Remote:")?;
+    write_status(t, task.remote_code().is_some())?;
+    writeln!(t)?;
+
+    if diff {
+        for source in task.matched_sources()? {
+            let block = match source.block {
+                Some(block) => block,
+                None => continue,
+            };
+
+            t.set_color(ColorSpec::new().set_bold(true))?;
+            write!(t, "{}", source.path.display())?;
+            if !block.headings.is_empty() {
+                write!(t, " ({})", block.headings.join(" / "))?;
+            }
+            writeln!(t, ": {:.0}% similar", source.similarity * 100.0)?;
+            t.reset()?;
+
+            let local_code = fs::read_to_string(&source.path)?;
+
+            if semantic {
+                let remote_code = normalize(&block.code);
+                let local_code = normalize(&local_code);
+
+                if remote_code == local_code {
+                    writeln!(t, " equivalent")?;
+                } else {
+                    print_diff(t, &remote_code, &local_code)?;
+                }
+            } else {
+                print_diff(t, &block.code, &local_code)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a boolean as a pretty, human-readable string.
+fn write_status(t: &mut impl WriteColor, boolean: bool) -> io::Result<()> {
+    if boolean {
+        t.set_color(ColorSpec::new().set_fg(Some(Color::Green)).set_bold(true))?;
+        write!(t, " ✔ ")?
+    } else {
+        t.set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true))?;
+        write!(t, " ✘ ")?;
+    }
+
+    t.reset()?;
+    Ok(())
+}
+
+arg_enum! {
+    #[derive(Debug)]
+    enum Filter {
+        All,
+        LocalOnly,
+        RemoteOnly,
+        Unimplemented
+    }
+}
+
+// `arg_enum!` doesn't accept attributes on variants, so `#[default]` can't be used.
+#[allow(clippy::derivable_impls)]
+impl Default for Filter {
+    fn default() -> Self {
+        Filter::All
+    }
+}
+
+/// Query differences between the rust-rosetta repository and the Rosetta Code wiki.
+///
+/// This script prints out the name of each task, followed by whether it is implemented online,
+/// locally, or both.
+///
+/// If no tasks are specified, determines the status for all tasks.
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The name of a task on the wiki, such as "K-d tree"
+    #[structopt(name = "task")]
+    tasks: Vec<String>,
+
+    /// Print diffs of tasks between the local and remote version
+    #[structopt(long = "diff")]
+    diff: bool,
+
+    /// Normalize formatting, comments and import order before diffing, and only print diffs of
+    /// code that differs after normalization
+    #[structopt(long = "semantic", requires = "diff")]
+    semantic: bool,
+
+    /// Filter tasks printed by the program
+    #[structopt(
+        long = "filter",
+        possible_values = &Filter::variants(),
+        case_insensitive = true,
+        default_value = "all"
+    )]
+    filter: Filter,
+
+    /// Dump JSON to the provided filename
+    #[structopt(long = "json", parse(from_os_str))]
+    json_file: Option<PathBuf>,
+
+    /// Record every response from the wiki into the provided directory
+    #[structopt(long = "record", parse(from_os_str), conflicts_with = "snapshot")]
+    record_dir: Option<PathBuf>,
+
+    /// Replay responses from a directory created with `--record` instead of querying the wiki
+    #[structopt(long = "snapshot", parse(from_os_str))]
+    snapshot_dir: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Option<Subcommand>,
+}
+
+#[derive(Debug, StructOpt)]
+enum Subcommand {
+    /// Render the Rust section of the wiki page for local tasks.
+    ///
+    /// The section contains the code of the task, the output of running it, and a link to the
+    /// task in the repository, ready to be pasted into the wiki.
+    #[structopt(name = "render")]
+    Render(RenderOpt),
+
+    /// Summarize how many tasks are implemented locally, on the wiki, or both.
+    ///
+    /// Counts are reported for all tasks, and optionally for the tasks in specific wiki
+    /// categories. The report can be written as JSON, CSV or a Markdown table.
+    #[structopt(name = "report")]
+    Report(ReportOpt),
+}
+
+fn main() {
+    let opt = Opt::from_args();
+
+    let mut t = StandardStream::stdout(ColorChoice::Auto);
+
+    let source: Box<dyn Source> = match (&opt.record_dir, &opt.snapshot_dir) {
+        (Some(dir), _) => Box::new(RecordingSource::new(Client::default(), dir).unwrap()),
+        (_, Some(dir)) => Box::new(SnapshotSource::open(dir).unwrap()),
+        (None, None) => Box::new(Client::default()),
+    };
+
+    let task_index = TaskIndex::with_source(env!("CARGO_MANIFEST_DIR"), source).unwrap();
+
+    for mismatch in task_index.mismatches() {
+        match mismatch.status {
+            TaskStatus::Renamed { ref to } => eprintln!(
+                "warning: {} implements \"{}\", which was renamed to \"{}\" on the wiki",
+                mismatch.package_name, mismatch.title, to
+            ),
+            _ => eprintln!(
+                "warning: {} implements \"{}\", which could not be found on the wiki",
+                mismatch.package_name, mismatch.title
+            ),
+        }
+    }
+
+    match opt.command {
+        Some(Subcommand::Render(ref render_opt)) => {
+            render::render(&task_index, render_opt).unwrap();
+            return;
+        }
+        Some(Subcommand::Report(ref report_opt)) => {
+            report::report(&task_index, report_opt).unwrap();
+            return;
+        }
+        None => {}
+    }
+
+    let tasks = if !opt.tasks.is_empty() {
+        task_index.fetch_tasks(&opt.tasks)
+    } else {
+        task_index.fetch_all_tasks()
+    };
+
+    let tasks = tasks
+        .flat_map(|task| {
+            let task = task.unwrap();
+
+            match opt.filter {
+                Filter::LocalOnly if !task.is_local_only() => return None,
+                Filter::RemoteOnly if !task.is_remote_only() => return None,
+                Filter::Unimplemented if !task.is_unimplemented() => return None,
+                _ => {}
+            }
+
+            print_task(&mut t, &task, opt.diff, opt.semantic).unwrap();
+
+            if opt.json_file.is_some() {
+                let json = json!({
+                    "title": task.title(),
+                    "url": task.url().to_string(),
+                    "local_code": task.local_code(),
+                    "remote_code": task.remote_code(),
+                    "remote_blocks": task
+                        .remote_code_blocks()
+                        .iter()
+                        .map(|block| json!({ "headings": block.headings, "code": block.code }))
+                        .collect::<Vec<_>>(),
+                    "path": task.local_path(),
+                });
+
+                Some(json)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(filename) = opt.json_file {
+        let mut file = File::create(filename).unwrap();
+        file.write_all(serde_json::to_string_pretty(&tasks).unwrap().as_bytes())
+            .unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+//! Rendering of local tasks as wiki markup.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::prelude::*;
+use std::path::{Component, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use structopt::StructOpt;
+
+use meta::local::SourceKind;
+use meta::{Task, TaskIndex};
+
+/// Options for the `render` subcommand.
+#[derive(Debug, StructOpt)]
+pub struct RenderOpt {
+    /// The name of a task on the wiki, such as "K-d tree"
+    #[structopt(name = "task", required = true)]
+    tasks: Vec<String>,
+
+    /// Don't run the task to capture its output
+    #[structopt(long = "no-output")]
+    no_output: bool,
+
+    /// Kill the task if it runs for longer than this many seconds
+    #[structopt(long = "time-limit", default_value = "10")]
+    time_limit: u64,
+
+    /// The URL that paths in the repository are linked relative to
+    #[structopt(
+        long = "repository-url",
+        default_value = "https://github.com/rust-rosetta/rust-rosetta/tree/master"
+    )]
+    repository_url: String,
+
+    /// Write each section to a file in this directory instead of printing it
+    #[structopt(long = "output-dir", parse(from_os_str))]
+    output_dir: Option<PathBuf>,
+}
+
+/// Renders the wiki section of each requested task.
+///
+/// A task that can't be rendered, or that can't be found on the wiki, is reported, and the others
+/// are still rendered.
+pub fn render(task_index: &TaskIndex, opt: &RenderOpt) -> meta::Result<()> {
+    let mut missing = opt.tasks.iter().collect::<BTreeSet<_>>();
+    let tasks = missing.len();
+    let mut failures = 0;
+    let mut fetch_failed = false;
+
+    for task in task_index.fetch_tasks(&opt.tasks) {
+        let task = match task {
+            Ok(task) => task,
+            Err(e) => {
+                eprintln!("error: {:#}", e);
+                fetch_failed = true;
+                continue;
+            }
+        };
+
+        missing.remove(&task.title());
+        if let Err(e) = render_one(&task, opt) {
+            eprintln!("error: {:#}", e);
+            failures += 1;
+        }
+    }
+
+    // The titles that couldn't be fetched are already covered by the errors above.
+    if !fetch_failed {
+        for title in &missing {
+            eprintln!("error: \"{}\" is not a task on the wiki", title);
+        }
+    }
+    failures += missing.len();
+
+    if failures > 0 {
+        anyhow::bail!("{} of {} tasks could not be rendered", failures, tasks);
+    }
+
+    Ok(())
+}
+
+/// Renders the wiki section of a single task, and prints it or writes it to the output directory.
+fn render_one(task: &Task, opt: &RenderOpt) -> meta::Result<()> {
+    let output = match task.local() {
+        Some(local) if !opt.no_output => local
+            .source
+            .files_of_kind(SourceKind::Main)
+            .next()
+            .and_then(|main| main.target.as_deref())
+            .map(|bin| {
+                run_task(
+                    &local.package_name,
+                    bin,
+                    Duration::from_secs(opt.time_limit),
+                )
+            })
+            .transpose()?,
+        _ => None,
+    };
+
+    let section = render_task(task, &opt.repository_url, output.as_deref())?;
+
+    match opt.output_dir {
+        Some(ref dir) => {
+            fs::create_dir_all(dir)?;
+            let package_name = &task.local().unwrap().package_name;
+            fs::write(dir.join(format!("{}.wiki", package_name)), section)?;
+        }
+        None => println!("{}", section),
+    }
+
+    Ok(())
+}
+
+/// Builds the main binary of a local task in release mode, returning the path to the executable.
+fn build_task(package_name: &str, bin_name: &str) -> meta::Result<PathBuf> {
+    let build = Command::new(env!("CARGO"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["build", "--quiet", "--release", "--package", package_name])
+        .args([
+            "--bin",
+            bin_name,
+            "--message-format=json-render-diagnostics",
+        ])
+        .stderr(Stdio::inherit())
+        .output()?;
+    if !build.status.success() {
+        anyhow::bail!("could not build {}", package_name);
+    }
+
+    // Cargo reports the path of every executable it builds, wherever the target directory is.
+    String::from_utf8_lossy(&build.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| {
+            message["reason"] == "compiler-artifact" && message["target"]["name"] == bin_name
+        })
+        .find_map(|message| message["executable"].as_str().map(PathBuf::from))
+        .ok_or_else(|| anyhow::anyhow!("cargo did not report the executable of {}", bin_name))
+}
+
+/// Builds and runs the main binary of a local task, returning everything it printed to stdout.
+///
+/// The binary is run without any input. If it does not finish within the time limit, it is killed
+/// and the output it produced so far is returned.
+fn run_task(package_name: &str, bin_name: &str, time_limit: Duration) -> meta::Result<String> {
+    // Run the binary itself rather than through `cargo run`, so that killing it on a timeout
+    // doesn't leave it running without cargo.
+    let mut child = Command::new(build_task(package_name, bin_name)?)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    // Read the output on another thread, so the child can't block on a full pipe while we wait.
+    let mut stdout = child.stdout.take().unwrap();
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).map(|_| output)
+    });
+
+    let start = Instant::now();
+    while child.try_wait()?.is_none() {
+        if start.elapsed() > time_limit {
+            child.kill()?;
+            child.wait()?;
+            break;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    Ok(reader.join().unwrap()?)
+}
+
+/// Renders the complete Rust section of the wiki page for a local task.
+///
+/// The section links back to the task in the repository, contains the code of every source file,
+/// and, if provided, the output of running the task.
+fn render_task(task: &Task, repository_url: &str, output: Option<&str>) -> meta::Result<String> {
+    let local = task
+        .local()
+        .ok_or_else(|| anyhow::anyhow!("{} is not implemented locally", task.title()))?;
+
+    // Build the link from path components, so it uses forward slashes on every platform.
+    let path = task
+        .local_path()
+        .unwrap()
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(c) => c.to_str(),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let mut section = String::from("=={{header|Rust}}==\n");
+    section.push_str(&format!(
+        "This solution is also available in the [{}/{} rust-rosetta repository].\n\n",
+        repository_url.trim_end_matches('/'),
+        path
+    ));
+    section.push_str(&local.source.to_wiki()?);
+
+    if let Some(output) = output {
+        section.push_str(&format!(
+            "\n{{{{out}}}}\n<pre>\n{}\n</pre>\n",
+            output.trim_end()
+        ));
+    }
+
+    Ok(section)
+}
@@ -0,0 +1,344 @@
+//! Summary reports of how many tasks are implemented locally and on the wiki.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+use structopt::clap::arg_enum;
+use structopt::StructOpt;
+
+use meta::{Task, TaskIndex};
+
+/// The version of the JSON report format. Incremented whenever a field is removed or changes
+/// meaning, so dashboards can detect incompatible reports.
+const SCHEMA_VERSION: u32 = 1;
+
+/// The name used for the breakdown that includes every task.
+const ALL_TASKS: &str = "(all)";
+
+arg_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Format {
+        Json,
+        Csv,
+        Markdown
+    }
+}
+
+/// Options for the `report` subcommand.
+#[derive(Debug, StructOpt)]
+pub struct ReportOpt {
+    /// The format of the report
+    #[structopt(
+        long = "format",
+        possible_values = &Format::variants(),
+        case_insensitive = true,
+        default_value = "markdown"
+    )]
+    format: Format,
+
+    /// Also count the tasks in this wiki category, such as "Sorting Algorithms". May be repeated.
+    #[structopt(long = "category", number_of_values = 1)]
+    categories: Vec<String>,
+
+    /// Write the report to the provided filename instead of printing it
+    #[structopt(long = "output", parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+/// Where a task is implemented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Both,
+    LocalOnly,
+    RemoteOnly,
+    Unimplemented,
+}
+
+impl Status {
+    fn of(task: &Task) -> Status {
+        match (task.local().is_some(), task.remote_code().is_some()) {
+            (true, true) => Status::Both,
+            (true, false) => Status::LocalOnly,
+            (false, true) => Status::RemoteOnly,
+            (false, false) => Status::Unimplemented,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Status::Both => "both",
+            Status::LocalOnly => "local-only",
+            Status::RemoteOnly => "remote-only",
+            Status::Unimplemented => "unimplemented",
+        }
+    }
+}
+
+/// The information about a single task included in the report.
+#[derive(Debug, Clone)]
+struct Entry {
+    title: String,
+    url: String,
+    status: Status,
+    draft: bool,
+    path: Option<String>,
+}
+
+/// The number of tasks with each status in a set of tasks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Counts {
+    total: usize,
+    both: usize,
+    local_only: usize,
+    remote_only: usize,
+    unimplemented: usize,
+    draft: usize,
+}
+
+impl Counts {
+    fn add(&mut self, entry: &Entry) {
+        self.total += 1;
+
+        match entry.status {
+            Status::Both => self.both += 1,
+            Status::LocalOnly => self.local_only += 1,
+            Status::RemoteOnly => self.remote_only += 1,
+            Status::Unimplemented => self.unimplemented += 1,
+        }
+
+        if entry.draft {
+            self.draft += 1;
+        }
+    }
+
+    fn values(&self) -> [usize; 6] {
+        [
+            self.total,
+            self.both,
+            self.local_only,
+            self.remote_only,
+            self.unimplemented,
+            self.draft,
+        ]
+    }
+}
+
+/// The column names for the counts, in the order returned by [`Counts::values`].
+const COUNT_COLUMNS: [&str; 6] = [
+    "total",
+    "both",
+    "local_only",
+    "remote_only",
+    "unimplemented",
+    "draft",
+];
+
+/// A named breakdown of task counts. The first breakdown always includes every task.
+type Breakdown = (String, Counts);
+
+/// Renders the full report as JSON.
+fn to_json(breakdowns: &[Breakdown], entries: &[Entry]) -> String {
+    let counts_json = |counts: &Counts| -> Value {
+        COUNT_COLUMNS
+            .iter()
+            .zip(counts.values().iter())
+            .map(|(&column, &value)| (column.to_owned(), json!(value)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    };
+
+    let report = json!({
+        "schema_version": SCHEMA_VERSION,
+        "summary": counts_json(&breakdowns[0].1),
+        "categories": breakdowns[1..]
+            .iter()
+            .map(|(name, counts)| json!({ "name": name, "counts": counts_json(counts) }))
+            .collect::<Vec<_>>(),
+        "tasks": entries
+            .iter()
+            .map(|entry| json!({
+                "title": entry.title,
+                "url": entry.url,
+                "status": entry.status.name(),
+                "draft": entry.draft,
+                "path": entry.path,
+            }))
+            .collect::<Vec<_>>(),
+    });
+
+    serde_json::to_string_pretty(&report).unwrap()
+}
+
+/// Quotes a CSV field if it contains characters that would otherwise break the row.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Renders the counts as CSV, with one row per breakdown.
+fn to_csv(breakdowns: &[Breakdown]) -> String {
+    let mut csv = format!("category,{}\n", COUNT_COLUMNS.join(","));
+
+    for (name, counts) in breakdowns {
+        let values = counts
+            .values()
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>();
+        csv.push_str(&format!("{},{}\n", csv_field(name), values.join(",")));
+    }
+
+    csv
+}
+
+/// Renders the counts as a Markdown table, with one row per breakdown.
+fn to_markdown(breakdowns: &[Breakdown]) -> String {
+    let mut table = format!("| category | {} |\n", COUNT_COLUMNS.join(" | "));
+    table.push_str(&format!("|---{}|\n", "|--:".repeat(COUNT_COLUMNS.len())));
+
+    for (name, counts) in breakdowns {
+        let values = counts
+            .values()
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>();
+        table.push_str(&format!(
+            "| {} | {} |\n",
+            name.replace('|', "\\|"),
+            values.join(" | ")
+        ));
+    }
+
+    table
+}
+
+/// Generates a report of every task and writes it in the requested format.
+pub fn report(task_index: &TaskIndex, opt: &ReportOpt) -> meta::Result<()> {
+    let entries = task_index
+        .fetch_all_tasks()
+        .map(|task| {
+            let task = task?;
+            Ok(Entry {
+                title: task.title(),
+                url: task.url().to_string(),
+                status: Status::of(&task),
+                draft: task.is_draft(),
+                path: task
+                    .local_path()
+                    .map(|path| path.to_string_lossy().replace('\\', "/")),
+            })
+        })
+        .collect::<meta::Result<Vec<_>>>()?;
+
+    let mut breakdowns = vec![(ALL_TASKS.to_owned(), Counts::default())];
+    for entry in &entries {
+        breakdowns[0].1.add(entry);
+    }
+
+    for category in &opt.categories {
+        let members = task_index
+            .category_members(category)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        let mut counts = Counts::default();
+        for entry in entries
+            .iter()
+            .filter(|entry| members.contains(&entry.title))
+        {
+            counts.add(entry);
+        }
+        breakdowns.push((category.clone(), counts));
+    }
+
+    let report = match opt.format {
+        Format::Json => to_json(&breakdowns, &entries),
+        Format::Csv => to_csv(&breakdowns),
+        Format::Markdown => to_markdown(&breakdowns),
+    };
+
+    match opt.output {
+        Some(ref filename) => fs::write(filename, report)?,
+        None => print!("{}", report),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_csv, to_json, to_markdown, Breakdown, Counts, Entry, Status};
+
+    fn fixture() -> (Vec<Breakdown>, Vec<Entry>) {
+        let entries = vec![
+            Entry {
+                title: "FizzBuzz".to_owned(),
+                url: "http://rosettacode.org/wiki/FizzBuzz".to_owned(),
+                status: Status::Both,
+                draft: false,
+                path: Some("tasks/fizzbuzz".to_owned()),
+            },
+            Entry {
+                title: "K-d tree".to_owned(),
+                url: "http://rosettacode.org/wiki/K-d_tree".to_owned(),
+                status: Status::RemoteOnly,
+                draft: true,
+                path: None,
+            },
+        ];
+
+        let mut all = Counts::default();
+        let mut games = Counts::default();
+        for entry in &entries {
+            all.add(entry);
+        }
+        games.add(&entries[0]);
+
+        let breakdowns = vec![
+            ("(all)".to_owned(), all),
+            ("Games, \"puzzles\"".to_owned(), games),
+        ];
+
+        (breakdowns, entries)
+    }
+
+    #[test]
+    fn csv() {
+        let (breakdowns, _) = fixture();
+
+        assert_eq!(
+            to_csv(&breakdowns),
+            "category,total,both,local_only,remote_only,unimplemented,draft\n\
+             (all),2,1,0,1,0,1\n\
+             \"Games, \"\"puzzles\"\"\",1,1,0,0,0,0\n"
+        );
+    }
+
+    #[test]
+    fn markdown() {
+        let (breakdowns, _) = fixture();
+
+        assert_eq!(
+            to_markdown(&breakdowns).lines().nth(2),
+            Some("| (all) | 2 | 1 | 0 | 1 | 0 | 1 |")
+        );
+    }
+
+    #[test]
+    fn json() {
+        let (breakdowns, entries) = fixture();
+        let json: serde_json::Value =
+            serde_json::from_str(&to_json(&breakdowns, &entries)).unwrap();
+
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["summary"]["remote_only"], 1);
+        assert_eq!(json["categories"][0]["counts"]["both"], 1);
+        assert_eq!(json["tasks"][1]["status"], "remote-only");
+        assert_eq!(json["tasks"][1]["path"], serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use reqwest::Url;
+use structopt::StructOpt;
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+use walkdir::WalkDir;
+
+use meta::local::{self, LocalTask};
+use meta::workspace::{self, Member};
+
+/// Macros from `meta::test_utils` that expand to tests.
+const TEST_MACROS: &[&str] = &[
+    "test_sort!",
+    "test_stable_sort!",
+    "test_sort_floats!",
+    "test_sort_by!",
+    "test_hash!",
+    "test_cipher!",
+    "test_codec!",
+];
+
+/// How serious a problem is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    /// A convention that has legitimate exceptions, such as a task without tests.
+    Warning,
+
+    /// Something that is definitely wrong, such as a broken link to the wiki.
+    Error,
+}
+
+/// A problem found in the workspace.
+#[derive(Debug)]
+struct Problem {
+    severity: Severity,
+    location: String,
+    message: String,
+}
+
+/// Collects the problems found in the workspace.
+#[derive(Debug, Default)]
+struct Report {
+    problems: Vec<Problem>,
+}
+
+impl Report {
+    fn error(&mut self, location: impl Into<String>, message: impl Into<String>) {
+        self.problems.push(Problem {
+            severity: Severity::Error,
+            location: location.into(),
+            message: message.into(),
+        });
+    }
+
+    fn warning(&mut self, location: impl Into<String>, message: impl Into<String>) {
+        self.problems.push(Problem {
+            severity: Severity::Warning,
+            location: location.into(),
+            message: message.into(),
+        });
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.problems
+            .iter()
+            .filter(|problem| problem.severity == severity)
+            .count()
+    }
+}
+
+/// Checks that the comment above a workspace member links to the same task as the crate metadata.
+fn check_comment(report: &mut Report, member: &Member, task: &LocalTask) {
+    let location = format!("Cargo.toml:{}", member.line);
+
+    let comment_url = match member.comment_url {
+        Some(ref url) => url,
+        None => {
+            report.error(
+                location,
+                format!("{} has no comment linking to the wiki", member.path),
+            );
+            return;
+        }
+    };
+
+    match Url::parse(comment_url)
+        .map_err(From::from)
+        .and_then(|url| local::task_title(&url))
+    {
+        Ok(ref title) if *title == task.title => {}
+        Ok(title) => report.error(
+            location,
+            format!(
+                "comment above {} links to \"{}\", but the crate metadata links to \"{}\"",
+                member.path, title, task.title
+            ),
+        ),
+        Err(err) => report.error(location, format!("{:#}", err)),
+    }
+}
+
+/// Checks that a task is in the directory that its title suggests.
+///
+/// Directories are allowed to be shortened versions of long titles, and tasks with multiple
+/// solutions may have a subdirectory for each solution.
+fn check_directory(report: &mut Report, member: &Member, task: &LocalTask) {
+    let expected = local::task_directory(&task.title);
+    let actual = member.path.trim_start_matches("tasks/");
+
+    let is_prefix = |long: &str, short: &str| {
+        long.strip_prefix(short)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '-']))
+    };
+
+    if !is_prefix(actual, &expected) && !is_prefix(&expected, actual) {
+        report.warning(
+            &member.path,
+            format!(
+                "directory doesn't match the title \"{}\" (expected tasks/{})",
+                task.title, expected
+            ),
+        );
+    }
+}
+
+/// Checks that a task has at least one test.
+fn check_tests(report: &mut Report, member: &Member, task: &LocalTask) {
+    let has_tests = task.source.files().iter().any(|file| {
+        fs::read_to_string(&file.path).is_ok_and(|code| {
+            code.contains("#[test]") || TEST_MACROS.iter().any(|m| code.contains(m))
+        })
+    });
+
+    if !has_tests {
+        report.warning(&member.path, "crate has no tests");
+    }
+}
+
+/// Checks that every crate in the `tasks` directory is a member of the workspace.
+fn check_orphans(report: &mut Report, workspace_root: &Path, members: &[Member]) {
+    let member_paths = members
+        .iter()
+        .map(|member| workspace_root.join(&member.path))
+        .collect::<HashSet<_>>();
+
+    let walker = WalkDir::new(workspace_root.join("tasks"))
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != "target");
+
+    for entry in walker.flatten() {
+        if entry.file_name() != "Cargo.toml" {
+            continue;
+        }
+
+        let crate_dir = entry.path().parent().unwrap();
+        if !member_paths.contains(crate_dir) {
+            report.error(
+                crate_dir
+                    .strip_prefix(workspace_root)
+                    .unwrap()
+                    .display()
+                    .to_string(),
+                "crate is not a member of the workspace",
+            );
+        }
+    }
+}
+
+/// Runs every check on the workspace.
+fn lint(workspace_root: &Path) -> meta::Result<Report> {
+    let manifest_path = workspace_root.join("Cargo.toml");
+    let members = workspace::read_members(&manifest_path)?;
+
+    let tasks = local::try_parse_tasks(&manifest_path)?
+        .into_iter()
+        .map(|(manifest_path, task)| (manifest_path.parent().unwrap().to_owned(), task))
+        .collect::<HashMap<PathBuf, _>>();
+
+    let mut report = Report::default();
+
+    for member in &members {
+        // Crates that aren't tasks, such as proc-macro helpers, aren't checked.
+        let task = match tasks.get(&workspace_root.join(&member.path)) {
+            Some(Ok(task)) => task,
+            Some(Err(err)) => {
+                report.error(&member.path, format!("{:#}", err));
+                continue;
+            }
+            None => continue,
+        };
+
+        check_comment(&mut report, member, task);
+        check_directory(&mut report, member, task);
+        check_tests(&mut report, member, task);
+    }
+
+    check_orphans(&mut report, workspace_root, &members);
+
+    Ok(report)
+}
+
+/// Check that every task crate in the workspace is consistent.
+///
+/// Verifies that each task links to a valid Rosetta Code URL in its metadata, that the comment
+/// above each member in the root `Cargo.toml` links to the same task, that directories are named
+/// after their tasks, that each task has tests, and that every crate in the `tasks` directory is a
+/// member of the workspace.
+///
+/// Exits with a non-zero status if any errors are found.
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// Treat warnings, such as tasks without tests, as errors
+    #[structopt(long = "deny-warnings")]
+    deny_warnings: bool,
+
+    /// Only print errors
+    #[structopt(long = "quiet", short = "q")]
+    quiet: bool,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+
+    let mut t = StandardStream::stderr(ColorChoice::Auto);
+
+    let report = lint(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+
+    for problem in &report.problems {
+        let (label, color) = match problem.severity {
+            Severity::Error => ("error", Color::Red),
+            Severity::Warning if opt.quiet => continue,
+            Severity::Warning => ("warning", Color::Yellow),
+        };
+
+        t.set_color(ColorSpec::new().set_fg(Some(color)).set_bold(true))
+            .unwrap();
+        write!(t, "{}", label).unwrap();
+        t.reset().unwrap();
+        writeln!(t, ": {}: {}", problem.location, problem.message).unwrap();
+    }
+
+    let errors = report.count(Severity::Error);
+    let warnings = report.count(Severity::Warning);
+    writeln!(t, "{} errors, {} warnings", errors, warnings).unwrap();
+
+    if errors > 0 || (opt.deny_warnings && warnings > 0) {
+        process::exit(1);
+    }
+}
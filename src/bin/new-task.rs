@@ -0,0 +1,99 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use structopt::StructOpt;
+
+use mediawiki::Client;
+use meta::local;
+use meta::workspace;
+
+/// The code for `src/main.rs` if it isn't seeded from the wiki.
+const MAIN_TEMPLATE: &str = "fn main() {}\n";
+
+/// Create a new task crate from the title of a task on the wiki.
+///
+/// The crate is created in the conventional directory for the task, with a link to the wiki in its
+/// metadata, and added to the workspace in the root `Cargo.toml` in sorted order.
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The title of the task, such as "K-d tree"
+    title: String,
+
+    /// Seed `src/main.rs` with the first Rust code block of the task on the wiki, if there is one
+    #[structopt(long = "seed")]
+    seed: bool,
+}
+
+/// Returns the name of the crate in the given directory, relative to `tasks`.
+///
+/// Crates in subdirectories are named after the whole path, such as `hello-world-text` for
+/// `hello-world/text`.
+fn crate_name(directory: &str) -> String {
+    directory.replace('/', "-")
+}
+
+/// Returns the contents of the `Cargo.toml` of a new task crate.
+fn crate_manifest(name: &str, url: &str) -> String {
+    format!(
+        r#"[package]
+name = "{}"
+version = "0.1.0"
+edition = "2021"
+
+[package.metadata.rosettacode]
+url = "{}"
+"#,
+        name, url
+    )
+}
+
+fn new_task(workspace_root: &Path, opt: &Opt) -> meta::Result<()> {
+    let mut title = opt.title.clone();
+    let mut code = String::from(MAIN_TEMPLATE);
+
+    if opt.seed {
+        let task = meta::fetch_remote_task(&Client::default(), &title)?
+            .ok_or_else(|| anyhow!("there is no task called \"{}\" on the wiki", title))?;
+
+        // Use the title that the wiki uses, in case it was normalized or redirected.
+        title = task.title();
+
+        match task.source() {
+            Some(remote_code) => code = format!("{}\n", remote_code.trim()),
+            None => eprintln!("\"{}\" has no Rust solution on the wiki", title),
+        }
+    }
+
+    let directory = local::task_directory(&title);
+    let member_path = format!("tasks/{}", directory);
+    let url = format!("http://rosettacode.org/wiki/{}", meta::encode_title(&title));
+
+    let crate_dir = workspace_root.join(&member_path);
+    if crate_dir.exists() {
+        return Err(anyhow!("{} already exists", crate_dir.display()));
+    }
+
+    let manifest_path = workspace_root.join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path)?;
+    let manifest = workspace::insert_member(&manifest, &member_path, &url)
+        .with_context(|| format!("could not add {} to the workspace", member_path))?;
+
+    fs::create_dir_all(crate_dir.join("src"))?;
+    fs::write(
+        crate_dir.join("Cargo.toml"),
+        crate_manifest(&crate_name(&directory), &url),
+    )?;
+    fs::write(crate_dir.join("src/main.rs"), code)?;
+    fs::write(&manifest_path, manifest)?;
+
+    println!("Created {} for {}", member_path, url);
+
+    Ok(())
+}
+
+fn main() -> meta::Result<()> {
+    let opt = Opt::from_args();
+
+    new_task(Path::new(env!("CARGO_MANIFEST_DIR")), &opt)
+}
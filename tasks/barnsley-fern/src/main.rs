@@ -1,12 +1,13 @@
+extern crate bitmap;
 extern crate rand;
-extern crate raster;
 
+use bitmap::{Canvas, Color, Image};
 use rand::Rng;
 
 const MAX_ITER: u32 = 200_000;
-const HEIGHT: i32 = 640;
-const WIDTH: i32 = 640;
-const OUTPUT: &str = "fractal.png";
+const HEIGHT: usize = 640;
+const WIDTH: usize = 640;
+const OUTPUT: &str = "fractal.ppm";
 
 fn transform(x: f64, y: f64) -> (f64, f64) {
     let cx: f64;
@@ -35,9 +36,13 @@ fn transform(x: f64, y: f64) -> (f64, f64) {
 }
 
 fn main() {
-    let mut image = raster::Image::blank(WIDTH, HEIGHT);
+    let mut image = Image::new(WIDTH, HEIGHT);
 
-    raster::editor::fill(&mut image, raster::Color::white()).unwrap();
+    image.fill(Color {
+        red: 255,
+        green: 255,
+        blue: 255,
+    });
 
     let mut x = 0.0;
     let mut y = 0.0;
@@ -45,15 +50,20 @@ fn main() {
     for _ in 0..MAX_ITER {
         let (cx, cy) = transform(x, y);
 
-        let target_x = (WIDTH as f64 / 2.0 + cx * (WIDTH as f64) / 11.0).round() as i32;
-        let target_y = (HEIGHT as f64 - cy * (HEIGHT as f64) / 11.0).round() as i32;
-        let color = raster::Color::rgb(50, 205, 50);
+        let target_x = (WIDTH as f64 / 2.0 + cx * (WIDTH as f64) / 11.0).round() as isize;
+        let target_y = (HEIGHT as f64 - cy * (HEIGHT as f64) / 11.0).round() as isize;
+        let color = Color {
+            red: 50,
+            green: 205,
+            blue: 50,
+        };
 
-        image.set_pixel(target_x, target_y, color).unwrap();
+        // Points that land off the edge of the image are skipped
+        image.put(target_x, target_y, color);
 
         x = cx;
         y = cy;
     }
 
-    raster::save(&image, OUTPUT).unwrap();
+    image.write_ppm(OUTPUT).unwrap();
 }
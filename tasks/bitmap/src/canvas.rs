@@ -0,0 +1,97 @@
+use std::fmt;
+
+use super::{Color, Rgba};
+
+/// A value that a [`Canvas`] can hold at each point.
+pub trait Pixel: Copy + PartialEq + fmt::Debug {
+    /// Mixes in `other` with the weight `t`, giving `self` when `t` is 0 and `other` when it is 1.
+    #[must_use]
+    fn mix(self, other: Self, t: f64) -> Self;
+}
+
+fn mix_channel(a: u16, b: u16, t: f64) -> f64 {
+    (f64::from(a) + (f64::from(b) - f64::from(a)) * t.clamp(0.0, 1.0)).round()
+}
+
+/// A grayscale sample.
+impl Pixel for u16 {
+    fn mix(self, other: Self, t: f64) -> Self {
+        mix_channel(self, other, t) as u16
+    }
+}
+
+impl Pixel for Color {
+    fn mix(self, other: Self, t: f64) -> Self {
+        let mix = |a: u8, b: u8| mix_channel(a.into(), b.into(), t) as u8;
+        Color {
+            red: mix(self.red, other.red),
+            green: mix(self.green, other.green),
+            blue: mix(self.blue, other.blue),
+        }
+    }
+}
+
+impl Pixel for Rgba {
+    fn mix(self, other: Self, t: f64) -> Self {
+        let mix = |a: u8, b: u8| mix_channel(a.into(), b.into(), t) as u8;
+        Rgba {
+            red: mix(self.red, other.red),
+            green: mix(self.green, other.green),
+            blue: mix(self.blue, other.blue),
+            alpha: mix(self.alpha, other.alpha),
+        }
+    }
+}
+
+/// A rectangle of pixels, stored in rows from the top, each from left to right.
+///
+/// Only the dimensions and the storage need to be implemented. The provided methods take signed
+/// coordinates and ignore points that are off the canvas, so that shapes can be drawn partly
+/// outside of it.
+pub trait Canvas {
+    type Pixel: Pixel;
+
+    fn width(&self) -> usize;
+
+    fn height(&self) -> usize;
+
+    fn pixels(&self) -> &[Self::Pixel];
+
+    fn pixels_mut(&mut self) -> &mut [Self::Pixel];
+
+    /// Whether the point is on the canvas.
+    fn contains(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width() && (y as usize) < self.height()
+    }
+
+    /// The pixel at a point, or `None` if it is off the canvas.
+    fn get(&self, x: isize, y: isize) -> Option<Self::Pixel> {
+        if self.contains(x, y) {
+            Some(self.pixels()[x as usize + y as usize * self.width()])
+        } else {
+            None
+        }
+    }
+
+    /// Sets the pixel at a point, if it is on the canvas.
+    fn put(&mut self, x: isize, y: isize, pixel: Self::Pixel) {
+        if self.contains(x, y) {
+            let width = self.width();
+            self.pixels_mut()[x as usize + y as usize * width] = pixel;
+        }
+    }
+
+    /// Paints a pixel over the one at a point with the opacity `alpha`, from 0 for none to 1 for
+    /// full.
+    fn blend(&mut self, x: isize, y: isize, pixel: Self::Pixel, alpha: f64) {
+        if let Some(old) = self.get(x, y) {
+            self.put(x, y, old.mix(pixel, alpha));
+        }
+    }
+
+    fn fill(&mut self, pixel: Self::Pixel) {
+        for elem in self.pixels_mut() {
+            *elem = pixel;
+        }
+    }
+}
@@ -0,0 +1,358 @@
+use std::mem;
+
+use super::Canvas;
+
+/// Drawing on a [`Canvas`]: the [Bresenham's line], [Xiaolin Wu's line], [midpoint circle],
+/// [quadratic] and [cubic] Bézier curve and [flood fill] tasks, and filled polygons.
+///
+/// Points are the coordinates of pixels, and parts of shapes that are off the canvas are clipped.
+///
+/// [Bresenham's line]: http://rosettacode.org/wiki/Bitmap/Bresenham%27s_line_algorithm
+/// [Xiaolin Wu's line]: http://rosettacode.org/wiki/Xiaolin_Wu%27s_line_algorithm
+/// [midpoint circle]: http://rosettacode.org/wiki/Bitmap/Midpoint_circle_algorithm
+/// [quadratic]: http://rosettacode.org/wiki/Bitmap/B%C3%A9zier_curves/Quadratic
+/// [cubic]: http://rosettacode.org/wiki/Bitmap/B%C3%A9zier_curves/Cubic
+/// [flood fill]: http://rosettacode.org/wiki/Bitmap/Flood_fill
+pub trait Draw: Canvas {
+    /// Draws a line with Bresenham's algorithm, including both ends.
+    fn line(&mut self, from: (isize, isize), to: (isize, isize), pixel: Self::Pixel) {
+        let (mut x, mut y) = from;
+        let (dx, step_x) = ((to.0 - x).abs(), if x < to.0 { 1 } else { -1 });
+        let (dy, step_y) = (-(to.1 - y).abs(), if y < to.1 { 1 } else { -1 });
+        let mut error = dx + dy;
+
+        loop {
+            self.put(x, y, pixel);
+            if (x, y) == to {
+                break;
+            }
+
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    /// Draws an anti-aliased line with Xiaolin Wu's algorithm, blending the pixel into the two
+    /// pixels nearest to each point of the line in proportion to how close it is to them.
+    fn line_aa(&mut self, from: (f64, f64), to: (f64, f64), pixel: Self::Pixel) {
+        let ((mut x0, mut y0), (mut x1, mut y1)) = (from, to);
+        let steep = (y1 - y0).abs() > (x1 - x0).abs();
+        if steep {
+            mem::swap(&mut x0, &mut y0);
+            mem::swap(&mut x1, &mut y1);
+        }
+        if x0 > x1 {
+            mem::swap(&mut x0, &mut x1);
+            mem::swap(&mut y0, &mut y1);
+        }
+
+        let gradient = if x1 == x0 { 1.0 } else { (y1 - y0) / (x1 - x0) };
+        let plot = |canvas: &mut Self, x: f64, y: f64, coverage: f64| {
+            let (x, y) = if steep { (y, x) } else { (x, y) };
+            canvas.blend(x as isize, y as isize, pixel, coverage);
+        };
+
+        // The ends only partly cover the pixels they are in.
+        let end = |canvas: &mut Self, x: f64, y: f64, gap: f64| {
+            let x_end = round(x);
+            let y_end = y + gradient * (x_end - x);
+            plot(canvas, x_end, y_end.floor(), (1.0 - fract(y_end)) * gap);
+            plot(canvas, x_end, y_end.floor() + 1.0, fract(y_end) * gap);
+            x_end
+        };
+        let first = end(self, x0, y0, 1.0 - fract(x0 + 0.5));
+        let last = end(self, x1, y1, fract(x1 + 0.5));
+
+        let mut y = y0 + gradient * (first - x0) + gradient;
+        let mut x = first + 1.0;
+        while x < last {
+            plot(self, x, y.floor(), 1.0 - fract(y));
+            plot(self, x, y.floor() + 1.0, fract(y));
+            y += gradient;
+            x += 1.0;
+        }
+    }
+
+    /// Draws the outline of a circle with the midpoint algorithm.
+    fn circle(&mut self, center: (isize, isize), radius: isize, pixel: Self::Pixel) {
+        let (cx, cy) = center;
+        let (mut x, mut y) = (radius, 0);
+        let mut error = 1 - radius;
+
+        // Each point found in the first octant is mirrored into the other seven.
+        while x >= y {
+            for &(dx, dy) in &[
+                (x, y),
+                (y, x),
+                (-y, x),
+                (-x, y),
+                (-x, -y),
+                (-y, -x),
+                (y, -x),
+                (x, -y),
+            ] {
+                self.put(cx + dx, cy + dy, pixel);
+            }
+
+            y += 1;
+            if error < 0 {
+                error += 2 * y + 1;
+            } else {
+                x -= 1;
+                error += 2 * (y - x) + 1;
+            }
+        }
+    }
+
+    /// Draws a quadratic Bézier curve from `from` to `to`, pulled towards `control`.
+    fn quadratic_bezier(
+        &mut self,
+        from: (isize, isize),
+        control: (isize, isize),
+        to: (isize, isize),
+        pixel: Self::Pixel,
+    ) {
+        bezier(self, &[from, control, to], pixel);
+    }
+
+    /// Draws a cubic Bézier curve from `from` to `to`, leaving in the direction of `controls.0`
+    /// and arriving from that of `controls.1`.
+    fn cubic_bezier(
+        &mut self,
+        from: (isize, isize),
+        controls: ((isize, isize), (isize, isize)),
+        to: (isize, isize),
+        pixel: Self::Pixel,
+    ) {
+        bezier(self, &[from, controls.0, controls.1, to], pixel);
+    }
+
+    /// Fills a polygon with the even-odd rule, one row at a time.
+    ///
+    /// A pixel is filled if its point is inside the polygon. Points on the top or left edges count
+    /// as inside, and points on the bottom or right ones don't, so polygons that share an edge
+    /// don't overlap.
+    fn fill_polygon(&mut self, vertices: &[(isize, isize)], pixel: Self::Pixel) {
+        let ys = vertices.iter().map(|&(_, y)| y);
+        let (top, bottom) = match (ys.clone().min(), ys.max()) {
+            (Some(top), Some(bottom)) => (top.max(0), bottom.min(self.height() as isize - 1)),
+            _ => return,
+        };
+
+        let edges = vertices.iter().zip(vertices.iter().cycle().skip(1));
+        let mut crossings = vec![];
+        for y in top..=bottom {
+            crossings.clear();
+            for (&(x0, y0), &(x1, y1)) in edges.clone() {
+                if (y0 <= y) != (y1 <= y) {
+                    let t = (y - y0) as f64 / (y1 - y0) as f64;
+                    crossings.push(x0 as f64 + t * (x1 - x0) as f64);
+                }
+            }
+            crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            for span in crossings.chunks(2) {
+                let start = (span[0].ceil() as isize).max(0);
+                let end = (span[1].ceil() as isize).min(self.width() as isize);
+                for x in start..end {
+                    self.put(x, y, pixel);
+                }
+            }
+        }
+    }
+
+    /// Replaces the pixels of the area around `start` that are the same as it, and connected to it
+    /// horizontally or vertically, with `pixel`.
+    fn flood_fill(&mut self, start: (isize, isize), pixel: Self::Pixel) {
+        let target = match self.get(start.0, start.1) {
+            Some(target) if target != pixel => target,
+            _ => return,
+        };
+
+        // Fills whole runs of a row at once, and remembers where runs start in the rows above and
+        // below.
+        let mut stack = vec![start];
+        while let Some((x, y)) = stack.pop() {
+            if self.get(x, y) != Some(target) {
+                continue;
+            }
+
+            let mut left = x;
+            while self.get(left - 1, y) == Some(target) {
+                left -= 1;
+            }
+            let mut right = x;
+            while self.get(right + 1, y) == Some(target) {
+                right += 1;
+            }
+
+            for x in left..=right {
+                self.put(x, y, pixel);
+            }
+            for &row in &[y - 1, y + 1] {
+                for x in left..=right {
+                    let starts_run = x == left || self.get(x - 1, row) != Some(target);
+                    if starts_run && self.get(x, row) == Some(target) {
+                        stack.push((x, row));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<C: Canvas + ?Sized> Draw for C {}
+
+/// The fractional part of a number, which is always positive.
+fn fract(x: f64) -> f64 {
+    x - x.floor()
+}
+
+/// Rounds halves up, rather than away from zero.
+fn round(x: f64) -> f64 {
+    (x + 0.5).floor()
+}
+
+/// Draws a Bézier curve as a chain of lines, with de Casteljau's algorithm. There are enough
+/// lines that none of them is much longer than a few pixels.
+fn bezier<C: Canvas + ?Sized>(canvas: &mut C, points: &[(isize, isize)], pixel: C::Pixel) {
+    let points = points
+        .iter()
+        .map(|&(x, y)| (x as f64, y as f64))
+        .collect::<Vec<_>>();
+    let length = points
+        .windows(2)
+        .map(|pair| (pair[1].0 - pair[0].0).hypot(pair[1].1 - pair[0].1))
+        .sum::<f64>();
+    let segments = (length / 4.0).ceil().max(1.0) as usize;
+
+    let mut scratch = points.clone();
+    let mut from = (points[0].0 as isize, points[0].1 as isize);
+    for i in 1..=segments {
+        let t = i as f64 / segments as f64;
+        scratch.copy_from_slice(&points);
+        for len in (1..scratch.len()).rev() {
+            for j in 0..len {
+                scratch[j].0 += (scratch[j + 1].0 - scratch[j].0) * t;
+                scratch[j].1 += (scratch[j + 1].1 - scratch[j].1) * t;
+            }
+        }
+
+        let to = (round(scratch[0].0) as isize, round(scratch[0].1) as isize);
+        canvas.line(from, to, pixel);
+        from = to;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Draw;
+    use std::env;
+    use {Color, Image, ImageFormat};
+
+    const WHITE: Color = Color {
+        red: 255,
+        green: 255,
+        blue: 255,
+    };
+    const RED: Color = Color {
+        red: 255,
+        green: 0,
+        blue: 0,
+    };
+    const BLUE: Color = Color {
+        red: 0,
+        green: 0,
+        blue: 255,
+    };
+
+    /// Compares an image with the one in a file in `resources`. If they differ, or the file is
+    /// missing, the image is written to the temporary directory, to be looked at and copied over
+    /// the file if it is right.
+    fn assert_golden(image: &mut Image, name: &str) {
+        let fixture = format!("{}/resources/{}", env!("CARGO_MANIFEST_DIR"), name);
+        let matches = Image::read_ppm(&fixture).is_ok_and(|expected| {
+            (expected.width, expected.height) == (image.width, image.height)
+                && expected.data == image.data
+        });
+
+        if !matches {
+            let actual = env::temp_dir().join(name);
+            let actual = actual.to_str().unwrap();
+            image.format = ImageFormat::P3;
+            image.write_ppm(actual).unwrap();
+            panic!("{} differs from {}", actual, fixture);
+        }
+    }
+
+    #[test]
+    fn lines() {
+        let mut image = Image::new(16, 16);
+        for &end in &[
+            (15, 8),
+            (15, 13),
+            (12, 15),
+            (8, 15),
+            (3, 15),
+            (0, 12),
+            (0, 4),
+            (5, 0),
+            (14, 0),
+        ] {
+            image.line((8, 8), end, WHITE);
+        }
+        image.line((-4, 14), (20, 2), RED);
+
+        assert_golden(&mut image, "lines.ppm");
+    }
+
+    #[test]
+    fn antialiased_lines() {
+        let mut image = Image::new(16, 10);
+        image.line_aa((1.0, 1.0), (14.0, 7.0), WHITE);
+        image.line_aa((5.0, 1.0), (2.5, 8.5), RED);
+
+        assert_golden(&mut image, "antialiased-lines.ppm");
+    }
+
+    #[test]
+    fn circles() {
+        let mut image = Image::new(16, 16);
+        image.circle((7, 7), 6, WHITE);
+        image.circle((7, 7), 0, WHITE);
+        image.circle((15, 15), 4, RED);
+
+        assert_golden(&mut image, "circles.ppm");
+    }
+
+    #[test]
+    fn bezier_curves() {
+        let mut image = Image::new(24, 16);
+        image.quadratic_bezier((1, 14), (12, -10), (22, 14), WHITE);
+        image.cubic_bezier((1, 1), ((8, 24), (16, -8)), (22, 8), RED);
+
+        assert_golden(&mut image, "bezier-curves.ppm");
+    }
+
+    #[test]
+    fn fills() {
+        let mut image = Image::new(16, 16);
+        // a concave arrow, and a triangle that shares an edge with it
+        image.fill_polygon(&[(1, 1), (8, 4), (1, 8), (4, 4)], WHITE);
+        image.fill_polygon(&[(1, 8), (8, 4), (8, 8)], RED);
+
+        // a ring, whose inside and outside are filled separately
+        image.circle((11, 11), 4, WHITE);
+        image.flood_fill((11, 11), RED);
+        image.flood_fill((0, 15), BLUE);
+
+        assert_golden(&mut image, "fills.ppm");
+    }
+}
@@ -1,14 +1,24 @@
 extern crate nom;
 extern crate thiserror;
+mod canvas;
+mod draw;
+pub mod ops;
 mod parser;
+mod pnm;
 
+use std::convert::TryFrom;
 use std::default::Default;
 use std::fmt;
-use std::io::{BufWriter, Error, Write};
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Write};
 use std::ops::{Index, IndexMut};
-use std::{fs::File, io::Read};
+use std::path::Path;
 use thiserror::Error;
 
+pub use canvas::{Canvas, Pixel};
+pub use draw::Draw;
+pub use pnm::{Decoder, Header};
+
 #[derive(Copy, Clone, Default, PartialEq, Debug)]
 pub struct Color {
     pub red: u8,
@@ -16,32 +26,80 @@ pub struct Color {
     pub blue: u8,
 }
 
+/// A color with an alpha channel, from 0 for transparent to 255 for opaque.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct Rgba {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl Rgba {
+    /// Composites this color over an opaque one.
+    #[must_use]
+    pub fn over(self, background: Color) -> Color {
+        let color = Color {
+            red: self.red,
+            green: self.green,
+            blue: self.blue,
+        };
+        background.mix(color, f64::from(self.alpha) / 255.0)
+    }
+}
+
+impl From<Color> for Rgba {
+    fn from(color: Color) -> Self {
+        Rgba {
+            red: color.red,
+            green: color.green,
+            blue: color.blue,
+            alpha: 255,
+        }
+    }
+}
+
+/// The Netpbm formats: P1 and P4 are bitmaps (PBM), P2 and P5 grayscale (PGM), and P3 and P6
+/// color (PPM). The first of each pair is written in ASCII decimal, the second in binary.
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum ImageFormat {
+    P1,
+    P2,
     P3,
+    P4,
+    P5,
     P6,
 }
 
-impl From<&str> for ImageFormat {
-    fn from(i: &str) -> Self {
+impl ImageFormat {
+    /// The number of samples in each pixel.
+    fn channels(self) -> usize {
+        match self {
+            ImageFormat::P3 | ImageFormat::P6 => 3,
+            _ => 1,
+        }
+    }
+}
+
+impl TryFrom<&str> for ImageFormat {
+    type Error = ImageError;
+
+    fn try_from(i: &str) -> Result<Self, Self::Error> {
         match i.to_lowercase().as_str() {
-            "p3" => ImageFormat::P3,
-            "p6" => ImageFormat::P6,
-            _ => unimplemented!("no other formats supported"),
+            "p1" => Ok(ImageFormat::P1),
+            "p2" => Ok(ImageFormat::P2),
+            "p3" => Ok(ImageFormat::P3),
+            "p4" => Ok(ImageFormat::P4),
+            "p5" => Ok(ImageFormat::P5),
+            "p6" => Ok(ImageFormat::P6),
+            _ => Err(ImageError::UnsupportedFormat(i.to_string())),
         }
     }
 }
 
 impl fmt::Display for ImageFormat {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            ImageFormat::P3 => {
-                write!(f, "P3")
-            }
-            ImageFormat::P6 => {
-                write!(f, "P6")
-            }
-        }
+        write!(f, "{:?}", self)
     }
 }
 
@@ -59,9 +117,19 @@ pub enum ImageError {
     InvalidMaxColor,
     #[error("File is incomplete")]
     IncompleteFile,
+    #[error("Unsupported format: {0}")]
+    UnsupportedFormat(String),
     #[error("unknown data store error")]
     Unknown,
 }
+
+/// Scales a sample from the range `0..=from` to `0..=to`, rounding to the nearest value.
+fn rescale(sample: u16, from: u16, to: u16) -> u16 {
+    let (sample, from, to) = (u32::from(sample), u32::from(from), u32::from(to));
+    ((sample * to + from / 2) / from) as u16
+}
+
+/// A color image, which is read and written as a PPM.
 pub struct Image {
     pub format: ImageFormat,
     pub width: usize,
@@ -86,99 +154,281 @@ impl Image {
         }
     }
 
+    /// Writes the image to a stream, after which another one may be written.
+    ///
     /// # Errors
     ///
-    /// Will return `Error` if `filename` does not exist or the user does not have
-    /// permission to write to it, or the write operation fails.
-    pub fn write_ppm(&self, filename: &str) -> Result<(), Error> {
-        let file = File::create(filename)?;
-        let mut writer = BufWriter::new(file);
-        writeln!(&mut writer, "{}", self.format)?;
-        writeln!(&mut writer, "{} {} 255", self.width, self.height)?;
-        match self.format {
-            ImageFormat::P3 => {
-                writer.write_all(
-                    self.data
-                        .iter()
-                        .flat_map(|color| {
-                            vec![
-                                color.red.to_string(),
-                                color.green.to_string(),
-                                color.blue.to_string(),
-                            ]
-                        })
-                        .collect::<Vec<String>>()
-                        .join(" ")
-                        .as_bytes(),
-                )?;
-            }
-            ImageFormat::P6 => {
-                writer.write_all(
-                    &self
-                        .data
-                        .iter()
-                        .flat_map(|color| vec![color.red, color.green, color.blue])
-                        .collect::<Vec<u8>>(),
-                )?;
-            }
+    /// Will return `Error` if the write operation fails, or the format is not
+    /// P3 or P6 PPM.
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), Error> {
+        if self.format.channels() != 3 {
+            let msg = format!("{} is not a PPM format", self.format);
+            return Err(Error::new(ErrorKind::InvalidInput, msg));
+        }
+
+        let header = Header {
+            format: self.format,
+            width: self.width,
+            height: self.height,
+            maxval: 255,
+        };
+        let samples = self
+            .data
+            .iter()
+            .flat_map(|color| [color.red, color.green, color.blue])
+            .map(u16::from)
+            .collect::<Vec<_>>();
+        pnm::write(writer, &header, &samples)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Error` if `path` does not exist or the user does not have
+    /// permission to write to it, or the write operation fails, or the format is not
+    /// P3 or P6 PPM.
+    pub fn write_ppm<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        self.write(File::create(path)?)
+    }
+
+    /// Reads the next image of a stream, or returns `None` at its end. Samples of a maxval other
+    /// than 255, including 16-bit ones, are scaled to 8 bits.
+    ///
+    /// # Errors
+    ///
+    /// Will return `ImageError` if the stream can't be read, or does not match the
+    /// specification, or the image is not a P3 or P6 PPM
+    pub fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Option<Image>, ImageError> {
+        let header = match decoder.next_header()? {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+        if header.format.channels() != 3 {
+            return Err(ImageError::UnsupportedFormat(header.format.to_string()));
         }
-        Ok(())
+
+        let channel = |sample| rescale(sample, header.maxval, 255) as u8;
+        let mut data = Vec::with_capacity(header.width * header.height);
+        while let Some(row) = decoder.read_row()? {
+            data.extend(row.chunks(3).map(|rgb| Color {
+                red: channel(rgb[0]),
+                green: channel(rgb[1]),
+                blue: channel(rgb[2]),
+            }));
+        }
+
+        Ok(Some(Image {
+            format: header.format,
+            width: header.width,
+            height: header.height,
+            data,
+        }))
     }
 
-    /// # Panics
+    /// Reads the first image of a stream.
+    ///
+    /// # Errors
     ///
-    /// Panics if the format is not P6 or P3 PPM
+    /// Will return `ImageError` if the stream can't be read or is empty, or does not match the
+    /// specification, or the image is not a P3 or P6 PPM
+    pub fn read<R: Read>(reader: R) -> Result<Image, ImageError> {
+        Image::decode(&mut Decoder::new(reader))?.ok_or(ImageError::IncompleteFile)
+    }
+
     /// # Errors
     ///
-    /// Will return `Error` if `filename` does not exist or the user does not have
+    /// Will return `ImageError` if `path` does not exist or the user does not have
     /// permission to read it or the read operation fails, or the file format does not
-    /// match the specification
-    pub fn read_ppm(filename: &str) -> Result<Image, ImageError> {
-        let mut file = File::open(filename).map_err(|_| ImageError::FileNotFound)?;
-        let mut data: Vec<u8> = Vec::new();
-        file.read_to_end(&mut data)
-            .map_err(|_| ImageError::FileNotReadable)?;
-
-        let (i, format) = parser::parse_version(&data).map_err(|_| ImageError::InvalidHeader)?;
-        let (i, (width, height, max_color)) =
-            parser::parse_image_attributes(i).map_err(|_| ImageError::InvalidHeader)?;
-
-        if max_color != 255 {
-            return Err(ImageError::InvalidMaxColor);
+    /// match the specification, or it is not a PPM
+    pub fn read_ppm<P: AsRef<Path>>(path: P) -> Result<Image, ImageError> {
+        let file = File::open(path).map_err(|_| ImageError::FileNotFound)?;
+        Image::read(file)
+    }
+}
+
+/// A grayscale image, whose samples run from 0 for black up to `maxval` for white. It is read and
+/// written as a PGM, or as a PBM with a maxval of 1.
+pub struct GrayImage {
+    pub format: ImageFormat,
+    pub width: usize,
+    pub height: usize,
+    /// The sample value of white, up to 65535. Files with a maxval over 255 take two bytes per
+    /// sample.
+    pub maxval: u16,
+    pub data: Vec<u16>,
+}
+
+impl GrayImage {
+    /// Creates a black 8-bit image.
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            format: ImageFormat::P5,
+            width,
+            height,
+            maxval: 255,
+            data: vec![0; width * height],
         }
+    }
 
-        let (_, data) = match format {
-            ImageFormat::P3 => parser::parse_data_ascii(i).map_err(|_| ImageError::InvalidData)?,
-            ImageFormat::P6 => parser::parse_data_binary(i).map_err(|_| ImageError::InvalidData)?,
+    /// Writes the image to a stream in its format, after which another one may be written. A
+    /// PBM is black where the samples are below half of the maxval, and a PPM is gray.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Error` if the write operation fails.
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let mut header = Header {
+            format: self.format,
+            width: self.width,
+            height: self.height,
+            maxval: self.maxval,
         };
+        let samples = match self.format {
+            ImageFormat::P1 | ImageFormat::P4 => {
+                header.maxval = 1;
+                let threshold = u32::from(self.maxval);
+                self.data
+                    .iter()
+                    .map(|&sample| u16::from(u32::from(sample) * 2 >= threshold))
+                    .collect()
+            }
+            ImageFormat::P2 | ImageFormat::P5 => self.data.clone(),
+            ImageFormat::P3 | ImageFormat::P6 => self.data.iter().flat_map(|&s| [s; 3]).collect(),
+        };
+        pnm::write(writer, &header, &samples)
+    }
 
-        if data.len() != height * width {
-            return Err(ImageError::IncompleteFile);
+    /// # Errors
+    ///
+    /// Will return `Error` if `path` does not exist or the user does not have
+    /// permission to write to it, or the write operation fails.
+    pub fn write_pgm<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        self.write(File::create(path)?)
+    }
+
+    /// Reads the next image of a stream, or returns `None` at its end. A PBM is read as an image
+    /// with a maxval of 1.
+    ///
+    /// # Errors
+    ///
+    /// Will return `ImageError` if the stream can't be read, or does not match the
+    /// specification, or the image is not a PGM or a PBM
+    pub fn decode<R: Read>(decoder: &mut Decoder<R>) -> Result<Option<GrayImage>, ImageError> {
+        let header = match decoder.next_header()? {
+            Some(header) => header,
+            None => return Ok(None),
         };
+        if header.format.channels() != 1 {
+            return Err(ImageError::UnsupportedFormat(header.format.to_string()));
+        }
 
-        Ok(Image {
-            format,
-            width,
-            height,
+        let mut data = Vec::with_capacity(header.width * header.height);
+        while let Some(row) = decoder.read_row()? {
+            data.extend_from_slice(row);
+        }
+
+        Ok(Some(GrayImage {
+            format: header.format,
+            width: header.width,
+            height: header.height,
+            maxval: header.maxval,
             data,
-        })
+        }))
     }
-}
 
-impl Index<(usize, usize)> for Image {
-    type Output = Color;
+    /// Reads the first image of a stream.
+    ///
+    /// # Errors
+    ///
+    /// Will return `ImageError` if the stream can't be read or is empty, or does not match the
+    /// specification, or the image is not a PGM or a PBM
+    pub fn read<R: Read>(reader: R) -> Result<GrayImage, ImageError> {
+        GrayImage::decode(&mut Decoder::new(reader))?.ok_or(ImageError::IncompleteFile)
+    }
 
-    fn index(&self, (x, y): (usize, usize)) -> &Color {
-        &self.data[x + y * self.width]
+    /// # Errors
+    ///
+    /// Will return `ImageError` if `path` does not exist or the user does not have
+    /// permission to read it or the read operation fails, or the file format does not
+    /// match the specification, or it is not a PGM or a PBM
+    pub fn read_pgm<P: AsRef<Path>>(path: P) -> Result<GrayImage, ImageError> {
+        let file = File::open(path).map_err(|_| ImageError::FileNotFound)?;
+        GrayImage::read(file)
     }
 }
 
-impl IndexMut<(usize, usize)> for Image {
-    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Color {
-        &mut self.data[x + y * self.width]
+/// An image with an alpha channel, for drawing a layer that is then composited onto an opaque
+/// color. None of the Netpbm formats hold alpha, so it has to be flattened to be written.
+pub struct RgbaImage {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<Rgba>,
+}
+
+impl RgbaImage {
+    /// Creates a transparent image.
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![Rgba::default(); width * height],
+        }
+    }
+
+    /// Composites the image over a background color.
+    #[must_use]
+    pub fn flatten(&self, background: Color) -> Image {
+        let mut image = Image::new(self.width, self.height);
+        for (pixel, rgba) in image.data.iter_mut().zip(&self.data) {
+            *pixel = rgba.over(background);
+        }
+        image
     }
 }
 
+macro_rules! raster {
+    ($($image:ident => $pixel:ty),*) => {
+        $(
+            impl Canvas for $image {
+                type Pixel = $pixel;
+
+                fn width(&self) -> usize {
+                    self.width
+                }
+
+                fn height(&self) -> usize {
+                    self.height
+                }
+
+                fn pixels(&self) -> &[$pixel] {
+                    &self.data
+                }
+
+                fn pixels_mut(&mut self) -> &mut [$pixel] {
+                    &mut self.data
+                }
+            }
+
+            impl Index<(usize, usize)> for $image {
+                type Output = $pixel;
+
+                fn index(&self, (x, y): (usize, usize)) -> &$pixel {
+                    &self.data[x + y * self.width]
+                }
+            }
+
+            impl IndexMut<(usize, usize)> for $image {
+                fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut $pixel {
+                    &mut self.data[x + y * self.width]
+                }
+            }
+        )*
+    };
+}
+
+raster!(Image => Color, GrayImage => u16, RgbaImage => Rgba);
+
 #[allow(dead_code)]
 pub fn main() {
     let mut image = Image::new(10, 10);
@@ -210,6 +460,13 @@ pub fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::{env, fs, process};
+
+    /// A path in the temporary directory that no other test uses.
+    fn temp_file(name: &str) -> String {
+        let path = env::temp_dir().join(format!("bitmap-{}-{}", process::id(), name));
+        path.to_str().unwrap().to_string()
+    }
 
     #[test]
     #[should_panic]
@@ -262,4 +519,174 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn gray_round_trip() {
+        for &(format, maxval) in &[
+            (ImageFormat::P2, 255),
+            (ImageFormat::P5, 255),
+            (ImageFormat::P2, 1000),
+            (ImageFormat::P5, 65535),
+        ] {
+            let mut image = GrayImage::new(3, 2);
+            image.format = format;
+            image.maxval = maxval;
+            image.data = vec![0, 1, 2, maxval / 2, maxval - 1, maxval];
+
+            let fname = temp_file(&format!("{}-{}.pgm", format, maxval));
+            image.write_pgm(&fname).unwrap();
+            let read = GrayImage::read_pgm(&fname).unwrap();
+
+            assert_eq!(read.format, format);
+            assert_eq!((read.width, read.height), (3, 2));
+            assert_eq!(read.maxval, maxval);
+            assert_eq!(read.data, image.data);
+        }
+    }
+
+    #[test]
+    fn pbm_round_trip() {
+        for &format in &[ImageFormat::P1, ImageFormat::P4] {
+            // wider than a byte, so that the rows of a P4 are padded
+            let mut image = GrayImage::new(10, 2);
+            image.format = format;
+            for x in 0..10 {
+                image[(x, x % 2)] = 255;
+            }
+
+            let fname = temp_file(&format!("{}.pbm", format));
+            image.write_pgm(&fname).unwrap();
+            let read = GrayImage::read_pgm(&fname).unwrap();
+
+            assert_eq!(read.maxval, 1);
+            let expected = image.data.iter().map(|&s| s / 255).collect::<Vec<_>>();
+            assert_eq!(read.data, expected);
+        }
+
+        let fname = temp_file("plain.pbm");
+        fs::write(&fname, "P1\n3 2\n010\n1 0 1\n").unwrap();
+        assert_eq!(
+            GrayImage::read_pgm(&fname).unwrap().data,
+            [1, 0, 1, 0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn deep_ppm() {
+        let fname = temp_file("deep.ppm");
+        let mut data = b"P6\n2 1\n65535\n".to_vec();
+        for sample in &[65535u16, 0, 32768, 257, 514, 0] {
+            data.extend(&sample.to_be_bytes());
+        }
+        fs::write(&fname, data).unwrap();
+
+        let image = Image::read_ppm(&fname).unwrap();
+        assert_eq!(
+            image.data,
+            [
+                Color {
+                    red: 255,
+                    green: 0,
+                    blue: 128,
+                },
+                Color {
+                    red: 1,
+                    green: 2,
+                    blue: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn wrong_kind_of_file() {
+        let fname = temp_file("gray.pgm");
+        GrayImage::new(2, 2).write_pgm(&fname).unwrap();
+        assert!(matches!(
+            Image::read_ppm(&fname),
+            Err(ImageError::UnsupportedFormat(ref format)) if format == "P5"
+        ));
+
+        let fname = temp_file("short.pgm");
+        fs::write(&fname, b"P5 2 2 255\n\x01\x02\x03").unwrap();
+        assert!(matches!(
+            GrayImage::read_pgm(&fname),
+            Err(ImageError::IncompleteFile)
+        ));
+
+        let fname = temp_file("bright.pgm");
+        fs::write(&fname, "P2 2 1 7\n3 8\n").unwrap();
+        assert!(matches!(
+            GrayImage::read_pgm(&fname),
+            Err(ImageError::InvalidData)
+        ));
+    }
+
+    #[test]
+    fn canvas() {
+        let mut image = GrayImage::new(3, 2);
+        image.put(1, 1, 200);
+        image.put(-1, 0, 200);
+        image.put(3, 1, 200);
+        assert_eq!(image.data, [0, 0, 0, 0, 200, 0]);
+        assert_eq!(image.get(1, 1), Some(200));
+        assert_eq!(image.get(1, 2), None);
+
+        image.blend(1, 1, 100, 0.25);
+        image.blend(0, 0, 100, 1.5);
+        assert_eq!(image.data, [100, 0, 0, 0, 175, 0]);
+    }
+
+    #[test]
+    fn alpha() {
+        let mut layer = RgbaImage::new(2, 1);
+        layer[(1, 0)] = Rgba {
+            red: 255,
+            green: 0,
+            blue: 0,
+            alpha: 51,
+        };
+
+        let background = Color {
+            red: 0,
+            green: 0,
+            blue: 255,
+        };
+        let image = layer.flatten(background);
+        assert_eq!(image[(0, 0)], background);
+        assert_eq!(
+            image[(1, 0)],
+            Color {
+                red: 51,
+                green: 0,
+                blue: 204,
+            }
+        );
+    }
+
+    #[test]
+    fn streams() {
+        let mut stream = b"# made by hand\nP3 # red and blue\n2 1\n# 8 bits\n255\n".to_vec();
+        stream.extend_from_slice(b"255 0 0  0 0 255\n");
+        let mut gray = GrayImage::new(1, 1);
+        gray.data[0] = 42;
+        gray.write(&mut stream).unwrap();
+
+        let mut decoder = Decoder::new(&stream[..]);
+        let image = Image::decode(&mut decoder).unwrap().unwrap();
+        assert_eq!(image.format, ImageFormat::P3);
+        assert_eq!(image[(1, 0)].blue, 255);
+        assert_eq!(GrayImage::decode(&mut decoder).unwrap().unwrap().data, [42]);
+        assert!(GrayImage::decode(&mut decoder).unwrap().is_none());
+
+        assert!(matches!(
+            Image::read(&b""[..]),
+            Err(ImageError::IncompleteFile)
+        ));
+        assert_eq!(ImageFormat::try_from("p4").unwrap(), ImageFormat::P4);
+        assert!(matches!(
+            ImageFormat::try_from("PAM"),
+            Err(ImageError::UnsupportedFormat(_))
+        ));
+    }
 }
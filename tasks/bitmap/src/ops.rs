@@ -0,0 +1,440 @@
+//! Image processing: the [grayscale image], [histogram], [image convolution] and [median filter]
+//! tasks, and resizing.
+//!
+//! Operations that work on each channel of a color image separately are written once for
+//! grayscale images, and run on color ones through [`Channels`]. Pixels beyond the edges of an
+//! image are taken to be copies of the nearest pixel on the edge.
+//!
+//! [grayscale image]: http://rosettacode.org/wiki/Grayscale_image
+//! [histogram]: http://rosettacode.org/wiki/Image_histogram
+//! [image convolution]: http://rosettacode.org/wiki/Image_convolution
+//! [median filter]: http://rosettacode.org/wiki/Median_filter
+
+use super::{rescale, Color, GrayImage, Image, ImageFormat};
+
+/// An image whose channels can be processed one at a time, as grayscale images.
+pub trait Channels {
+    /// Makes an image of the same kind from the results of an operation on each channel, which
+    /// must all have the same size.
+    #[must_use]
+    fn map_channels<F: FnMut(&GrayImage) -> GrayImage>(&self, f: F) -> Self;
+}
+
+impl Channels for GrayImage {
+    fn map_channels<F: FnMut(&GrayImage) -> GrayImage>(&self, mut f: F) -> Self {
+        f(self)
+    }
+}
+
+impl Channels for Image {
+    fn map_channels<F: FnMut(&GrayImage) -> GrayImage>(&self, mut f: F) -> Self {
+        let mut channel = |get: fn(&Color) -> u8| {
+            f(&GrayImage {
+                format: ImageFormat::P5,
+                width: self.width,
+                height: self.height,
+                maxval: 255,
+                data: self.data.iter().map(|color| get(color).into()).collect(),
+            })
+        };
+        let red = channel(|color| color.red);
+        let green = channel(|color| color.green);
+        let blue = channel(|color| color.blue);
+
+        let data = red
+            .data
+            .iter()
+            .zip(&green.data)
+            .zip(&blue.data)
+            .map(|((&red, &green), &blue)| Color {
+                red: red as u8,
+                green: green as u8,
+                blue: blue as u8,
+            })
+            .collect();
+        Image {
+            format: self.format,
+            width: red.width,
+            height: red.height,
+            data,
+        }
+    }
+}
+
+/// Converts a color image to grayscale, by the luminance of each pixel as defined for HDTV by
+/// [ITU-R BT.709](https://en.wikipedia.org/wiki/Rec._709).
+#[must_use]
+pub fn luminance(image: &Image) -> GrayImage {
+    let data = image
+        .data
+        .iter()
+        .map(|color| {
+            let luminance = 0.2126 * f64::from(color.red)
+                + 0.7152 * f64::from(color.green)
+                + 0.0722 * f64::from(color.blue);
+            luminance.round() as u16
+        })
+        .collect();
+
+    GrayImage {
+        format: if image.format == ImageFormat::P3 {
+            ImageFormat::P2
+        } else {
+            ImageFormat::P5
+        },
+        width: image.width,
+        height: image.height,
+        maxval: 255,
+        data,
+    }
+}
+
+/// Converts a grayscale image to a color one, scaling its samples to 8 bits.
+#[must_use]
+pub fn to_color(image: &GrayImage) -> Image {
+    let data = image
+        .data
+        .iter()
+        .map(|&sample| {
+            let level = rescale(sample, image.maxval, 255) as u8;
+            Color {
+                red: level,
+                green: level,
+                blue: level,
+            }
+        })
+        .collect();
+
+    Image {
+        format: match image.format {
+            ImageFormat::P1 | ImageFormat::P2 | ImageFormat::P3 => ImageFormat::P3,
+            _ => ImageFormat::P6,
+        },
+        width: image.width,
+        height: image.height,
+        data,
+    }
+}
+
+/// Counts the pixels of each level of gray, from black to the maxval.
+#[must_use]
+pub fn histogram(image: &GrayImage) -> Vec<usize> {
+    let mut histogram = vec![0; usize::from(image.maxval) + 1];
+    for &sample in &image.data {
+        histogram[usize::from(sample)] += 1;
+    }
+    histogram
+}
+
+/// The median level of gray of a histogram: the darkest level that at least half of the pixels
+/// are at or below.
+#[must_use]
+pub fn median_level(histogram: &[usize]) -> u16 {
+    let half = histogram.iter().sum::<usize>().div_ceil(2);
+    let mut count = 0;
+    for (level, &pixels) in histogram.iter().enumerate() {
+        count += pixels;
+        if count >= half {
+            return level as u16;
+        }
+    }
+    0
+}
+
+/// Converts an image to black and white: pixels at or above the level are white, and those below
+/// it are black. The result is a PBM, with a maxval of 1.
+#[must_use]
+pub fn threshold(image: &GrayImage, level: u16) -> GrayImage {
+    GrayImage {
+        format: if image.format == ImageFormat::P2 {
+            ImageFormat::P1
+        } else {
+            ImageFormat::P4
+        },
+        width: image.width,
+        height: image.height,
+        maxval: 1,
+        data: image
+            .data
+            .iter()
+            .map(|&sample| u16::from(sample >= level))
+            .collect(),
+    }
+}
+
+/// A square matrix of weights, with an odd number of rows so that it has a center.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Kernel {
+    size: usize,
+    weights: Vec<f64>,
+}
+
+impl Kernel {
+    /// Creates a kernel from its weights, row by row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of weights is not the square of an odd number.
+    #[must_use]
+    pub fn new(weights: Vec<f64>) -> Self {
+        let size = (weights.len() as f64).sqrt() as usize;
+        assert!(
+            size * size == weights.len() && size % 2 == 1,
+            "a kernel needs an odd square number of weights, not {}",
+            weights.len()
+        );
+        Kernel { size, weights }
+    }
+
+    /// Averages the pixels of a square of `size` by `size` pixels.
+    #[must_use]
+    pub fn box_blur(size: usize) -> Self {
+        Kernel::new(vec![1.0 / (size * size) as f64; size * size])
+    }
+
+    /// Exaggerates the difference between each pixel and its neighbors.
+    #[must_use]
+    pub fn sharpen() -> Self {
+        Kernel::new(vec![-1.0, -1.0, -1.0, -1.0, 9.0, -1.0, -1.0, -1.0, -1.0])
+    }
+
+    /// Lights edges from the top left, as though the image were raised.
+    #[must_use]
+    pub fn emboss() -> Self {
+        Kernel::new(vec![-2.0, -1.0, 0.0, -1.0, 1.0, 1.0, 0.0, 1.0, 2.0])
+    }
+
+    /// The number of rows and of columns.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+/// The sample at a point, or at the nearest point of the image to it.
+fn clamped(image: &GrayImage, x: isize, y: isize) -> u16 {
+    let x = x.clamp(0, image.width as isize - 1) as usize;
+    let y = y.clamp(0, image.height as isize - 1) as usize;
+    image[(x, y)]
+}
+
+/// Makes an image of the same size, format and maxval from a function of each point.
+fn map_points<F: FnMut(isize, isize) -> u16>(image: &GrayImage, mut f: F) -> GrayImage {
+    let mut data = Vec::with_capacity(image.data.len());
+    for y in 0..image.height as isize {
+        for x in 0..image.width as isize {
+            data.push(f(x, y));
+        }
+    }
+
+    GrayImage {
+        format: image.format,
+        width: image.width,
+        height: image.height,
+        maxval: image.maxval,
+        data,
+    }
+}
+
+/// Replaces each pixel with the sum of it and its neighbors, each multiplied by the weight at the
+/// same place in the kernel when it is centered on the pixel. Sums beyond black or white are
+/// clamped.
+#[must_use]
+pub fn convolve<I: Channels>(image: &I, kernel: &Kernel) -> I {
+    let radius = (kernel.size / 2) as isize;
+    image.map_channels(|channel| {
+        map_points(channel, |x, y| {
+            let mut sum = 0.0;
+            for (i, &weight) in kernel.weights.iter().enumerate() {
+                let dx = (i % kernel.size) as isize - radius;
+                let dy = (i / kernel.size) as isize - radius;
+                sum += weight * f64::from(clamped(channel, x + dx, y + dy));
+            }
+            sum.round().clamp(0.0, f64::from(channel.maxval)) as u16
+        })
+    })
+}
+
+/// Replaces each pixel with the median of the square of pixels around it, `radius` pixels in
+/// each direction.
+#[must_use]
+pub fn median_filter<I: Channels>(image: &I, radius: usize) -> I {
+    let radius = radius as isize;
+    let mut window = vec![];
+    image.map_channels(|channel| {
+        map_points(channel, |x, y| {
+            window.clear();
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    window.push(clamped(channel, x + dx, y + dy));
+                }
+            }
+            let middle = window.len() / 2;
+            *window.select_nth_unstable(middle).1
+        })
+    })
+}
+
+/// How [`resize`] works out the pixels of the new image.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Filter {
+    /// Takes the pixel of the old image whose area contains the center of the new pixel.
+    Nearest,
+    /// Interpolates between the four pixels of the old image whose centers are nearest to the
+    /// center of the new pixel.
+    Bilinear,
+}
+
+/// Scales an image to a new size.
+///
+/// # Panics
+///
+/// Panics if the image is empty, but the new size is not.
+#[must_use]
+pub fn resize<I: Channels>(image: &I, width: usize, height: usize, filter: Filter) -> I {
+    image.map_channels(|channel| {
+        assert!(
+            channel.width * channel.height > 0 || width * height == 0,
+            "an empty image can't be resized"
+        );
+
+        let scale_x = channel.width as f64 / width as f64;
+        let scale_y = channel.height as f64 / height as f64;
+        let mut resized = GrayImage {
+            format: channel.format,
+            width,
+            height,
+            maxval: channel.maxval,
+            data: vec![],
+        };
+        resized = map_points(&resized, |x, y| {
+            // the center of the new pixel, in the coordinates of the old image
+            let old_x = (x as f64 + 0.5) * scale_x;
+            let old_y = (y as f64 + 0.5) * scale_y;
+            match filter {
+                Filter::Nearest => clamped(channel, old_x as isize, old_y as isize),
+                Filter::Bilinear => {
+                    let (old_x, old_y) = (old_x - 0.5, old_y - 0.5);
+                    let (left, top) = (old_x.floor(), old_y.floor());
+                    let (tx, ty) = (old_x - left, old_y - top);
+                    let (left, top) = (left as isize, top as isize);
+                    let sample = |x, y| f64::from(clamped(channel, x, y));
+
+                    let upper = sample(left, top) * (1.0 - tx) + sample(left + 1, top) * tx;
+                    let lower = sample(left, top + 1) * (1.0 - tx) + sample(left + 1, top + 1) * tx;
+                    (upper * (1.0 - ty) + lower * ty).round() as u16
+                }
+            }
+        });
+        resized
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gray(width: usize, maxval: u16, data: &[u16]) -> GrayImage {
+        GrayImage {
+            format: ImageFormat::P5,
+            width,
+            height: data.len() / width,
+            maxval,
+            data: data.to_vec(),
+        }
+    }
+
+    fn color(red: u8, green: u8, blue: u8) -> Color {
+        Color { red, green, blue }
+    }
+
+    #[test]
+    fn grayscale() {
+        let mut image = Image::new(5, 1);
+        image.data = vec![
+            color(255, 0, 0),
+            color(0, 255, 0),
+            color(0, 0, 255),
+            color(255, 255, 255),
+            color(10, 20, 30),
+        ];
+        assert_eq!(luminance(&image).data, [54, 182, 18, 255, 19]);
+
+        let image = to_color(&gray(2, 1000, &[500, 1000]));
+        assert_eq!(image.format, ImageFormat::P6);
+        assert_eq!(image.data, [color(128, 128, 128), color(255, 255, 255)]);
+    }
+
+    #[test]
+    fn histogram_and_threshold() {
+        let image = gray(4, 7, &[0, 1, 1, 2, 3, 3, 3, 7]);
+        let histogram = histogram(&image);
+        assert_eq!(histogram, [1, 2, 1, 3, 0, 0, 0, 1]);
+        assert_eq!(median_level(&histogram), 2);
+
+        let black_and_white = threshold(&image, 2);
+        assert_eq!(black_and_white.format, ImageFormat::P4);
+        assert_eq!(black_and_white.maxval, 1);
+        assert_eq!(black_and_white.data, [0, 0, 0, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn convolution() {
+        let ramp = gray(3, 255, &[10, 20, 30, 40, 50, 60, 70, 80, 90]);
+        let cross = Kernel::new(vec![0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0]);
+        assert_eq!(
+            convolve(&ramp, &cross).data,
+            [0, 0, 10, 30, 50, 70, 90, 110, 130]
+        );
+
+        let mut image = Image::new(3, 1);
+        image.data = vec![color(0, 90, 255), color(90, 90, 255), color(0, 90, 255)];
+        assert_eq!(
+            convolve(&image, &Kernel::box_blur(3)).data,
+            [color(30, 90, 255), color(30, 90, 255), color(30, 90, 255)]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn even_kernel() {
+        let _ = Kernel::new(vec![1.0; 4]);
+    }
+
+    #[test]
+    fn median() {
+        let image = gray(3, 255, &[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(median_filter(&image, 1).data, [2, 3, 3, 4, 5, 6, 7, 7, 8]);
+
+        // a speck of noise disappears
+        let mut image = Image::new(3, 3);
+        image.fill(color(10, 20, 30));
+        image[(1, 1)] = color(255, 255, 255);
+        assert!(median_filter(&image, 1)
+            .data
+            .iter()
+            .all(|&pixel| pixel == color(10, 20, 30)));
+    }
+
+    #[test]
+    fn resizing() {
+        let image = gray(2, 255, &[1, 2, 3, 4]);
+        assert_eq!(
+            resize(&image, 4, 4, Filter::Nearest).data,
+            [1, 1, 2, 2, 1, 1, 2, 2, 3, 3, 4, 4, 3, 3, 4, 4]
+        );
+        assert_eq!(resize(&image, 1, 2, Filter::Nearest).data, [2, 4]);
+
+        let image = gray(2, 255, &[0, 100]);
+        assert_eq!(
+            resize(&image, 4, 1, Filter::Bilinear).data,
+            [0, 25, 75, 100]
+        );
+
+        let mut image = Image::new(1, 1);
+        image.data[0] = color(255, 0, 0);
+        let resized = resize(&image, 2, 3, Filter::Bilinear);
+        assert_eq!((resized.width, resized.height), (2, 3));
+        assert!(resized.data.iter().all(|&pixel| pixel == color(255, 0, 0)));
+    }
+}
@@ -1,85 +1,151 @@
-use super::{Color, ImageFormat};
+//! The parsers for the parts of a Netpbm stream. They are complete parsers, which run on whatever
+//! part of the stream has been read so far; see [`Decoder`](super::Decoder) for how they are fed.
+
 use std::str::from_utf8;
 use std::str::FromStr;
 
-pub fn parse_version(input: &[u8]) -> nom::IResult<&[u8], ImageFormat> {
+/// Parses a comment, from a `#` up to the end of the line.
+pub fn parse_comment(input: &[u8]) -> nom::IResult<&[u8], ()> {
+    use nom::character::complete::{char, not_line_ending};
+    use nom::combinator::value;
+    use nom::sequence::pair;
+
+    value((), pair(char('#'), not_line_ending))(input)
+}
+
+/// Parses the whitespace between the fields of a header, in which there may be comments.
+pub fn parse_separator(input: &[u8]) -> nom::IResult<&[u8], ()> {
     use nom::branch::alt;
-    use nom::bytes::complete::tag;
-    use nom::character::complete::line_ending;
-    use nom::combinator::map;
+    use nom::character::complete::multispace1;
+    use nom::combinator::value;
+    use nom::multi::many1_count;
+
+    value(
+        (),
+        many1_count(alt((value((), multispace1), parse_comment))),
+    )(input)
+}
+
+/// Parses any whitespace and comments before an image.
+pub fn parse_padding(input: &[u8]) -> nom::IResult<&[u8], ()> {
+    use nom::combinator::{opt, value};
+
+    value((), opt(parse_separator))(input)
+}
+
+/// Parses the single whitespace character that ends a header, and a comment before it.
+fn parse_header_end(input: &[u8]) -> nom::IResult<&[u8], ()> {
+    use nom::character::complete::satisfy;
+    use nom::combinator::{opt, value};
+    use nom::sequence::pair;
+
+    value(
+        (),
+        pair(opt(parse_comment), satisfy(|c| c.is_ascii_whitespace())),
+    )(input)
+}
+
+fn parse_number(input: &[u8]) -> nom::IResult<&[u8], usize> {
+    use nom::character::complete::digit1;
+    use nom::combinator::map_res;
+
+    map_res(digit1, |digits| usize::from_str(from_utf8(digits).unwrap()))(input)
+}
+
+/// Parses the magic number, such as `P6`, whether or not it is one of the supported formats.
+pub fn parse_version(input: &[u8]) -> nom::IResult<&[u8], String> {
+    use nom::character::complete::{char, satisfy};
+    use nom::combinator::{map, recognize};
+    use nom::sequence::{pair, terminated};
+
+    // starts with P and a digit or letter followed by whitespace
+    terminated(
+        map(
+            recognize(pair(char('P'), satisfy(|c| c.is_ascii_alphanumeric()))),
+            |magic| from_utf8(magic).unwrap().to_string(),
+        ),
+        parse_separator,
+    )(input)
+}
+
+pub fn parse_dimensions(input: &[u8]) -> nom::IResult<&[u8], (usize, usize)> {
     use nom::sequence::terminated;
+    use nom::sequence::tuple;
 
-    // starts with P3/P6 ends with a CR/LF
+    // 2 numbers separated by whitespace, then a single whitespace character
     terminated(
-        alt((
-            map(tag("P3".as_bytes()), |_| ImageFormat::P3),
-            map(tag("P6".as_bytes()), |_| ImageFormat::P6),
-        )),
-        line_ending,
+        tuple((parse_number, parse_separator, parse_number)),
+        parse_header_end,
     )(input)
+    .map(|(next_input, result)| (next_input, (result.0, result.2)))
 }
 
 pub fn parse_image_attributes(input: &[u8]) -> nom::IResult<&[u8], (usize, usize, usize)> {
-    use nom::character::complete::line_ending;
-    use nom::character::complete::{digit1, space1};
     use nom::sequence::terminated;
     use nom::sequence::tuple;
 
-    // 3 numbers separated by spaces ends with a CR/LF
-    terminated(tuple((digit1, space1, digit1, space1, digit1)), line_ending)(input).map(
-        |(next_input, result)| {
-            (
-                next_input,
-                (
-                    from_utf8(result.0).unwrap().parse().unwrap(),
-                    from_utf8(result.2).unwrap().parse().unwrap(),
-                    from_utf8(result.4).unwrap().parse().unwrap(),
-                ),
-            )
-        },
-    )
+    // 3 numbers separated by whitespace, then a single whitespace character
+    terminated(
+        tuple((
+            parse_number,
+            parse_separator,
+            parse_number,
+            parse_separator,
+            parse_number,
+        )),
+        parse_header_end,
+    )(input)
+    .map(|(next_input, result)| (next_input, (result.0, result.2, result.4)))
 }
 
-pub fn parse_color_binary(input: &[u8]) -> nom::IResult<&[u8], Color> {
-    use nom::number::complete::u8 as nom_u8;
-    use nom::sequence::tuple;
+/// Parses a decimal sample of a P2 or P3 raster, and the whitespace before it.
+pub fn parse_sample_ascii(input: &[u8]) -> nom::IResult<&[u8], u16> {
+    use nom::character::complete::{digit1, multispace0};
+    use nom::combinator::map_res;
+    use nom::sequence::preceded;
 
-    tuple((nom_u8, nom_u8, nom_u8))(input).map(|(next_input, res)| {
-        (
-            next_input,
-            Color {
-                red: res.0,
-                green: res.1,
-                blue: res.2,
-            },
-        )
-    })
+    preceded(
+        multispace0,
+        map_res(digit1, |digits| u16::from_str(from_utf8(digits).unwrap())),
+    )(input)
 }
 
-pub fn parse_data_binary(input: &[u8]) -> nom::IResult<&[u8], Vec<Color>> {
+/// Parses samples of one byte, or of two big-endian bytes if `wide`, as in a P5 or P6 raster.
+pub fn parse_samples_binary(input: &[u8], wide: bool) -> nom::IResult<&[u8], Vec<u16>> {
+    use nom::combinator::map;
     use nom::multi::many0;
-    many0(parse_color_binary)(input)
+    use nom::number::complete::{be_u16, u8 as nom_u8};
+
+    if wide {
+        many0(be_u16)(input)
+    } else {
+        many0(map(nom_u8, u16::from))(input)
+    }
 }
 
-pub fn parse_color_ascii(input: &[u8]) -> nom::IResult<&[u8], Color> {
-    use nom::character::complete::{digit1, space0, space1};
-    use nom::sequence::tuple;
+/// Parses a bit of a P1 raster, and the whitespace before it, which may be left out.
+pub fn parse_bit_ascii(input: &[u8]) -> nom::IResult<&[u8], u8> {
+    use nom::character::complete::{multispace0, one_of};
+    use nom::combinator::map;
+    use nom::sequence::preceded;
 
-    tuple((digit1, space1, digit1, space1, digit1, space0))(input).map(|(next_input, res)| {
-        (
-            next_input,
-            Color {
-                red: u8::from_str(from_utf8(res.0).unwrap()).unwrap(),
-                green: u8::from_str(from_utf8(res.2).unwrap()).unwrap(),
-                blue: u8::from_str(from_utf8(res.4).unwrap()).unwrap(),
-            },
-        )
-    })
+    preceded(
+        multispace0,
+        map(one_of("01"), |bit| if bit == '1' { 1 } else { 0 }),
+    )(input)
 }
 
-pub fn parse_data_ascii(input: &[u8]) -> nom::IResult<&[u8], Vec<Color>> {
-    use nom::multi::many0;
-    many0(parse_color_ascii)(input)
+/// Parses a row of a P4 raster, which is packed into bytes from the most significant bit and
+/// padded to a whole byte.
+pub fn parse_bits_binary(input: &[u8], width: usize) -> nom::IResult<&[u8], Vec<u8>> {
+    use nom::bytes::complete::take;
+
+    take(width.div_ceil(8))(input).map(|(next_input, row): (&[u8], &[u8])| {
+        let bits = (0..width)
+            .map(|x| (row[x / 8] >> (7 - x % 8)) & 1)
+            .collect();
+        (next_input, bits)
+    })
 }
 
 #[cfg(test)]
@@ -90,27 +156,25 @@ mod tests {
     #[test]
     fn test_parse_version() {
         assert_eq!(
-            Ok((&b"200 300 255\nabcde"[..], ImageFormat::P6)),
+            Ok((&b"200 300 255\nabcde"[..], "P6".to_string())),
             parse_version(&b"P6\n200 300 255\nabcde"[..])
         );
 
         assert_eq!(
-            Ok((&b"200 300 255\nabcde"[..], ImageFormat::P3)),
+            Ok((&b"200 300 255\nabcde"[..], "P3".to_string())),
             parse_version(&b"P3\n200 300 255\nabcde"[..])
         );
 
+        // the format is checked afterwards
         assert_eq!(
-            Err(Err::Error(nom::error::Error::new(
-                &b"P1\n200 300 255\nabcde"[..],
-                nom::error::ErrorKind::Tag,
-            ))),
-            parse_version(&b"P1\n200 300 255\nabcde"[..])
+            Ok((&b"200 300 255\nabcde"[..], "P7".to_string())),
+            parse_version(&b"P7\n200 300 255\nabcde"[..])
         );
 
         assert_eq!(
             Err(Err::Error(nom::error::Error::new(
                 &b"\n200 300 255\nabcde"[..],
-                nom::error::ErrorKind::Tag,
+                nom::error::ErrorKind::Char,
             ))),
             parse_version(&b"\n200 300 255\nabcde"[..])
         );
@@ -138,76 +202,64 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_color_binary() {
+    fn test_parse_comments() {
+        assert_eq!(
+            Ok((&b"abcde"[..], "P6".to_string())),
+            parse_version(&b"P6# a comment\n\n  # another\n\nabcde"[..])
+        );
+
+        assert_eq!(
+            Ok((&b"abcde"[..], (200, 300, 255))),
+            parse_image_attributes(&b"200#width\n300 #height\n255# white\nabcde"[..])
+        );
+
         assert_eq!(
-            Ok((
-                &b""[..],
-                Color {
-                    red: 255,
-                    green: 0,
-                    blue: 0
-                }
-            )),
-            parse_color_binary(&[255, 0, 0])
+            Ok((&b"abcde"[..], (16, 2))),
+            parse_dimensions(&b"16 # 2\n2\nabcde"[..])
         );
+
+        assert_eq!(Ok((&b"P6"[..], ())), parse_padding(&b"\n#\nP6"[..]));
+        assert_eq!(Ok((&b"P6"[..], ())), parse_padding(&b"P6"[..]));
     }
 
     #[test]
-    fn test_parse_data_binary() {
+    fn test_parse_sample_ascii() {
         assert_eq!(
-            Ok((
-                &b""[..],
-                vec![
-                    Color {
-                        red: 255,
-                        green: 0,
-                        blue: 0
-                    },
-                    Color {
-                        red: 0,
-                        green: 0,
-                        blue: 255
-                    }
-                ]
-            )),
-            parse_data_binary(&[255, 0, 0, 0, 0, 255])
+            Ok((&b" 7"[..], 65535)),
+            parse_sample_ascii(&b"\n  65535 7"[..])
+        );
+
+        assert_eq!(
+            Err(Err::Error(nom::error::Error::new(
+                &b"65536"[..],
+                nom::error::ErrorKind::MapRes,
+            ))),
+            parse_sample_ascii(&b" 65536"[..])
         );
     }
 
     #[test]
-    fn test_parse_color_ascii() {
+    fn test_parse_samples_binary() {
         assert_eq!(
-            Ok((
-                &b""[..],
-                Color {
-                    red: 255,
-                    green: 0,
-                    blue: 0
-                }
-            )),
-            parse_color_ascii(&b"255 0 0"[..])
+            Ok((&b""[..], vec![255, 0, 1])),
+            parse_samples_binary(&[255, 0, 1], false)
+        );
+
+        assert_eq!(
+            Ok((&[7][..], vec![0xff00, 0x0102])),
+            parse_samples_binary(&[0xff, 0, 1, 2, 7], true)
         );
     }
 
     #[test]
-    fn test_parse_data_ascii() {
+    fn test_parse_bits() {
+        assert_eq!(Ok((&b"011"[..], 0)), parse_bit_ascii(&b"\n0011"[..]));
+        assert_eq!(Ok((&b"11"[..], 0)), parse_bit_ascii(&b"011"[..]));
+
         assert_eq!(
-            Ok((
-                &b""[..],
-                vec![
-                    Color {
-                        red: 255,
-                        green: 0,
-                        blue: 0
-                    },
-                    Color {
-                        red: 0,
-                        green: 0,
-                        blue: 255
-                    }
-                ]
-            )),
-            parse_data_ascii(&b"255 0 0 0 0 255"[..])
+            Ok((&[9][..], vec![1, 0, 1, 0, 0, 0, 0, 0, 1])),
+            // the last 7 bits are padding
+            parse_bits_binary(&[0b1010_0000, 0b1111_1111, 9], 9)
         );
     }
 }
@@ -0,0 +1,430 @@
+//! Reading and writing the samples of the Netpbm formats: PBM, PGM and PPM.
+
+use std::convert::TryFrom;
+use std::io::{BufWriter, Error, ErrorKind, Read, Write};
+use std::ops::Range;
+
+use super::{ImageError, ImageFormat};
+use parser;
+
+/// How much more of the stream is read each time the decoder needs more.
+const CHUNK_LEN: usize = 8192;
+
+/// What the header of an image says about its raster.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Header {
+    pub format: ImageFormat,
+    pub width: usize,
+    pub height: usize,
+    /// The largest sample value, which is always 1 for a PBM.
+    pub maxval: u16,
+}
+
+impl Header {
+    /// The number of samples in each row.
+    pub fn row_len(&self) -> usize {
+        self.width * self.format.channels()
+    }
+}
+
+/// Reads the images of a Netpbm stream one row at a time, so that an image never has to be held
+/// in memory whole, and a stream may hold any number of images one after another.
+///
+/// The samples of a PBM are 0 for black and 1 for white, as in a PGM with a maxval of 1, even
+/// though its bits are the other way round.
+///
+/// ```
+/// use bitmap::{Decoder, ImageFormat};
+///
+/// let mut decoder = Decoder::new(&b"P2 2 1 9 0 9 # a comment\nP1 3 1 011"[..]);
+/// let mut rows = vec![];
+/// while let Some(header) = decoder.next_header().unwrap() {
+///     while let Some(row) = decoder.read_row().unwrap() {
+///         rows.push((header.format, row.to_vec()));
+///     }
+/// }
+/// assert_eq!(
+///     rows,
+///     [(ImageFormat::P2, vec![0, 9]), (ImageFormat::P1, vec![1, 0, 0])]
+/// );
+/// ```
+pub struct Decoder<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    /// Where the part of the buffer that hasn't been decoded yet starts.
+    position: usize,
+    eof: bool,
+    header: Option<Header>,
+    rows_left: usize,
+    row: Vec<u16>,
+}
+
+impl<R: Read> Decoder<R> {
+    pub fn new(reader: R) -> Self {
+        Decoder {
+            reader,
+            buffer: vec![],
+            position: 0,
+            eof: false,
+            header: None,
+            rows_left: 0,
+            row: vec![],
+        }
+    }
+
+    /// Reads the header of the next image, skipping whatever is left of the current one, or
+    /// returns `None` at the end of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Will return `ImageError` if the stream can't be read, or the header does not match the
+    /// specification or is of an unsupported format.
+    pub fn next_header(&mut self) -> Result<Option<Header>, ImageError> {
+        while self.read_row()?.is_some() {}
+
+        self.parse(parser::parse_padding, ImageError::InvalidHeader)?;
+        if self.position == self.buffer.len() {
+            return Ok(None);
+        }
+
+        let version = self.parse(parser::parse_version, ImageError::InvalidHeader)?;
+        let format = ImageFormat::try_from(version.as_str())?;
+        let (width, height, max_color) = match format {
+            ImageFormat::P1 | ImageFormat::P4 => {
+                let (width, height) =
+                    self.parse(parser::parse_dimensions, ImageError::InvalidHeader)?;
+                (width, height, 1)
+            }
+            _ => self.parse(parser::parse_image_attributes, ImageError::InvalidHeader)?,
+        };
+
+        if max_color == 0 || max_color > usize::from(u16::MAX) {
+            return Err(ImageError::InvalidMaxColor);
+        }
+        // Two bytes for each sample must be countable, so that sizes can be worked out freely.
+        if width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(2 * format.channels()))
+            .is_none()
+        {
+            return Err(ImageError::InvalidHeader);
+        }
+
+        let header = Header {
+            format,
+            width,
+            height,
+            maxval: max_color as u16,
+        };
+        self.header = Some(header);
+        self.rows_left = height;
+        Ok(Some(header))
+    }
+
+    /// Reads the next row of samples of the current image, or returns `None` once they have all
+    /// been read.
+    ///
+    /// # Errors
+    ///
+    /// Will return `ImageError` if the stream can't be read or ends early, or the samples do not
+    /// match the specification.
+    pub fn read_row(&mut self) -> Result<Option<&[u16]>, ImageError> {
+        let header = match self.header {
+            Some(header) if self.rows_left > 0 => header,
+            _ => return Ok(None),
+        };
+
+        let len = header.row_len();
+        let mut row = std::mem::take(&mut self.row);
+        row.clear();
+        match header.format {
+            ImageFormat::P1 => {
+                for _ in 0..len {
+                    let bit = self.parse(parser::parse_bit_ascii, ImageError::InvalidData)?;
+                    row.push(u16::from(1 - bit));
+                }
+            }
+            ImageFormat::P2 | ImageFormat::P3 => {
+                for _ in 0..len {
+                    row.push(self.parse(parser::parse_sample_ascii, ImageError::InvalidData)?);
+                }
+            }
+            ImageFormat::P4 => {
+                let bytes = self.take(header.width.div_ceil(8))?;
+                let (_, bits) = parser::parse_bits_binary(&self.buffer[bytes], header.width)
+                    .map_err(|_| ImageError::InvalidData)?;
+                row.extend(bits.into_iter().map(|bit| u16::from(1 - bit)));
+            }
+            ImageFormat::P5 | ImageFormat::P6 => {
+                let wide = header.maxval > 255;
+                let bytes = self.take(if wide { 2 * len } else { len })?;
+                let (_, samples) = parser::parse_samples_binary(&self.buffer[bytes], wide)
+                    .map_err(|_| ImageError::InvalidData)?;
+                row.extend(samples);
+            }
+        }
+        self.row = row;
+
+        if self.row.iter().any(|&sample| sample > header.maxval) {
+            return Err(ImageError::InvalidData);
+        }
+        self.rows_left -= 1;
+        Ok(Some(&self.row))
+    }
+
+    /// Reads more of the stream into the buffer, and returns whether there was any.
+    fn fill(&mut self) -> Result<bool, ImageError> {
+        if self.eof {
+            return Ok(false);
+        }
+
+        // Drop what has been decoded, once it is most of the buffer.
+        if self.position * 2 > self.buffer.len() {
+            self.buffer.drain(..self.position);
+            self.position = 0;
+        }
+
+        let len = self.buffer.len();
+        self.buffer.resize(len + CHUNK_LEN, 0);
+        loop {
+            match self.reader.read(&mut self.buffer[len..]) {
+                Ok(read) => {
+                    self.buffer.truncate(len + read);
+                    self.eof = read == 0;
+                    return Ok(read > 0);
+                }
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(_) => {
+                    self.buffer.truncate(len);
+                    return Err(ImageError::FileNotReadable);
+                }
+            }
+        }
+    }
+
+    /// Runs a parser on the part of the stream that hasn't been decoded yet, and moves past what
+    /// it parsed. Errors that aren't caused by running out of input are reported as `error`.
+    ///
+    /// A parser can't tell the end of the buffer from the end of the stream, so when it runs
+    /// into the end of the buffer, whether it stopped there or failed, more of the stream is read
+    /// and it is run again.
+    fn parse<T, P>(&mut self, mut parser: P, error: ImageError) -> Result<T, ImageError>
+    where
+        P: FnMut(&[u8]) -> nom::IResult<&[u8], T>,
+    {
+        loop {
+            let input = &self.buffer[self.position..];
+            let result = parser(input);
+            let ran_out = match result {
+                Ok((rest, _)) => rest.is_empty(),
+                Err(nom::Err::Error(ref e)) | Err(nom::Err::Failure(ref e)) => e.input.is_empty(),
+                Err(nom::Err::Incomplete(_)) => true,
+            };
+
+            if ran_out && !self.eof {
+                self.fill()?;
+                continue;
+            }
+
+            return match result {
+                Ok((rest, value)) => {
+                    self.position = self.buffer.len() - rest.len();
+                    Ok(value)
+                }
+                Err(_) if ran_out => Err(ImageError::IncompleteFile),
+                Err(_) => Err(error),
+            };
+        }
+    }
+
+    /// Moves past the next `len` bytes of the stream, and returns where they are in the buffer.
+    fn take(&mut self, len: usize) -> Result<Range<usize>, ImageError> {
+        while self.buffer.len() - self.position < len {
+            if !self.fill()? {
+                return Err(ImageError::IncompleteFile);
+            }
+        }
+
+        let start = self.position;
+        self.position += len;
+        Ok(start..self.position)
+    }
+}
+
+/// Writes an image in the format of the header. The samples of a PBM are 0 for black and 1 for
+/// white, like those that [`Decoder`] reads.
+pub fn write<W: Write>(writer: W, header: &Header, samples: &[u16]) -> Result<(), Error> {
+    let mut writer = BufWriter::new(writer);
+    writeln!(&mut writer, "{}", header.format)?;
+    match header.format {
+        ImageFormat::P1 | ImageFormat::P4 => {
+            writeln!(&mut writer, "{} {}", header.width, header.height)?;
+        }
+        _ => writeln!(
+            &mut writer,
+            "{} {} {}",
+            header.width, header.height, header.maxval
+        )?,
+    }
+
+    let rows = samples.chunks(header.row_len().max(1));
+    match header.format {
+        ImageFormat::P1 => {
+            for row in rows {
+                let bits = row
+                    .iter()
+                    .map(|&sample| if sample == 0 { "1" } else { "0" })
+                    .collect::<Vec<_>>();
+                writeln!(&mut writer, "{}", bits.join(" "))?;
+            }
+        }
+        ImageFormat::P2 | ImageFormat::P3 => {
+            for row in rows {
+                let values = row.iter().map(u16::to_string).collect::<Vec<_>>();
+                writeln!(&mut writer, "{}", values.join(" "))?;
+            }
+        }
+        ImageFormat::P4 => {
+            for row in rows {
+                let mut bytes = vec![0u8; row.len().div_ceil(8)];
+                for (x, &sample) in row.iter().enumerate() {
+                    if sample == 0 {
+                        bytes[x / 8] |= 0x80 >> (x % 8);
+                    }
+                }
+                writer.write_all(&bytes)?;
+            }
+        }
+        ImageFormat::P5 | ImageFormat::P6 => {
+            let bytes = if header.maxval > 255 {
+                samples
+                    .iter()
+                    .flat_map(|sample| sample.to_be_bytes())
+                    .collect()
+            } else {
+                samples
+                    .iter()
+                    .map(|&sample| sample as u8)
+                    .collect::<Vec<_>>()
+            };
+            writer.write_all(&bytes)?;
+        }
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write, Decoder, Header};
+    use std::io::{self, Read};
+    use {ImageError, ImageFormat};
+
+    /// A stream that gives at most a few bytes at a time, so that numbers and rows are split
+    /// between reads.
+    struct Trickle<'a>(&'a [u8], usize);
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.1 = self.1 % 3 + 1;
+            let len = self.1.min(buf.len()).min(self.0.len());
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    /// An image, as its header and its rows.
+    type Rows = (Header, Vec<Vec<u16>>);
+
+    fn decode_all<R: Read>(reader: R) -> Result<Vec<Rows>, ImageError> {
+        let mut decoder = Decoder::new(reader);
+        let mut images = vec![];
+        while let Some(header) = decoder.next_header()? {
+            let mut rows = vec![];
+            while let Some(row) = decoder.read_row()? {
+                rows.push(row.to_vec());
+            }
+            images.push((header, rows));
+        }
+        Ok(images)
+    }
+
+    #[test]
+    fn many_images() {
+        let mut stream = vec![];
+        let mut expected = vec![];
+        for (i, &format) in [
+            ImageFormat::P1,
+            ImageFormat::P2,
+            ImageFormat::P3,
+            ImageFormat::P4,
+            ImageFormat::P5,
+            ImageFormat::P6,
+        ]
+        .iter()
+        .enumerate()
+        {
+            let header = Header {
+                format,
+                width: 9 + i,
+                height: 2,
+                maxval: if format == ImageFormat::P1 || format == ImageFormat::P4 {
+                    1
+                } else {
+                    1000
+                },
+            };
+            let samples = (0..header.row_len() * header.height)
+                .map(|sample| (sample * 37) as u16 % (header.maxval + 1))
+                .collect::<Vec<_>>();
+            write(&mut stream, &header, &samples).unwrap();
+
+            let rows = samples.chunks(header.row_len()).map(<[u16]>::to_vec);
+            expected.push((header, rows.collect::<Vec<_>>()));
+        }
+
+        assert_eq!(decode_all(&stream[..]).unwrap(), expected);
+        assert_eq!(decode_all(Trickle(&stream, 0)).unwrap(), expected);
+    }
+
+    #[test]
+    fn skipping_rows() {
+        let stream = b"P2 1 3 9 1 2 3 P5 1 1 255 \x07";
+        let mut decoder = Decoder::new(&stream[..]);
+
+        decoder.next_header().unwrap();
+        assert_eq!(decoder.read_row().unwrap(), Some(&[1][..]));
+        let header = decoder.next_header().unwrap().unwrap();
+        assert_eq!(header.format, ImageFormat::P5);
+        assert_eq!(decoder.read_row().unwrap(), Some(&[7][..]));
+        assert_eq!(decoder.read_row().unwrap(), None);
+        assert_eq!(decoder.next_header().unwrap(), None);
+    }
+
+    #[test]
+    fn errors() {
+        let decode = |stream: &[u8]| decode_all(Trickle(stream, 0)).unwrap_err();
+
+        assert!(
+            matches!(decode(b"P7\n1 1 255\n"), ImageError::UnsupportedFormat(ref format) if format == "P7")
+        );
+        assert!(matches!(decode(b"P6\n1 1"), ImageError::IncompleteFile));
+        assert!(matches!(
+            decode(b"P6\n1 1 255\n\0\0"),
+            ImageError::IncompleteFile
+        ));
+        assert!(matches!(
+            decode(b"P2\n1 1 0\n0"),
+            ImageError::InvalidMaxColor
+        ));
+        assert!(matches!(
+            decode(b"P2\n2 1 255\n0 x"),
+            ImageError::InvalidData
+        ));
+        assert!(matches!(decode(b"P2\nx"), ImageError::InvalidHeader));
+        assert!(matches!(
+            decode(b"P5 99999999999 99999999999 255\n"),
+            ImageError::InvalidHeader
+        ));
+    }
+}
@@ -17,10 +17,7 @@ impl PPMWritable for Image {
         writeln!(&mut writer, "P6")?;
         writeln!(&mut writer, "{} {} 255", self.width, self.height)?;
         for color in &(self.data) {
-            for &channel in &[color.red, color.green, color.blue] {
-                let ch = channel as u8;
-                writer.write_all(&[ch])?;
-            }
+            writer.write_all(&[color.red, color.green, color.blue])?;
         }
         Ok(())
     }
@@ -44,7 +41,8 @@ pub fn main() {
             };
         }
     }
-    image.write_ppm("./test_image.ppm").unwrap();
+    // call the trait's method rather than the one the bitmap library has
+    PPMWritable::write_ppm(&image, "./test_image.ppm").unwrap();
 }
 
 #[cfg(test)]
@@ -74,7 +72,7 @@ mod tests {
             self::rand::random::<i32>(),
         );
         // Can't use try! macro because we want to panic, not return.
-        image.write_ppm(&fname).unwrap();
+        PPMWritable::write_ppm(&image, &fname).unwrap();
 
         let file = File::open(&fname).unwrap();
         let mut reader = BufReader::new(file);
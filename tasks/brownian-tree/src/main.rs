@@ -1,9 +1,7 @@
-use std::cmp::min;
 use std::env;
-use std::path::Path;
 use std::process;
 
-use image::ColorType;
+use bitmap::GrayImage;
 use rand::distributions::Uniform;
 use rand::{thread_rng, Rng};
 
@@ -13,7 +11,7 @@ fn help() {
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let mut output_path = Path::new("out.png");
+    let mut output_path = "out.pgm";
     let mut mote_count: u32 = 10_000;
     let mut width: usize = 512;
     let mut height: usize = 512;
@@ -21,7 +19,7 @@ fn main() {
     match args.len() {
         1 => {}
         4 => {
-            output_path = Path::new(&args[1]);
+            output_path = &args[1];
             mote_count = args[2].parse::<u32>().unwrap();
             width = args[3].parse::<usize>().unwrap();
             height = width;
@@ -34,35 +32,24 @@ fn main() {
 
     assert!(width >= 2);
 
-    // Base 1d array
-    let mut field_raw = vec![0u8; width * height];
-    populate_tree(&mut field_raw, width, height, mote_count);
-
-    // Balance image for 8-bit grayscale
-    let our_max = field_raw.iter().max().unwrap();
-    let fudge = u8::max_value() / our_max;
-    let balanced: Vec<u8> = field_raw.iter().map(|e| e * fudge).collect();
-
-    match image::save_buffer(
-        output_path,
-        &balanced,
-        width as u32,
-        height as u32,
-        ColorType::L8,
-    ) {
+    // Count of motes stuck at each point
+    let mut field = GrayImage::new(width, height);
+    populate_tree(&mut field, mote_count);
+
+    // Balance image so that the highest count is white
+    field.maxval = *field.data.iter().max().unwrap();
+
+    match field.write_pgm(output_path) {
         Err(e) => println!("Error writing output image:\n{}", e),
-        Ok(_) => println!("Output written to:\n{}", output_path.to_str().unwrap()),
+        Ok(_) => println!("Output written to:\n{}", output_path),
     }
 }
 
-fn populate_tree(raw: &mut Vec<u8>, width: usize, height: usize, mc: u32) {
-    // Vector of 'width' elements slices
-    let mut field_base: Vec<_> = raw.chunks_mut(width).collect();
-    // Addressable 2d vector
-    let field = field_base.as_mut_slice();
+fn populate_tree(field: &mut GrayImage, mc: u32) {
+    let (width, height) = (field.width, field.height);
 
     // Seed mote
-    field[width / 2][height / 2] = 1;
+    field[(width / 2, height / 2)] = 1;
 
     let x_spawn_range = Uniform::new(1, width - 1);
     let y_spawn_range = Uniform::new(1, height - 1);
@@ -78,23 +65,25 @@ fn populate_tree(raw: &mut Vec<u8>, width: usize, height: usize, mc: u32) {
         let mut y = rng.sample(y_spawn_range);
 
         // Increment field value when motes spawn on top of the structure
-        if field[x][y] > 0 {
-            field[x][y] = min(field[x][y] + 1, u8::max_value()) as u8;
+        if field[(x, y)] > 0 {
+            field[(x, y)] = field[(x, y)].saturating_add(1);
             continue;
         }
 
         loop {
-            let contacts = field[x - 1][y - 1]
-                + field[x][y - 1]
-                + field[x + 1][y - 1]
-                + field[x - 1][y]
-                + field[x + 1][y]
-                + field[x - 1][y + 1]
-                + field[x][y + 1]
-                + field[x + 1][y + 1];
-
-            if contacts > 0 {
-                field[x][y] = min(field[x][y] + 1, u8::max_value()) as u8;
+            let contacts = [
+                field[(x - 1, y - 1)],
+                field[(x, y - 1)],
+                field[(x + 1, y - 1)],
+                field[(x - 1, y)],
+                field[(x + 1, y)],
+                field[(x - 1, y + 1)],
+                field[(x, y + 1)],
+                field[(x + 1, y + 1)],
+            ];
+
+            if contacts.iter().any(|&count| count > 0) {
+                field[(x, y)] = field[(x, y)].saturating_add(1);
                 break;
             } else {
                 let range = Uniform::new(-1, 2);
@@ -114,15 +103,14 @@ fn populate_tree(raw: &mut Vec<u8>, width: usize, height: usize, mc: u32) {
 #[cfg(test)]
 mod tests {
     use super::populate_tree;
+    use bitmap::GrayImage;
 
     #[test]
     fn test_brownian_tree() {
-        let width = 128;
-        let height = 128;
         let mote_count = 1000;
-        let mut field_raw = vec![0u8; width * height];
-        populate_tree(&mut field_raw, width, height, mote_count);
-        let our_max = field_raw.iter().max().unwrap();
+        let mut field = GrayImage::new(128, 128);
+        populate_tree(&mut field, mote_count);
+        let our_max = field.data.iter().max().unwrap();
         assert!(*our_max >= 1);
     }
 }
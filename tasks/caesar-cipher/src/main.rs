@@ -1,3 +1,7 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
 use std::fmt::Display;
 use std::io::{self, Write};
 use std::{env, process};
@@ -53,4 +57,11 @@ mod tests {
         let encoded = "Wkh ilyh eralqj zlcdugv mxps txlfnob";
         assert_eq!(super::cipher(original, 3), encoded);
     }
+
+    test_cipher! {
+        encrypt: |text| super::cipher(text, 3),
+        decrypt: |text| super::cipher(text, 23),
+        alphabet: "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789 .,!?",
+        vectors: ["The five boxing wizards jump quickly" => "Wkh ilyh eralqj zlcdugv mxps txlfnob"],
+    }
 }
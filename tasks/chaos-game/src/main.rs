@@ -1,6 +1,6 @@
 use std::f32::consts::PI;
 
-use image::DynamicImage::ImageLuma8;
+use bitmap::{Canvas, GrayImage};
 use rand::prelude::*;
 
 fn main() {
@@ -8,8 +8,8 @@ fn main() {
     let img_side = 800;
     let tri_size = 400.0;
 
-    // Create a new ImgBuf
-    let mut imgbuf = image::ImageBuffer::new(img_side, img_side);
+    // Create a new image
+    let mut image = GrayImage::new(img_side, img_side);
 
     // Create triangle vertices
     let mut vertices = [(0.0, 0.0); 3];
@@ -18,7 +18,7 @@ fn main() {
         *y = (img_side as f32 / 2.) + (tri_size / 2.) * (PI * i as f32 * 2. / 3.).sin();
     }
     for &(x, y) in &vertices {
-        imgbuf.put_pixel(x as u32, y as u32, image::Luma([255]));
+        image.put(x as isize, y as isize, 255);
     }
 
     // Iterate chaos game
@@ -30,8 +30,8 @@ fn main() {
         x = (x + choice_x) / 2.0;
         y = (y + choice_y) / 2.0;
 
-        imgbuf.put_pixel(x as u32, y as u32, image::Luma([255]));
+        image.put(x as isize, y as isize, 255);
     }
 
-    ImageLuma8(imgbuf).save("fractal.png").unwrap();
+    image.write_pgm("fractal.pgm").unwrap();
 }
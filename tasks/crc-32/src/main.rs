@@ -1,3 +1,7 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
 fn main() {
     let s = "The quick brown fox jumps over the lazy dog";
     println!("{:X}", crc(s.as_bytes()));
@@ -30,3 +34,13 @@ fn test() {
     let s = "The quick brown fox jumps over the lazy dog";
     assert_eq!(crc(s.as_bytes()), 0x414F_A339);
 }
+
+#[cfg(test)]
+mod tests {
+    test_hash! {
+        super::crc,
+        b"" => "00000000",
+        b"123456789" => "cbf43926",
+        b"The quick brown fox jumps over the lazy dog" => "414fa339",
+    }
+}
@@ -1,102 +1,19 @@
 //! Contributed by Gavin Baker <gavinb@antonym.org>
 //! Adapted from the Go version
 
-use std::fs::File;
-use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
-use std::iter::repeat;
-
-/// Simple 8-bit grayscale image
-struct ImageGray8 {
-    width: usize,
-    height: usize,
-    data: Vec<u8>,
-}
-
-fn load_pgm(filename: &str) -> io::Result<ImageGray8> {
-    // Open file
-    let mut file = BufReader::new(File::open(filename)?);
-
-    // Read header
-    let mut magic_in = String::new();
-    let _ = file.read_line(&mut magic_in)?;
-    let mut width_in = String::new();
-    let _ = file.read_line(&mut width_in)?;
-    let mut height_in = String::new();
-    let _ = file.read_line(&mut height_in)?;
-    let mut maxval_in = String::new();
-    let _ = file.read_line(&mut maxval_in)?;
-
-    assert_eq!(magic_in, "P5\n");
-    assert_eq!(maxval_in, "255\n");
-
-    // Parse header
-    let width = width_in
-        .trim()
-        .parse::<usize>()
-        .map_err(|_| io::ErrorKind::InvalidData)?;
-    let height: usize = height_in
-        .trim()
-        .parse::<usize>()
-        .map_err(|_| io::ErrorKind::InvalidData)?;
-
-    println!("Reading pgm file {}: {} x {}", filename, width, height);
-
-    // Create image and allocate buffer
-    let mut img = ImageGray8 {
-        width,
-        height,
-        data: vec![],
-    };
-
-    // Read image data
-    let expected_bytes = width * height;
-    let bytes_read = file.read_to_end(&mut img.data)?;
-    if bytes_read != expected_bytes {
-        let kind = if bytes_read < expected_bytes {
-            io::ErrorKind::UnexpectedEof
-        } else {
-            io::ErrorKind::InvalidData
-        };
-        let msg = format!("expected {} bytes", expected_bytes);
-        return Err(io::Error::new(kind, msg));
-    }
-
-    Ok(img)
-}
-
-fn save_pgm(img: &ImageGray8, filename: &str) {
-    // Open file
-    let mut file = BufWriter::new(File::create(filename).unwrap());
-
-    // Write header
-    if let Err(e) = writeln!(&mut file, "P5\n{}\n{}\n255", img.width, img.height) {
-        println!("Failed to write header: {}", e);
-    }
-
-    println!(
-        "Writing pgm file {}: {} x {}",
-        filename, img.width, img.height
-    );
-
-    // Write binary image data
-    if let Err(e) = file.write_all(&(img.data[..])) {
-        println!("Failed to image data: {}", e);
-    }
-}
+use bitmap::GrayImage;
 
 #[allow(clippy::cast_precision_loss)]
 #[allow(clippy::cast_possible_truncation)]
-fn hough(image: &ImageGray8, out_width: usize, out_height: usize) -> ImageGray8 {
+fn hough(image: &GrayImage, out_width: usize, out_height: usize) -> GrayImage {
     let in_width = image.width;
     let in_height = image.height;
 
     // Allocate accumulation buffer
-    let out_height = ((out_height / 2) * 2) as usize;
-    let mut accum = ImageGray8 {
-        width: out_width,
-        height: out_height,
-        data: repeat(255).take(out_width * out_height).collect(),
-    };
+    let out_height = (out_height / 2) * 2;
+    let mut accum = GrayImage::new(out_width, out_height);
+    accum.maxval = image.maxval;
+    accum.data = vec![image.maxval; out_width * out_height];
 
     // Transform extents
     let rmax = (in_width as f64).hypot(in_height as f64);
@@ -108,7 +25,7 @@ fn hough(image: &ImageGray8, out_width: usize, out_height: usize) -> ImageGray8
         for x in 0..in_width {
             let in_idx = y * in_width + x;
             let col = image.data[in_idx];
-            if col == 255 {
+            if col == image.maxval {
                 continue;
             }
 
@@ -117,7 +34,7 @@ fn hough(image: &ImageGray8, out_width: usize, out_height: usize) -> ImageGray8
                 let th = dth * (jtx as f64);
                 let r = (x as f64) * (th.cos()) + (y as f64) * (th.sin());
 
-                let iry = out_height as i64 / 2 - (r / (dr as f64) + 0.5).floor() as i64;
+                let iry = out_height as i64 / 2 - (r / dr + 0.5).floor() as i64;
                 #[allow(clippy::cast_sign_loss)]
                 let out_idx = (jtx as i64 + iry * out_width as i64) as usize;
                 let col = accum.data[out_idx];
@@ -130,9 +47,38 @@ fn hough(image: &ImageGray8, out_width: usize, out_height: usize) -> ImageGray8
     accum
 }
 
-fn main() -> io::Result<()> {
-    let image = load_pgm("resources/Pentagon.pgm")?;
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let image = GrayImage::read_pgm("resources/Pentagon.pgm")?;
+    println!(
+        "Read pgm file resources/Pentagon.pgm: {} x {}",
+        image.width, image.height
+    );
+
     let accum = hough(&image, 460, 360);
-    save_pgm(&accum, "hough.pgm");
+    accum.write_pgm("hough.pgm")?;
+    println!(
+        "Wrote pgm file hough.pgm: {} x {}",
+        accum.width, accum.height
+    );
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use bitmap::GrayImage;
+
+    use super::hough;
+
+    #[test]
+    fn keeps_maxval() {
+        let mut image = GrayImage::new(4, 4);
+        image.maxval = 1000;
+        image.data = vec![1000; 16];
+        image.data[5] = 0;
+
+        let accum = hough(&image, 8, 6);
+        assert_eq!(accum.maxval, 1000);
+        assert_eq!(accum.data.iter().max(), Some(&1000));
+        assert!(accum.data.contains(&999));
+    }
+}
@@ -1,3 +1,7 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
 use std::collections::hash_map::HashMap;
 
 /// Compress using LZW
@@ -43,8 +47,12 @@ fn decompress(compressed: &[i32]) -> String {
         dictionary.insert(i, vec![i as u8]);
     }
 
-    let mut w = vec![compressed[0] as u8];
-    let compressed = &compressed[1..];
+    let (&first, compressed) = match compressed.split_first() {
+        Some(split) => split,
+        None => return String::new(),
+    };
+
+    let mut w = vec![first as u8];
     let mut result = w.clone();
     for &k in compressed {
         let entry = match dictionary.get(&k) {
@@ -97,3 +105,13 @@ fn test_example() {
         [84i32, 79, 66, 69, 79, 82, 78, 79, 84, 256, 258, 260, 265, 259, 261, 263,]
     );
 }
+
+#[cfg(test)]
+mod tests {
+    test_codec! {
+        encode: |original: &str| super::compress(original),
+        decode: |compressed: &[i32]| super::decompress(compressed),
+        arbitrary: |rng| meta::test_utils::random_string(rng, "TOBERN", 200),
+        vectors: ["TOBEORNOT" => &[84, 79, 66, 69, 79, 82, 78, 79, 84]],
+    }
+}
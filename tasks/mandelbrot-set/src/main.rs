@@ -1,9 +1,9 @@
-use image::DynamicImage::ImageLuma8;
+use bitmap::GrayImage;
 use num_complex::Complex;
 
 fn main() {
     let max_iterations = 256u16;
-    let img_side = 800u32;
+    let img_side = 800;
     let cxmin = -2f32;
     let cxmax = 1f32;
     let cymin = -1.5f32;
@@ -11,11 +11,13 @@ fn main() {
     let scalex = (cxmax - cxmin) / img_side as f32;
     let scaley = (cymax - cymin) / img_side as f32;
 
-    // Create a new ImgBuf
-    let mut imgbuf = image::ImageBuffer::new(img_side, img_side);
+    // Create a new image, with a gray level for each iteration count
+    let mut image = GrayImage::new(img_side, img_side);
+    image.maxval = max_iterations - 1;
 
     // Calculate for each pixel
-    for (x, y, pixel) in imgbuf.enumerate_pixels_mut() {
+    for (index, pixel) in image.data.iter_mut().enumerate() {
+        let (x, y) = (index % img_side, index / img_side);
         let cx = cxmin + x as f32 * scalex;
         let cy = cymin + y as f32 * scaley;
 
@@ -31,8 +33,8 @@ fn main() {
             i = t;
         }
 
-        *pixel = image::Luma([i as u8]);
+        *pixel = i;
     }
 
-    ImageLuma8(imgbuf).save("fractal.png").unwrap();
+    image.write_pgm("fractal.pgm").unwrap();
 }
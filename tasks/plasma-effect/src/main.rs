@@ -1,5 +1,4 @@
-use image::ColorType;
-use std::path::Path;
+use bitmap::{Color, Image};
 
 // Framebuffer dimensions
 const WIDTH: usize = 640;
@@ -26,9 +25,9 @@ fn create_plasma_lut() -> Vec<f64> {
     plasma
 }
 
-/// Convert from HSV float(1.0,1.0,1.0) to RGB u8 tuple (255,255,255).
+/// Convert from HSV float(1.0,1.0,1.0) to RGB u8 color (255,255,255).
 /// From https://crates.io/crates/palette 0.5.0 rgb.rs, simplified for example
-fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> (u8, u8, u8) {
+fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> Color {
     let c = value * saturation;
     let h = hue * 6.0;
     let x = c * (1.0 - (h % 2.0 - 1.0).abs());
@@ -42,37 +41,29 @@ fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> (u8, u8, u8) {
         _ => (c, 0.0, x),
     };
     // Convert back to RGB (where components are integers from 0 to 255)
-    (
-        ((red + m) * 255.0).round() as u8,
-        ((green + m) * 255.0).round() as u8,
-        ((blue + m) * 255.0).round() as u8,
-    )
+    Color {
+        red: ((red + m) * 255.0).round() as u8,
+        green: ((green + m) * 255.0).round() as u8,
+        blue: ((blue + m) * 255.0).round() as u8,
+    }
 }
+
 fn main() {
-    // The bitmap/framebuffer for our application. 3 u8 elements per output pixel
-    let mut framebuffer: Vec<u8> = vec![0; WIDTH * HEIGHT * 3];
+    // The bitmap/framebuffer for our application
+    let mut framebuffer = Image::new(WIDTH, HEIGHT);
     // Generate a lookup table so we don't do too much math for every pixel.
     // Do it in a function so that the local one can be immutable.
     let plasma_lookup_table = create_plasma_lut();
-    // For each (r,g,b) pixel in our output buffer
-    for (index, rgb) in framebuffer.chunks_mut(3).enumerate() {
+    // For each pixel in our output buffer
+    for (index, color) in framebuffer.data.iter_mut().enumerate() {
         // Lookup the precalculated plasma value
         let hue_lookup = plasma_lookup_table[index] % 1.0;
-        let (red, green, blue) = hsv_to_rgb(hue_lookup, 1.0, 1.0);
-        rgb[0] = red;
-        rgb[1] = green;
-        rgb[2] = blue;
+        *color = hsv_to_rgb(hue_lookup, 1.0, 1.0);
     }
-    // Save our plasma image to out.png
-    let output_path = Path::new("out.png");
-    match image::save_buffer(
-        output_path,
-        framebuffer.as_slice(),
-        WIDTH as u32,
-        HEIGHT as u32,
-        ColorType::Rgb8,
-    ) {
+    // Save our plasma image to out.ppm
+    let output_path = "out.ppm";
+    match framebuffer.write_ppm(output_path) {
         Err(e) => println!("Error writing output image:\n{}", e),
-        Ok(_) => println!("Output written to:\n{}", output_path.to_str().unwrap()),
+        Ok(_) => println!("Output written to:\n{}", output_path),
     }
 }
@@ -16,6 +16,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::ripemd160;
+    use meta::test_hash;
 
     #[test]
     fn test_ripemd160() {
@@ -29,4 +30,11 @@ mod tests {
             "b0e20b6e3116640286ed3a87a5713079b21f5189"
         );
     }
+
+    test_hash! {
+        ripemd160,
+        "a" => "0bdc9d2d256b3ee9daae347be6f4dc835a467ffe",
+        "abc" => "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc",
+        "message digest" => "5d0689ef49d2fae572b881b123a85ffa21595f36",
+    }
 }
@@ -0,0 +1,256 @@
+//! Counting examples by language, and ranking languages by how many tasks they implement.
+
+use std::collections::BTreeMap;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::TaskPage;
+
+lazy_static! {
+    /// Matches a top-level language header, such as `=={{header|Rust}}==`, capturing the name of
+    /// the language. Sub-headings like `==={{header|...}}===` are not examples of their own.
+    static ref HEADER_RE: Regex =
+        Regex::new(r"(?m)^==\s*\{\{\s*header\s*\|\s*([^|}]*[^|}\s])\s*(?:\|[^}]*)?\}\}").unwrap();
+}
+
+/// Counts the examples on a page for each language, keyed by the name of the language.
+pub fn count_languages(content: &str) -> BTreeMap<String, u32> {
+    let mut counts = BTreeMap::new();
+    for captures in HEADER_RE.captures_iter(content) {
+        *counts.entry(captures[1].to_owned()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// The examples of a single task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskCount {
+    pub title: String,
+
+    /// The total number of examples, in any language.
+    pub examples: u32,
+
+    /// The number of examples in each language.
+    pub languages: BTreeMap<String, u32>,
+}
+
+impl TaskCount {
+    pub fn new(page: &TaskPage) -> TaskCount {
+        let languages = count_languages(&page.content);
+        TaskCount {
+            title: page.title.clone(),
+            examples: languages.values().sum(),
+            languages,
+        }
+    }
+}
+
+/// The examples of a single language across every task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageTotal {
+    /// The position of the language when ranked by the number of tasks it implements. Languages
+    /// that implement the same number of tasks share a rank.
+    pub rank: usize,
+
+    pub language: String,
+
+    /// The number of tasks with at least one example in the language.
+    pub tasks: u32,
+
+    /// The number of examples in the language.
+    pub examples: u32,
+}
+
+/// Example counts for every task and every language.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Report {
+    /// The tasks, sorted by title.
+    pub tasks: Vec<TaskCount>,
+
+    /// The languages, sorted by rank and then by name.
+    pub languages: Vec<LanguageTotal>,
+}
+
+impl Report {
+    pub fn new(pages: &[TaskPage]) -> Report {
+        let mut tasks = pages.iter().map(TaskCount::new).collect::<Vec<_>>();
+        tasks.sort_by(|a, b| a.title.cmp(&b.title));
+
+        let mut totals = BTreeMap::new();
+        for task in &tasks {
+            for (language, &examples) in &task.languages {
+                let total = totals.entry(language.as_str()).or_insert((0, 0));
+                total.0 += 1;
+                total.1 += examples;
+            }
+        }
+
+        let mut totals = totals.into_iter().collect::<Vec<_>>();
+        totals.sort_by(|(a, (a_tasks, _)), (b, (b_tasks, _))| b_tasks.cmp(a_tasks).then(a.cmp(b)));
+
+        let mut languages = Vec::<LanguageTotal>::with_capacity(totals.len());
+        for (i, (language, (tasks, examples))) in totals.into_iter().enumerate() {
+            let rank = match languages.last() {
+                Some(previous) if previous.tasks == tasks => previous.rank,
+                _ => i + 1,
+            };
+            languages.push(LanguageTotal {
+                rank,
+                language: language.to_owned(),
+                tasks,
+                examples,
+            });
+        }
+
+        Report { tasks, languages }
+    }
+
+    /// Returns the counts of the task with the given title.
+    pub fn task(&self, title: &str) -> Option<&TaskCount> {
+        self.tasks.iter().find(|task| task.title == title)
+    }
+}
+
+/// How the section of a language on a task page changed between two reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionChange {
+    /// The task no longer has a section for the language.
+    Disappeared,
+
+    /// The section now has a different header that still looks like the language, such as
+    /// `{{header|rust}}` instead of `{{header|Rust}}`.
+    Renamed { to: String },
+}
+
+/// Simplifies the name of a language for comparison, by lowercasing it and removing anything that
+/// isn't alphanumeric.
+fn simplify(language: &str) -> String {
+    language
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Finds the tasks that had a section for `language` in the previous report, but not in the
+/// current one. Tasks that are missing from either report are ignored.
+pub fn section_changes(
+    previous: &Report,
+    current: &Report,
+    language: &str,
+) -> Vec<(String, SectionChange)> {
+    let simplified = simplify(language);
+
+    current
+        .tasks
+        .iter()
+        .filter(|task| !task.languages.contains_key(language))
+        .filter(|task| {
+            previous
+                .task(&task.title)
+                .is_some_and(|previous| previous.languages.contains_key(language))
+        })
+        .map(|task| {
+            let renamed = task
+                .languages
+                .keys()
+                .find(|other| simplify(other).contains(&simplified));
+
+            let change = match renamed {
+                Some(to) => SectionChange::Renamed { to: to.clone() },
+                None => SectionChange::Disappeared,
+            };
+            (task.title.clone(), change)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_languages, section_changes, Report, SectionChange};
+    use crate::TaskPage;
+
+    fn page(title: &str, content: &str) -> TaskPage {
+        TaskPage {
+            title: title.to_owned(),
+            content: content.to_owned(),
+        }
+    }
+
+    #[test]
+    fn headers() {
+        let content = "\
+Task description mentioning =={{header|Fake}}== inline.
+=={{header|Ada}}==
+<lang ada>null;</lang>
+== {{header| C sharp |C#}} ==
+==={{header|Nested}}===
+=={{header|Ada}}==
+";
+        let counts = count_languages(content);
+        assert_eq!(
+            counts.into_iter().collect::<Vec<_>>(),
+            [("Ada".to_owned(), 2), ("C sharp".to_owned(), 1)]
+        );
+    }
+
+    #[test]
+    fn ranking() {
+        let report = Report::new(&[
+            page(
+                "A",
+                "=={{header|Rust}}==\n=={{header|C}}==\n=={{header|C}}==\n",
+            ),
+            page("B", "=={{header|Rust}}==\n=={{header|Go}}==\n"),
+            page("C", "=={{header|Go}}==\n=={{header|Ada}}==\n"),
+        ]);
+
+        assert_eq!(report.task("A").unwrap().examples, 3);
+
+        let ranks = report
+            .languages
+            .iter()
+            .map(|l| (l.rank, l.language.as_str(), l.tasks, l.examples))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranks,
+            [
+                (1, "Go", 2, 2),
+                (1, "Rust", 2, 2),
+                (3, "Ada", 1, 1),
+                (3, "C", 1, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn rust_section_changes() {
+        let previous = Report::new(&[
+            page("Kept", "=={{header|Rust}}=="),
+            page("Renamed", "=={{header|Rust}}=="),
+            page("Removed", "=={{header|Rust}}==\n=={{header|C}}=="),
+            page("Never", "=={{header|C}}=="),
+        ]);
+        let current = Report::new(&[
+            page("Kept", "=={{header|Rust}}=="),
+            page("Renamed", "=={{header|rust-lang}}=="),
+            page("Removed", "=={{header|C}}=="),
+            page("Never", "=={{header|C}}=="),
+        ]);
+
+        assert_eq!(
+            section_changes(&previous, &current, "Rust"),
+            [
+                ("Removed".to_owned(), SectionChange::Disappeared),
+                (
+                    "Renamed".to_owned(),
+                    SectionChange::Renamed {
+                        to: "rust-lang".to_owned()
+                    }
+                ),
+            ]
+        );
+    }
+}
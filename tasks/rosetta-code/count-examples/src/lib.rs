@@ -1,106 +1,80 @@
-use reqwest::Url;
-use serde::Deserialize;
-use serde_json::Value;
+use std::time::Instant;
 
-#[derive(Deserialize)]
-pub struct Task {
-    #[serde(rename = "pageid")]
-    page_id: u64,
-    pub title: String,
-}
+use mediawiki::{Cache, Result, Source, Summary};
 
-#[derive(Debug)]
-enum ParseError {
-    /// Something went wrong with the HTTP request to the API.
-    Http(reqwest::Error),
+pub use mediawiki::CategoryMember as Task;
+/// The content of a task page.
+pub use mediawiki::PageContent as TaskPage;
 
-    /// There was a problem parsing the API response into JSON.
-    Json(serde_json::Error),
+pub mod languages;
 
-    /// Unexpected JSON format from response
-    UnexpectedFormat,
+pub fn query_all_tasks(source: &dyn Source) -> Result<Vec<Task>> {
+    mediawiki::category_members(source, "Programming_Tasks")
 }
 
-impl From<serde_json::Error> for ParseError {
-    fn from(error: serde_json::Error) -> Self {
-        ParseError::Json(error)
-    }
-}
+/// Fetches the content of every task page, like [`mediawiki::fetch_contents`].
+pub fn fetch_pages(
+    source: &(dyn Source + Sync),
+    cache: Option<&Cache>,
+    workers: usize,
+) -> Result<(Vec<TaskPage>, Summary)> {
+    let start = Instant::now();
 
-impl From<reqwest::Error> for ParseError {
-    fn from(error: reqwest::Error) -> Self {
-        ParseError::Http(error)
-    }
-}
+    let tasks = query_all_tasks(source)?;
+    let titles = tasks
+        .iter()
+        .map(|task| task.title.as_str())
+        .collect::<Vec<_>>();
 
-fn construct_query_category(category: &str) -> Url {
-    let mut base_url = Url::parse("http://rosettacode.org/mw/api.php").unwrap();
-    let cat = format!("Category:{}", category);
-    let query_pairs = vec![
-        ("action", "query"),
-        ("format", "json"),
-        ("list", "categorymembers"),
-        ("cmlimit", "500"),
-        ("cmtitle", &cat),
-        ("continue", ""),
-    ];
-    base_url
-        .query_pairs_mut()
-        .extend_pairs(query_pairs.into_iter());
-    base_url
+    let (pages, summary) = mediawiki::fetch_contents(source, &titles, cache, workers)?;
+    Ok((
+        pages,
+        Summary {
+            elapsed: start.elapsed(),
+            ..summary
+        },
+    ))
 }
 
-fn construct_query_task_content(task_id: &str) -> Url {
-    let mut base_url = Url::parse("http://rosettacode.org/mw/api.php").unwrap();
-    let mut query_pairs = vec![
-        ("action", "query"),
-        ("format", "json"),
-        ("prop", "revisions"),
-        ("rvprop", "content"),
-    ];
-    query_pairs.push(("pageids", task_id));
-    base_url
-        .query_pairs_mut()
-        .extend_pairs(query_pairs.into_iter());
-    base_url
-}
+#[cfg(test)]
+mod tests {
+    use mediawiki::{Result, Source};
+    use serde_json::json;
 
-fn query_api(url: Url) -> Result<Value, ParseError> {
-    Ok(reqwest::blocking::get(url)?.json()?)
-}
+    use super::fetch_pages;
 
-fn parse_all_tasks(reply: &Value) -> Result<Vec<Task>, ParseError> {
-    let tasks_json = reply
-        .pointer("/query/categorymembers")
-        .and_then(|tasks| tasks.as_array())
-        .ok_or(ParseError::UnexpectedFormat)?;
+    /// A wiki with two tasks, each of which has an example in its own language.
+    struct FakeWiki;
 
-    tasks_json
-        .iter()
-        .map(|json| Task::deserialize(json).map_err(From::from))
-        .collect()
-}
+    impl Source for FakeWiki {
+        fn query(&self, params: &[(&str, &str)]) -> Result<String> {
+            let param = |name| params.iter().find(|(k, _)| *k == name).map(|(_, v)| *v);
 
-fn count_number_examples(task: &Value, task_id: u64) -> Result<u32, ParseError> {
-    let revisions = task
-        .pointer(&format!("/query/pages/{}/revisions", task_id))
-        .and_then(|content| content.as_array())
-        .ok_or(ParseError::UnexpectedFormat)?;
-    let content = revisions[0]
-        .get("*")
-        .and_then(Value::as_str)
-        .ok_or(ParseError::UnexpectedFormat)?;
-    Ok(content.split("=={{header").count() as u32)
-}
+            let response = match param("prop") {
+                None => json!({ "query": { "categorymembers": [
+                    { "pageid": 2, "title": "FizzBuzz" },
+                    { "pageid": 1, "title": "100 doors" },
+                ]}}),
+                Some(_) => json!({ "query": { "pages": {
+                    "1": { "title": "100 doors", "revisions": [{ "*": "=={{header|Rust}}==" }] },
+                    "2": { "title": "FizzBuzz", "revisions": [{ "*": "=={{header|Go}}==" }] },
+                }}}),
+            };
 
-pub fn query_all_tasks() -> Vec<Task> {
-    let query = construct_query_category("Programming_Tasks");
-    let json: Value = query_api(query).unwrap();
-    parse_all_tasks(&json).unwrap()
-}
+            Ok(response.to_string())
+        }
+    }
+
+    #[test]
+    fn fetches_every_task() {
+        let (pages, summary) = fetch_pages(&FakeWiki, None, 2).unwrap();
 
-pub fn query_a_task(task: &Task) -> u32 {
-    let query = construct_query_task_content(&task.page_id.to_string());
-    let json: Value = query_api(query).unwrap();
-    count_number_examples(&json, task.page_id).unwrap()
+        let titles = pages
+            .iter()
+            .map(|page| page.title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(titles, ["100 doors", "FizzBuzz"]);
+        assert_eq!(pages[1].content, "=={{header|Go}}==");
+        assert_eq!((summary.pages, summary.fetched), (2, 2));
+    }
 }
@@ -1,8 +1,112 @@
 extern crate count_examples;
-fn main() {
-    let all_tasks = count_examples::query_all_tasks();
-    for task in &all_tasks {
-        let count = count_examples::query_a_task(task);
-        println!("Task: {} has {} examples", task.title, count);
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use count_examples::languages::{self, Report, SectionChange};
+use mediawiki::{Cache, Client};
+use structopt::clap::arg_enum;
+use structopt::StructOpt;
+
+arg_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Format {
+        Text,
+        Json
     }
 }
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The number of pages to download at the same time
+    #[structopt(short, long, default_value = "4")]
+    workers: usize,
+
+    /// The directory to cache pages in, so that only pages that changed since the last run are
+    /// downloaded. Defaults to a directory in the system's temporary directory
+    #[structopt(long, parse(from_os_str))]
+    cache_dir: Option<PathBuf>,
+
+    /// Download every page, without reading or updating the cache
+    #[structopt(long, conflicts_with = "cache-dir")]
+    no_cache: bool,
+
+    /// The format of the output. JSON includes the counts of every language for every task
+    #[structopt(
+        long,
+        possible_values = &Format::variants(),
+        case_insensitive = true,
+        default_value = "text"
+    )]
+    format: Format,
+
+    /// A JSON report from a previous run, to warn about tasks whose section for `--language`
+    /// disappeared or was renamed since then
+    #[structopt(long, parse(from_os_str))]
+    previous: Option<PathBuf>,
+
+    /// The language whose sections are checked against the previous report
+    #[structopt(long, default_value = "Rust")]
+    language: String,
+}
+
+fn print_text(report: &Report) {
+    for task in &report.tasks {
+        println!("Task: {} has {} examples", task.title, task.examples);
+    }
+
+    println!();
+    println!(
+        "{:>5}  {:<30} {:>6} {:>8}",
+        "Rank", "Language", "Tasks", "Examples"
+    );
+    for language in &report.languages {
+        println!(
+            "{:>5}  {:<30} {:>6} {:>8}",
+            language.rank, language.language, language.tasks, language.examples
+        );
+    }
+}
+
+fn main() -> mediawiki::Result<()> {
+    let opt = Opt::from_args();
+
+    let cache = if opt.no_cache {
+        None
+    } else {
+        let directory = opt
+            .cache_dir
+            .unwrap_or_else(|| env::temp_dir().join("count-examples"));
+        Some(Cache::open(directory)?)
+    };
+
+    let client = Client::default();
+    let (pages, summary) = count_examples::fetch_pages(&client, cache.as_ref(), opt.workers)?;
+    let report = Report::new(&pages);
+
+    match opt.format {
+        Format::Text => print_text(&report),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+
+    if let Some(previous) = opt.previous {
+        let previous: Report = serde_json::from_str(&fs::read_to_string(previous)?)?;
+
+        for (title, change) in languages::section_changes(&previous, &report, &opt.language) {
+            match change {
+                SectionChange::Disappeared => eprintln!(
+                    "warning: the {} section of \"{}\" disappeared",
+                    opt.language, title
+                ),
+                SectionChange::Renamed { to } => eprintln!(
+                    "warning: the {} section of \"{}\" was renamed to \"{}\"",
+                    opt.language, title, to
+                ),
+            }
+        }
+    }
+
+    eprintln!("{}", summary);
+    Ok(())
+}
@@ -0,0 +1,290 @@
+extern crate regex;
+
+use std::fmt;
+
+use regex::Regex;
+
+/// A bare language tag, without the name of a highlighter.
+const BARE_TAG: &str = "<lang>";
+
+/// The number of unchanged lines shown around each change in a diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// Matches any header, including those of languages with symbols in their names, such as `C++`.
+fn header_re() -> Regex {
+    Regex::new(r"==\{\{header\|(?P<lang>[^|}]+)(?:\|[^}]*)?\}\}==").unwrap()
+}
+
+/// Counts the lines with bare language tags under each header. The first pair holds the lines
+/// before any header.
+pub fn find_bare_lang_tags(input: &str) -> Vec<(Option<String>, i32)> {
+    let mut language_pairs = vec![];
+    let mut language = None;
+    let mut counter = 0_i32;
+
+    let header_re = Regex::new(r"==\{\{header\|(?P<lang>[[:alpha:]]+)\}\}==").unwrap();
+
+    for line in input.lines() {
+        if let Some(captures) = header_re.captures(line) {
+            if let Some(header_lang) = captures.name("lang") {
+                language_pairs.push((language, counter));
+                language = Some(header_lang.as_str().to_owned());
+                counter = 0;
+            }
+        }
+
+        if line.contains(BARE_TAG) {
+            counter += 1;
+        }
+    }
+
+    language_pairs.push((language, counter));
+    language_pairs
+}
+
+/// How a header maps to the name of a highlighter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution<'a> {
+    /// The header is the name of a highlighter, ignoring case.
+    Highlighter(&'a str),
+
+    /// The header only matches highlighters once symbols are spelled out, such as `C++` for
+    /// `cpp`, so it might be an alias for any of them.
+    Ambiguous(Vec<&'a str>),
+
+    /// No highlighter matches the header.
+    Unknown,
+}
+
+impl<'a> fmt::Display for Resolution<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Resolution::Highlighter(name) => write!(f, "{}", name),
+            Resolution::Ambiguous(ref names) => write!(f, "could be {}", names.join(" or ")),
+            Resolution::Unknown => write!(f, "no matching highlighter"),
+        }
+    }
+}
+
+/// Spells out the symbols in a language name and drops anything else that isn't alphanumeric, so
+/// that `C++` becomes `cpp` and `F#` becomes `fsharp`.
+fn simplify(name: &str) -> String {
+    name.to_lowercase()
+        .replace('+', "p")
+        .replace('#', "sharp")
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// Maps a header to one of the given highlighters.
+pub fn resolve_header<'a>(languages: &[&'a str], header: &str) -> Resolution<'a> {
+    let header = header.trim();
+
+    if let Some(&name) = languages
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(header))
+    {
+        return Resolution::Highlighter(name);
+    }
+
+    let simplified = simplify(header);
+    let candidates = languages
+        .iter()
+        .cloned()
+        .filter(|name| simplify(name) == simplified)
+        .collect::<Vec<_>>();
+
+    if candidates.is_empty() {
+        Resolution::Unknown
+    } else {
+        Resolution::Ambiguous(candidates)
+    }
+}
+
+/// A line with bare tags that could not be fixed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unfixed<'a> {
+    /// The line number, starting at 1.
+    pub line: usize,
+
+    /// The header that the line is under, if any.
+    pub header: Option<String>,
+
+    /// Why the header couldn't be used. `Resolution::Unknown` if there is no header.
+    pub resolution: Resolution<'a>,
+}
+
+/// Rewrites each bare `<lang>` tag to name the highlighter of the header it is under. Tags whose
+/// header doesn't name a highlighter exactly are left alone and returned instead.
+pub fn fix_bare_lang_tags<'a>(languages: &[&'a str], input: &str) -> (String, Vec<Unfixed<'a>>) {
+    let header_re = header_re();
+    let mut output = String::with_capacity(input.len());
+    let mut unfixed = vec![];
+    let mut header: Option<String> = None;
+
+    for (i, line) in input.split_inclusive('\n').enumerate() {
+        if let Some(captures) = header_re.captures(line) {
+            header = Some(captures["lang"].to_owned());
+        }
+
+        if !line.contains(BARE_TAG) {
+            output.push_str(line);
+            continue;
+        }
+
+        let resolution = match header {
+            Some(ref header) => resolve_header(languages, header),
+            None => Resolution::Unknown,
+        };
+
+        match resolution {
+            Resolution::Highlighter(name) => {
+                output.push_str(&line.replace(BARE_TAG, &format!("<lang {}>", name)));
+            }
+            resolution => {
+                output.push_str(line);
+                unfixed.push(Unfixed {
+                    line: i + 1,
+                    header: header.clone(),
+                    resolution,
+                });
+            }
+        }
+    }
+
+    (output, unfixed)
+}
+
+/// Formats the changes between two texts with the same number of lines as a unified diff.
+pub fn unified_diff(old_name: &str, new_name: &str, old: &str, new: &str) -> String {
+    let old_lines = old.lines().collect::<Vec<_>>();
+    let new_lines = new.lines().collect::<Vec<_>>();
+    assert_eq!(
+        old_lines.len(),
+        new_lines.len(),
+        "lines were added or removed"
+    );
+
+    let changed = (0..old_lines.len())
+        .filter(|&i| old_lines[i] != new_lines[i])
+        .collect::<Vec<_>>();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    // Group changes whose context overlaps into the same hunk.
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for &i in &changed {
+        let start = i.saturating_sub(DIFF_CONTEXT);
+        let end = (i + DIFF_CONTEXT + 1).min(old_lines.len());
+        match hunks.last_mut() {
+            Some(hunk) if start <= hunk.1 => hunk.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut diff = format!("--- {}\n+++ {}\n", old_name, new_name);
+    for (start, end) in hunks {
+        let len = end - start;
+        diff += &format!("@@ -{},{} +{},{} @@\n", start + 1, len, start + 1, len);
+
+        for i in start..end {
+            if old_lines[i] == new_lines[i] {
+                diff += &format!(" {}\n", old_lines[i]);
+            } else {
+                diff += &format!("-{}\n+{}\n", old_lines[i], new_lines[i]);
+            }
+        }
+    }
+
+    diff
+}
+
+#[test]
+fn test_bare_tags() {
+    let input = r#"
+    Description
+    <lang>Pseudocode</lang>
+
+    =={{header|C}}==
+    <lang C>printf("Hello world!\n");</lang>
+
+    =={{header|Perl}}==
+    <lang>print "Hello world!\n"</lang>"#;
+
+    let expected = vec![
+        (None, 1),
+        (Some("C".to_owned()), 0),
+        (Some("Perl".to_owned()), 1),
+    ];
+
+    assert_eq!(expected, find_bare_lang_tags(input));
+}
+
+#[test]
+fn test_resolve_header() {
+    let languages = ["c", "cpp", "cpp-qt", "csharp", "perl"];
+
+    assert_eq!(
+        resolve_header(&languages, "Perl"),
+        Resolution::Highlighter("perl")
+    );
+    assert_eq!(
+        resolve_header(&languages, "C++"),
+        Resolution::Ambiguous(vec!["cpp"])
+    );
+    assert_eq!(
+        resolve_header(&languages, "C sharp"),
+        Resolution::Ambiguous(vec!["csharp"])
+    );
+    assert_eq!(resolve_header(&languages, "F#"), Resolution::Unknown);
+}
+
+#[test]
+fn test_fix_and_diff() {
+    let languages = ["c", "cpp", "perl"];
+    let input = "Description\n\
+                 <lang>Pseudocode</lang>\n\
+                 =={{header|C}}==\n\
+                 <lang>int x;</lang>\n\
+                 =={{header|C++}}==\n\
+                 <lang>int x;</lang>\n\
+                 =={{header|Perl}}==\n\
+                 <lang>my $x;</lang>\n";
+
+    let (fixed, unfixed) = fix_bare_lang_tags(&languages, input);
+
+    assert_eq!(
+        unfixed,
+        [
+            Unfixed {
+                line: 2,
+                header: None,
+                resolution: Resolution::Unknown,
+            },
+            Unfixed {
+                line: 6,
+                header: Some("C++".to_owned()),
+                resolution: Resolution::Ambiguous(vec!["cpp"]),
+            },
+        ]
+    );
+
+    assert_eq!(
+        unified_diff("a", "b", input, &fixed),
+        "--- a\n\
+         +++ b\n\
+         @@ -1,8 +1,8 @@\n \
+         Description\n \
+         <lang>Pseudocode</lang>\n \
+         =={{header|C}}==\n\
+         -<lang>int x;</lang>\n\
+         +<lang c>int x;</lang>\n \
+         =={{header|C++}}==\n \
+         <lang>int x;</lang>\n \
+         =={{header|Perl}}==\n\
+         -<lang>my $x;</lang>\n\
+         +<lang perl>my $x;</lang>\n"
+    );
+}
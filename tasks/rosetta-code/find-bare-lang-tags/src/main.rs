@@ -1,40 +1,23 @@
-extern crate regex;
+extern crate find_bare_lang_tags;
+extern crate fix_code_tags;
+extern crate structopt;
 
 use std::io;
 use std::io::prelude::*;
 
-use regex::Regex;
+use find_bare_lang_tags::{find_bare_lang_tags, fix_bare_lang_tags, unified_diff};
+use structopt::StructOpt;
 
-fn find_bare_lang_tags(input: &str) -> Vec<(Option<String>, i32)> {
-    let mut language_pairs = vec![];
-    let mut language = None;
-    let mut counter = 0_i32;
-
-    let header_re = Regex::new(r"==\{\{header\|(?P<lang>[[:alpha:]]+)\}\}==").unwrap();
-
-    for line in input.lines() {
-        if let Some(captures) = header_re.captures(line) {
-            if let Some(header_lang) = captures.name("lang") {
-                language_pairs.push((language, counter));
-                language = Some(header_lang.as_str().to_owned());
-                counter = 0;
-            }
-        }
-
-        if line.contains("<lang>") {
-            counter += 1;
-        }
-    }
-
-    language_pairs.push((language, counter));
-    language_pairs
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// Print a unified diff that names the highlighter of each bare tag, based on the header it
+    /// is under, instead of counting the tags. Tags that can't be fixed are reported on stderr
+    #[structopt(long)]
+    fix: bool,
 }
 
-fn main() {
-    let stdin = io::stdin();
-    let mut buf = String::new();
-    stdin.lock().read_to_string(&mut buf).unwrap();
-    let results = find_bare_lang_tags(&buf);
+fn count(input: &str) {
+    let results = find_bare_lang_tags(input);
     let total_bare = results.iter().map(|r| r.1).sum::<i32>();
 
     println!("{} bare language tags.\n", total_bare);
@@ -54,23 +37,31 @@ fn main() {
     }
 }
 
-#[test]
-fn test_bare_tags() {
-    let input = r#"
-    Description
-    <lang>Pseudocode</lang>
+fn fix(input: &str) {
+    let (fixed, unfixed) = fix_bare_lang_tags(&fix_code_tags::languages(), input);
+    print!("{}", unified_diff("a/input", "b/input", input, &fixed));
 
-    =={{header|C}}==
-    <lang C>printf("Hello world!\n");</lang>
+    for tag in unfixed {
+        match tag.header {
+            Some(header) => eprintln!(
+                "line {}: left bare under \"{}\": {}",
+                tag.line, header, tag.resolution
+            ),
+            None => eprintln!("line {}: left bare outside of any header", tag.line),
+        }
+    }
+}
 
-    =={{header|Perl}}==
-    <lang>print "Hello world!\n"</lang>"#;
+fn main() {
+    let opt = Opt::from_args();
 
-    let expected = vec![
-        (None, 1),
-        (Some("C".to_owned()), 0),
-        (Some("Perl".to_owned()), 1),
-    ];
+    let stdin = io::stdin();
+    let mut buf = String::new();
+    stdin.lock().read_to_string(&mut buf).unwrap();
 
-    assert_eq!(expected, find_bare_lang_tags(input));
+    if opt.fix {
+        fix(&buf);
+    } else {
+        count(&buf);
+    }
 }
@@ -1,134 +1,225 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{HashMap, HashSet};
 
-use reqwest::Url;
-use serde::Deserialize;
-use serde_json::Value;
+use lazy_static::lazy_static;
+use mediawiki::{Cache, Result, Source};
+use regex::Regex;
 
-/// A Rosetta Code task.
-#[derive(Clone, PartialEq, Eq, Hash, Debug, Deserialize)]
-pub struct Task {
-    /// The ID of the page containing the task in the MediaWiki API.
-    #[serde(rename = "pageid")]
-    pub id: u64,
+/// A Rosetta Code task: its page ID in the MediaWiki API and its human-readable title.
+pub use mediawiki::CategoryMember as Task;
 
-    /// The human-readable title of the task.
-    pub title: String,
+lazy_static! {
+    /// Matches a top-level language header, such as `=={{header|Rust}}==`, capturing the name of
+    /// the language.
+    static ref HEADER_RE: Regex =
+        Regex::new(r"(?m)^==\s*\{\{\s*header\s*\|\s*([^|}]*[^|}\s])\s*(?:\|[^}]*)?\}\}").unwrap();
 }
 
-/// Encapsulates errors that might occur during JSON parsing.
-#[derive(Debug)]
-enum TaskParseError {
-    /// Something went wrong with the HTTP request to the API.
-    Http(reqwest::Error),
+/// The category of tasks that are finished.
+pub const PROGRAMMING_TASKS: &str = "Programming Tasks";
 
-    /// There was a problem parsing the API response into JSON.
-    Json(serde_json::Error),
+/// The category of tasks that are still being specified.
+pub const DRAFT_PROGRAMMING_TASKS: &str = "Draft Programming Tasks";
 
-    /// The response JSON contained unexpected keys or values.
-    UnexpectedFormat,
-}
+/// Which tasks to look for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query<'a> {
+    /// The language that the tasks must not be implemented in, such as "Rust".
+    pub language: &'a str,
 
-impl From<serde_json::Error> for TaskParseError {
-    fn from(err: serde_json::Error) -> Self {
-        TaskParseError::Json(err)
-    }
+    /// The categories to look for tasks in, such as [`PROGRAMMING_TASKS`].
+    pub categories: Vec<&'a str>,
+
+    /// If set, only tasks implemented in this language are reported, such as "Go" to find the
+    /// tasks implemented in Go but not in Rust.
+    pub implemented_in: Option<&'a str>,
 }
 
-impl From<reqwest::Error> for TaskParseError {
-    fn from(err: reqwest::Error) -> Self {
-        TaskParseError::Http(err)
+impl<'a> Query<'a> {
+    /// Looks for the programming tasks that aren't implemented in a language.
+    pub fn new(language: &'a str) -> Query<'a> {
+        Query {
+            language,
+            categories: vec![PROGRAMMING_TASKS],
+            implemented_in: None,
+        }
     }
 }
 
-/// Represents a category of pages on Rosetta Code, such as "Rust".
-struct Category {
-    name: String,
-    continue_params: Option<BTreeMap<String, String>>,
+pub fn all_tasks(source: &dyn Source) -> Result<Vec<Task>> {
+    mediawiki::category_members(source, PROGRAMMING_TASKS)
 }
 
-impl Category {
-    fn new(name: &str) -> Category {
-        let mut continue_params = BTreeMap::new();
-        continue_params.insert("continue".to_owned(), "".to_owned());
+/// Finds the tasks matching a query, sorted by title.
+pub fn find_tasks(source: &dyn Source, query: &Query) -> Result<Vec<Task>> {
+    let mut tasks = HashSet::new();
+    for category in &query.categories {
+        tasks.extend(mediawiki::category_members(source, category)?);
+    }
 
-        Category {
-            name: name.to_owned(),
-            continue_params: Some(continue_params),
-        }
+    if let Some(other) = query.implemented_in {
+        let implemented = mediawiki::category_members(source, other)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        tasks.retain(|task| implemented.contains(task));
     }
+
+    let implemented_tasks = mediawiki::category_members(source, query.language)?
+        .into_iter()
+        .collect::<HashSet<_>>();
+    let mut unimplemented_tasks = tasks
+        .difference(&implemented_tasks)
+        .cloned()
+        .collect::<Vec<Task>>();
+    unimplemented_tasks.sort_by(|a, b| a.title.cmp(&b.title));
+    Ok(unimplemented_tasks)
 }
 
-/// Sends a request to Rosetta Code through the MediaWiki API. If successful, returns the response
-/// as a JSON object.
-fn query_api(
-    category_name: &str,
-    continue_params: &BTreeMap<String, String>,
-) -> Result<Value, TaskParseError> {
-    let mut url = Url::parse("http://rosettacode.org/mw/api.php").expect("invalid URL");
-    url.query_pairs_mut()
-        .append_pair("action", "query")
-        .append_pair("list", "categorymembers")
-        .append_pair("cmtitle", &format!("Category:{}", category_name))
-        .append_pair("cmlimit", "500")
-        .append_pair("format", "json")
-        .extend_pairs(continue_params);
-
-    Ok(reqwest::blocking::get(url)?.json()?)
+/// Counts the languages that a page has an example in.
+pub fn count_languages(content: &str) -> usize {
+    HEADER_RE
+        .captures_iter(content)
+        .map(|captures| captures.get(1).unwrap().as_str())
+        .collect::<HashSet<_>>()
+        .len()
 }
 
-/// Given a JSON object, parses the task information from the MediaWiki API response.
-fn parse_tasks(json: &Value) -> Result<Vec<Task>, TaskParseError> {
-    let tasks_json = json
-        .pointer("/query/categorymembers")
-        .and_then(Value::as_array)
-        .ok_or(TaskParseError::UnexpectedFormat)?;
+pub fn unimplemented_tasks(source: &dyn Source, lang: &str) -> Result<Vec<Task>> {
+    find_tasks(source, &Query::new(lang))
+}
 
-    tasks_json
+/// Pairs each task with the number of languages it is implemented in, as a measure of its
+/// popularity, and sorts the tasks from most to least popular.
+///
+/// The pages of the tasks are downloaded with up to `workers` threads, and kept in the cache if
+/// one is given.
+pub fn by_popularity(
+    source: &(dyn Source + Sync),
+    tasks: Vec<Task>,
+    cache: Option<&Cache>,
+    workers: usize,
+) -> Result<Vec<(Task, usize)>> {
+    let titles = tasks
         .iter()
-        .map(|json| Task::deserialize(json).map_err(From::from))
-        .collect()
+        .map(|task| task.title.as_str())
+        .collect::<Vec<_>>();
+    let (pages, _) = mediawiki::fetch_contents(source, &titles, cache, workers)?;
+    let languages = pages
+        .iter()
+        .map(|page| (page.title.as_str(), count_languages(&page.content)))
+        .collect::<HashMap<_, _>>();
+
+    let mut tasks = tasks
+        .into_iter()
+        .map(|task| {
+            let count = languages.get(task.title.as_str()).cloned().unwrap_or(0);
+            (task, count)
+        })
+        .collect::<Vec<_>>();
+    tasks.sort_by(|(a, a_languages), (b, b_languages)| {
+        b_languages.cmp(a_languages).then(a.title.cmp(&b.title))
+    });
+
+    Ok(tasks)
 }
 
-impl Iterator for Category {
-    type Item = Vec<Task>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.continue_params.as_ref()?;
-
-        query_api(&self.name, self.continue_params.as_ref()?)
-            .and_then(|result| {
-                // If there are more pages of results to request, save them for the next iteration.
-                self.continue_params =
-                    result
-                        .get("continue")
-                        .and_then(Value::as_object)
-                        .map(|continue_params| {
-                            continue_params
-                                .iter()
-                                .map(|(key, value)| {
-                                    (key.to_owned(), value.as_str().unwrap().to_owned())
-                                })
-                                .collect()
-                        });
-
-                parse_tasks(&result)
-            })
-            .map_err(|err| println!("Error parsing response: {:?}", err))
-            .ok()
+#[cfg(test)]
+mod tests {
+    use mediawiki::{Result, Source};
+    use serde_json::json;
+
+    use super::{
+        by_popularity, count_languages, find_tasks, Query, DRAFT_PROGRAMMING_TASKS,
+        PROGRAMMING_TASKS,
+    };
+
+    /// A wiki with three tasks and a draft task. Rust implements "A", and Go implements "B" and
+    /// the draft "D". The pages list an example for every language that implements them, plus
+    /// C for "C".
+    struct FakeWiki;
+
+    impl Source for FakeWiki {
+        fn query(&self, params: &[(&str, &str)]) -> Result<String> {
+            let param = |name| params.iter().find(|(k, _)| *k == name).map(|(_, v)| *v);
+            let members = |titles: &[&str]| {
+                let members = titles
+                    .iter()
+                    .map(|title| json!({ "pageid": title.as_bytes()[0], "title": title }))
+                    .collect::<Vec<_>>();
+                json!({ "query": { "categorymembers": members } })
+            };
+
+            let response = match (param("cmtitle"), param("prop")) {
+                (Some("Category:Programming Tasks"), _) => members(&["A", "B", "C"]),
+                (Some("Category:Draft Programming Tasks"), _) => members(&["D"]),
+                (Some("Category:Rust"), _) => members(&["A"]),
+                (Some("Category:Go"), _) => members(&["B", "D"]),
+                (None, Some("revisions")) => {
+                    let pages = param("titles")
+                        .unwrap()
+                        .split('|')
+                        .map(|title| {
+                            let content = match title {
+                                "A" => "=={{header|Rust}}==",
+                                "B" | "D" => "=={{header|Go}}==",
+                                _ => "=={{header|C}}==\n=={{header|Go}}==",
+                            };
+                            let page = json!({ "title": title, "revisions": [{ "*": content }] });
+                            (title.to_owned(), page)
+                        })
+                        .collect::<serde_json::Map<_, _>>();
+                    json!({ "query": { "pages": pages } })
+                }
+                _ => panic!("unexpected query: {:?}", params),
+            };
+
+            Ok(response.to_string())
+        }
     }
-}
 
-pub fn all_tasks() -> Vec<Task> {
-    Category::new("Programming Tasks").flatten().collect()
-}
+    fn titles<'a, I: IntoIterator<Item = &'a super::Task>>(tasks: I) -> Vec<&'a str> {
+        tasks.into_iter().map(|task| task.title.as_str()).collect()
+    }
 
-pub fn unimplemented_tasks(lang: &str) -> Vec<Task> {
-    let all_tasks = all_tasks().iter().cloned().collect::<HashSet<_>>();
-    let implemented_tasks = Category::new(lang).flatten().collect::<HashSet<_>>();
-    let mut unimplemented_tasks = all_tasks
-        .difference(&implemented_tasks)
-        .cloned()
-        .collect::<Vec<Task>>();
-    unimplemented_tasks.sort_by(|a, b| a.title.cmp(&b.title));
-    unimplemented_tasks
+    #[test]
+    fn unimplemented() {
+        let tasks = find_tasks(&FakeWiki, &Query::new("Rust")).unwrap();
+        assert_eq!(titles(&tasks), ["B", "C"]);
+
+        let query = Query {
+            categories: vec![PROGRAMMING_TASKS, DRAFT_PROGRAMMING_TASKS],
+            ..Query::new("Rust")
+        };
+        let tasks = find_tasks(&FakeWiki, &query).unwrap();
+        assert_eq!(titles(&tasks), ["B", "C", "D"]);
+    }
+
+    #[test]
+    fn implemented_in_another_language() {
+        let query = Query {
+            categories: vec![PROGRAMMING_TASKS, DRAFT_PROGRAMMING_TASKS],
+            implemented_in: Some("Go"),
+            ..Query::new("Rust")
+        };
+        let tasks = find_tasks(&FakeWiki, &query).unwrap();
+        assert_eq!(titles(&tasks), ["B", "D"]);
+    }
+
+    #[test]
+    fn languages() {
+        let content =
+            "=={{header|Go}}==\n===Iterative===\n=={{header|C++|C}}==\n== {{header|Go}} ==";
+        assert_eq!(count_languages(content), 2);
+    }
+
+    #[test]
+    fn popularity() {
+        let tasks = find_tasks(&FakeWiki, &Query::new("Rust")).unwrap();
+        let tasks = by_popularity(&FakeWiki, tasks, None, 2).unwrap();
+
+        assert_eq!(titles(tasks.iter().map(|(task, _)| task)), ["C", "B"]);
+        assert_eq!(
+            tasks.iter().map(|&(_, count)| count).collect::<Vec<_>>(),
+            [2, 1]
+        );
+    }
 }
@@ -1,7 +1,84 @@
 extern crate find_unimplemented_tasks;
 
-fn main() {
-    for task in find_unimplemented_tasks::unimplemented_tasks("Rust") {
-        println!("{:6} {}", task.id, task.title);
+use std::env;
+use std::path::PathBuf;
+
+use find_unimplemented_tasks::{Query, DRAFT_PROGRAMMING_TASKS, PROGRAMMING_TASKS};
+use mediawiki::{Cache, Client};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The language to find unimplemented tasks for
+    #[structopt(long, default_value = "Rust")]
+    language: String,
+
+    /// Only list tasks that are implemented in this language, such as "Go"
+    #[structopt(long)]
+    implemented_in: Option<String>,
+
+    /// A category of tasks to look in. May be repeated. Defaults to "Programming Tasks"
+    #[structopt(long = "category", number_of_values = 1)]
+    categories: Vec<String>,
+
+    /// Also look in "Draft Programming Tasks"
+    #[structopt(long)]
+    drafts: bool,
+
+    /// Sort the tasks by how many languages implement them, most popular first
+    #[structopt(long)]
+    popularity: bool,
+
+    /// The number of pages to download at the same time when sorting by popularity
+    #[structopt(short, long, default_value = "4")]
+    workers: usize,
+
+    /// The directory to cache pages in when sorting by popularity. Defaults to a directory in the
+    /// system's temporary directory
+    #[structopt(long, parse(from_os_str))]
+    cache_dir: Option<PathBuf>,
+}
+
+fn main() -> mediawiki::Result<()> {
+    let opt = Opt::from_args();
+
+    let mut categories = opt
+        .categories
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    if categories.is_empty() {
+        categories.push(PROGRAMMING_TASKS);
+    }
+    if opt.drafts {
+        categories.push(DRAFT_PROGRAMMING_TASKS);
     }
+
+    let query = Query {
+        language: &opt.language,
+        categories,
+        implemented_in: opt.implemented_in.as_deref(),
+    };
+
+    let client = Client::default();
+    let tasks = find_unimplemented_tasks::find_tasks(&client, &query)?;
+
+    if opt.popularity {
+        let directory = opt
+            .cache_dir
+            .unwrap_or_else(|| env::temp_dir().join("find-unimplemented-tasks"));
+        let cache = Cache::open(directory)?;
+
+        for (task, languages) in
+            find_unimplemented_tasks::by_popularity(&client, tasks, Some(&cache), opt.workers)?
+        {
+            println!("{:6} {:4} {}", task.id, languages, task.title);
+        }
+    } else {
+        for task in tasks {
+            println!("{:6} {}", task.id, task.title);
+        }
+    }
+
+    Ok(())
 }
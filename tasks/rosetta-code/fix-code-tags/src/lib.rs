@@ -0,0 +1,268 @@
+use std::collections::{HashMap, HashSet};
+
+/// Tags whose contents are left untouched, including tags that already highlight code.
+const VERBATIM_TAGS: &[&str] = &["pre", "nowiki", "lang", "syntaxhighlight", "source", "code"];
+
+/// The names of the syntax highlighters that `<lang>` tags accept.
+pub const LANGUAGES: &str =
+    "_div abap actionscript actionscript3 ada apache applescript apt_sources asm asp autoit \
+     avisynth bash basic4gl bf blitzbasic bnf boo c c_mac caddcl cadlisp cfdg cfm cil cobol cpp \
+     cpp-qt csharp css d delphi diff dos dot eiffel email fortran freebasic genero gettext glsl \
+     gml gnuplot groovy haskell hq9plus html4strict idl ini inno intercal io java java5 \
+     javascript kixtart klonec klonecpp latex lisp lolcode lotusformulas lotusscript lscript lua \
+     m68k make matlab mirc modula3 mpasm mxml mysql nsis objc ocaml ocaml-brief oobas oracle11 \
+     oracle8 pascal per perl php php-brief pic16 pixelbender plsql povray powershell progress \
+     prolog providex python qbasic rails reg robots ruby rust sas scala scheme scilab sdlbasic \
+     smalltalk smarty sql tcl teraterm text thinbasic tsql typoscript vb vbnet verilog vhdl vim \
+     visualfoxpro visualprolog whitespace winbatch xml xorg_conf xpp z80";
+
+/// Returns the names of the syntax highlighters in [`LANGUAGES`].
+pub fn languages() -> Vec<&'static str> {
+    LANGUAGES.split_whitespace().collect()
+}
+
+/// A tag in wikitext, such as `<code rust>` or `</pre>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Tag<'a> {
+    /// The byte offset of the `<` that starts the tag.
+    start: usize,
+
+    /// The byte offset just past the `>` that ends the tag.
+    end: usize,
+
+    closing: bool,
+    self_closing: bool,
+    name: &'a str,
+
+    /// Everything between the name and the end of the tag, trimmed.
+    attributes: &'a str,
+}
+
+impl<'a> Tag<'a> {
+    /// Parses the tag starting at `start`, which must be the offset of a `<`.
+    fn parse(text: &'a str, start: usize) -> Option<Tag<'a>> {
+        let rest = &text[start + 1..];
+        let (closing, rest) = match rest.strip_prefix('/') {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+
+        let name_len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || "_-.".contains(c)))
+            .unwrap_or(rest.len());
+        let (name, rest) = rest.split_at(name_len);
+        if name.is_empty() {
+            return None;
+        }
+
+        // The attributes must be separated from the name, and can't contain another tag.
+        let attributes_len = rest.find(['<', '>'])?;
+        if !rest[attributes_len..].starts_with('>')
+            || (attributes_len > 0
+                && !rest.starts_with(char::is_whitespace)
+                && !rest.starts_with('/'))
+        {
+            return None;
+        }
+
+        let attributes = rest[..attributes_len].trim();
+        let self_closing = attributes.ends_with('/');
+        let attributes = attributes.trim_end_matches('/').trim_end();
+
+        Some(Tag {
+            start,
+            end: text.len() - rest.len() + attributes_len + 1,
+            closing,
+            self_closing,
+            name,
+            attributes,
+        })
+    }
+}
+
+/// Iterates over the tags in a text, starting from a byte offset.
+fn tags(text: &str, from: usize) -> impl Iterator<Item = Tag<'_>> + '_ {
+    let mut position = from;
+
+    std::iter::from_fn(move || {
+        while let Some(offset) = text[position..].find('<') {
+            let start = position + offset;
+            match Tag::parse(text, start) {
+                Some(tag) => {
+                    position = tag.end;
+                    return Some(tag);
+                }
+                None => position = start + 1,
+            }
+        }
+        None
+    })
+}
+
+/// A region of text that starts with an opening tag and ends with a matching closing tag.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Region<'a> {
+    /// Code in a known language, marked with `<lang>` or `<code lang>`, which should be
+    /// rewritten to `<lang lang>`. Either `</lang>` or `</code>` closes it.
+    Code(&'a str),
+
+    /// A region whose contents and tags are kept as they are, closed by a tag with the given
+    /// lowercase name.
+    Verbatim(String),
+}
+
+impl<'a> Region<'a> {
+    /// Determines the region that an opening tag starts, if any.
+    fn open(tag: &Tag<'a>, languages: &HashSet<&str>) -> Option<Region<'a>> {
+        if tag.closing || tag.self_closing {
+            return None;
+        }
+
+        let name = tag.name.to_ascii_lowercase();
+        if name == "code" && languages.contains(tag.attributes) {
+            Some(Region::Code(tag.attributes))
+        } else if VERBATIM_TAGS.contains(&name.as_str()) {
+            Some(Region::Verbatim(name))
+        } else if tag.attributes.is_empty() && languages.contains(tag.name) {
+            Some(Region::Code(tag.name))
+        } else {
+            None
+        }
+    }
+
+    /// True if the tag closes this region.
+    fn is_closed_by(&self, tag: &Tag) -> bool {
+        if !tag.closing {
+            return false;
+        }
+
+        match *self {
+            Region::Code(lang) => tag.name == lang || tag.name.eq_ignore_ascii_case("code"),
+            Region::Verbatim(ref name) => tag.name.eq_ignore_ascii_case(name),
+        }
+    }
+}
+
+/// Replaces `<lang>`, `<code lang>` and the matching closing tags with `<lang lang>` and
+/// `</lang>`, for each of the given languages.
+///
+/// The text is scanned once, pairing each opening tag with the closing tag that ends it. The
+/// contents of code blocks and of `<pre>`, `<nowiki>`, `<syntaxhighlight>` and similar tags are
+/// left untouched, as are tags that aren't closed.
+pub fn fix_tags(languages: &[&str], text: &str) -> String {
+    let languages = languages.iter().cloned().collect::<HashSet<_>>();
+    let mut fixed = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut position = 0;
+
+    // The offsets after which each kind of region is known to have no closing tag, so that
+    // unclosed tags don't cause the rest of the text to be searched again.
+    let mut unclosed = HashMap::new();
+
+    while let Some(tag) = tags(text, position).next() {
+        position = tag.end;
+
+        let region = match Region::open(&tag, &languages) {
+            Some(region) => region,
+            None => continue,
+        };
+
+        if unclosed.get(&region).is_some_and(|&after| tag.end >= after) {
+            continue;
+        }
+
+        let close = match tags(text, tag.end).find(|close| region.is_closed_by(close)) {
+            Some(close) => close,
+            None => {
+                unclosed.insert(region, tag.end);
+                continue;
+            }
+        };
+
+        fixed.push_str(&text[copied..tag.start]);
+        match region {
+            Region::Code(lang) => {
+                fixed.push_str("<lang ");
+                fixed.push_str(lang);
+                fixed.push('>');
+                fixed.push_str(&text[tag.end..close.start]);
+                fixed.push_str("</lang>");
+            }
+            Region::Verbatim(_) => fixed.push_str(&text[tag.start..close.end]),
+        }
+
+        copied = close.end;
+        position = close.end;
+    }
+
+    fixed.push_str(&text[copied..]);
+    fixed
+}
+
+#[test]
+fn test_replace() {
+    let input = "Lorem ipsum <code foo>saepe audire</code> elaboraret ne quo, id equidem atomorum \
+                 inciderint usu. <foo>In sit inermis deleniti percipit</foo>, ius ex tale civibus \
+                 omittam. <barf>Vix ut doctus cetero invenire</barf>, his eu altera electram. \
+                 Tota adhuc altera te sea, <code bar>soluta appetere ut mel</bar>. Quo quis \
+                 graecis vivendo te, <baz>posse nullam lobortis ex usu</code>. Eam volumus \
+                 perpetua constituto id, mea an omittam fierent vituperatoribus.";
+
+    let expected = "Lorem ipsum <lang foo>saepe audire</lang> elaboraret ne quo, id equidem \
+                    atomorum inciderint usu. <lang foo>In sit inermis deleniti percipit</lang>, \
+                    ius ex tale civibus omittam. <barf>Vix ut doctus cetero invenire</barf>, his \
+                    eu altera electram. Tota adhuc altera te sea, <lang bar>soluta appetere ut \
+                    mel</lang>. Quo quis graecis vivendo te, <lang baz>posse nullam lobortis ex \
+                    usu</lang>. Eam volumus perpetua constituto id, mea an omittam fierent \
+                    vituperatoribus.";
+
+    let languages = vec!["foo", "bar", "baz"];
+
+    assert_eq!(expected, fix_tags(&languages, input));
+}
+
+#[test]
+fn test_pairing() {
+    let languages = vec!["foo", "bar"];
+
+    // A `</code>` only becomes `</lang>` if it closes a block that was rewritten.
+    assert_eq!(
+        fix_tags(&languages, "<code>x</code> <foo>y</foo> </code>"),
+        "<code>x</code> <lang foo>y</lang> </code>"
+    );
+
+    // Unclosed tags are left alone.
+    assert_eq!(
+        fix_tags(&languages, "<foo>y <bar>z</bar>"),
+        "<foo>y <lang bar>z</lang>"
+    );
+
+    // So are tags that aren't for a known language, or that have attributes.
+    assert_eq!(
+        fix_tags(&languages, "<baz>y</baz> <foo class=\"x\">z</foo>"),
+        "<baz>y</baz> <foo class=\"x\">z</foo>"
+    );
+}
+
+#[test]
+fn test_verbatim_regions() {
+    let languages = vec!["foo"];
+    let input = "<pre><foo>a</foo></pre> <nowiki><foo>b</foo></nowiki> \
+                 <syntaxhighlight lang=\"foo\"><foo>c</foo></syntaxhighlight> \
+                 <lang foo>if a <foo>d</foo> b</lang> <NoWiki><foo>e</foo></NOWIKI>";
+
+    assert_eq!(fix_tags(&languages, input), input);
+
+    // A nested block ends at the first closing tag.
+    assert_eq!(
+        fix_tags(&languages, "<foo><pre>x</foo></pre>"),
+        "<lang foo><pre>x</lang></pre>"
+    );
+}
+
+#[test]
+fn test_linear_time() {
+    // Each unclosed tag would otherwise cause the rest of the text to be scanned again.
+    let input = "<foo> <pre> ".repeat(20_000);
+    assert_eq!(fix_tags(&["foo"], &input), input);
+}
@@ -1,3 +1,7 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
 fn rot13(string: &str) -> String {
     fn rot13u8(c: char) -> char {
         let d = c as u8;
@@ -33,3 +37,13 @@ fn test_coherence() {
 
     assert!(coherence_test);
 }
+
+#[cfg(test)]
+mod tests {
+    test_cipher! {
+        encrypt: |text| super::rot13(text),
+        decrypt: |text| super::rot13(text),
+        alphabet: "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789 .,!?",
+        vectors: ["Hello, world!" => "Uryyb, jbeyq!"],
+    }
+}
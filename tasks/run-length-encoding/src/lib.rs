@@ -1,3 +1,7 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
 use std::iter::repeat;
 
 pub const INPUT: &str = r"WWWWWWWWWWWWBWWWWWWWWWWWWBBBWWWWWWWWWWWWWWWWWWWWWWWWBWWWWWWWWWWWWWW";
@@ -58,3 +62,13 @@ fn test_encode_decode() {
     assert_eq!(decode(&encode(INPUT)[..]).unwrap(), INPUT);
     assert_eq!(decode("a"), Err("expected digit, found a".to_string()));
 }
+
+#[cfg(test)]
+mod tests {
+    test_codec! {
+        encode: |value: &str| super::encode(value),
+        decode: |value: &str| super::decode(value).unwrap(),
+        arbitrary: |rng| meta::test_utils::random_string(rng, "WB", 100),
+        vectors: ["WWWWBBBW" => "4W3B1W", "" => ""],
+    }
+}
@@ -9,16 +9,18 @@
 //! It supports the ability to read individual atoms, not just lists, although whether this is
 //! useful is questionable.
 //!
-//! Caveats: Does not support symbols vs. non-symbols (it wouldn't be hard, but it would greatly
-//! complicate setting up our test structure since we'd have to force it to go through functions
-//! that checked to make sure `Symbol`s couldn't have spaces, or slow down our parser by checking
-//! for this information each time, which is obnoxious).  Does not support string escaping, because
-//! the decoding technique doesn't allocate extra space for strings.  Does support numbers, but
-//! only float types (supporting more types is possible but would complicate the code
-//! significantly).
+//! Caveats: `SExp` does not support symbols vs. non-symbols (it wouldn't be hard, but it would
+//! greatly complicate setting up our test structure since we'd have to force it to go through
+//! functions that checked to make sure `Symbol`s couldn't have spaces, or slow down our parser by
+//! checking for this information each time, which is obnoxious).  Does not support string
+//! escaping, because the decoding technique doesn't allocate extra space for strings.  Does support
+//! numbers, but only float types (supporting more types is possible but would complicate the code
+//! significantly).  The owned [`SExpr`] does all of these, at the cost of allocating as it parses.
 
 extern crate typed_arena;
 
+mod owned;
+
 use typed_arena::Arena;
 
 use self::Error::{ExpectedEof, IncorrectCloseDelimiter, UnexpectedEof, UnterminatedStringLiteral};
@@ -28,6 +30,8 @@ use std::io;
 use std::num::FpCategory;
 use std::str::FromStr;
 
+pub use owned::{PrettyPrinter, SExpr};
+
 /// The actual `SExp` structure.  Supports `f64`s, lists, and string literals.  Note that it takes
 /// everything by reference, rather than owning it--this is mostly done just so we can allocate
 /// `SExp`s statically (since we don't have to call `Vec`).  It does complicate the code a bit,
@@ -64,6 +68,12 @@ pub enum Error {
 
     /// More tokens after the list is finished, or after a literal if there is no list.
     ExpectedEof,
+
+    /// A backslash in a string literal followed by something other than `"`, `\\`, `n`, `t` or `r`
+    InvalidEscape,
+
+    /// A symbol that would be read back as something else, such as a number or several symbols
+    InvalidSymbol,
 }
 
 impl From<io::Error> for Error {
@@ -106,7 +116,7 @@ struct Tokens<'a> {
 
 impl<'a> Tokens<'a> {
     /// Initialize a token stream for a given string.
-    fn new(string: &str) -> Tokens {
+    fn new(string: &str) -> Tokens<'_> {
         let mut chars = string.chars();
 
         match chars.next() {
@@ -213,7 +223,7 @@ impl<'a> Tokens<'a> {
 
 /// This is not the most efficient way to do this, because we end up going over numeric literals
 /// twice, but it avoids having to write our own number parsing logic.
-fn parse_literal(literal: &str) -> SExp {
+fn parse_literal(literal: &str) -> SExp<'_> {
     match literal.bytes().next() {
         Some(b'0'..=b'9') | Some(b'-') => match f64::from_str(literal) {
             Ok(f) => F64(f),
@@ -1,7 +1,14 @@
-use s_expressions::{ParseContext, SExp, SEXP_STRING_IN, SEXP_STRUCT};
+use s_expressions::{ParseContext, PrettyPrinter, SExp, SExpr, SEXP_STRING_IN, SEXP_STRUCT};
 
 fn main() {
     println!("{:?}", SEXP_STRUCT.buffer_encode());
     let ctx = &mut ParseContext::new(SEXP_STRING_IN);
     println!("{:?}", SExp::parse(ctx));
+
+    let sexpr = SExpr::parse(SEXP_STRING_IN).unwrap();
+    let printer = PrettyPrinter {
+        indent: 2,
+        width: 30,
+    };
+    println!("{}", printer.print(&sexpr).unwrap());
 }
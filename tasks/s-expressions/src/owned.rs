@@ -0,0 +1,580 @@
+//! An owned S-expression, for trees that are built by hand or have to outlive their input.  It
+//! costs an allocation for every string and list, but in exchange it can tell symbols from
+//! strings and integers from floats, it handles string escapes and quoting, and it can be printed
+//! across several lines.
+
+use typed_arena::Arena;
+
+use super::Error::{
+    self, ExpectedEof, IncorrectCloseDelimiter, InvalidEscape, InvalidSymbol, NoReprForFloat,
+    UnexpectedEof, UnterminatedStringLiteral,
+};
+use super::SExp;
+use std::iter::Peekable;
+use std::mem;
+use std::str::{Chars, FromStr};
+
+/// An S-expression that owns all of its contents.
+#[derive(Clone, PartialEq, Debug)]
+pub enum SExpr {
+    /// Bare word: data
+    Symbol(String),
+
+    /// Integer literal: 123
+    Int(i64),
+
+    /// Float literal: 4.5
+    Float(f64),
+
+    /// String literal, which may contain escapes: "say \"hi\""
+    Str(String),
+
+    /// Quoted expression: 'a or '(a b)
+    Quote(Box<SExpr>),
+
+    /// List of SExprs: (a b c)
+    List(Vec<SExpr>),
+}
+
+/// Characters that end a symbol or number.
+fn is_delimiter(ch: char) -> bool {
+    ch.is_whitespace() || ch == '(' || ch == ')' || ch == '"' || ch == '\''
+}
+
+/// Reads a bare word as a number if it is one, or as a symbol.  Words too long for an `i64` are
+/// read as floats, but words that `f64` reads as infinite or `NaN`, like `inf`, are symbols.
+fn parse_atom(word: &str) -> SExpr {
+    if let Ok(i) = i64::from_str(word) {
+        return SExpr::Int(i);
+    }
+    match word.bytes().next() {
+        Some(b'0'..=b'9') | Some(b'-') | Some(b'+') | Some(b'.') => match f64::from_str(word) {
+            Ok(f) if f.is_finite() => SExpr::Float(f),
+            _ => SExpr::Symbol(word.to_string()),
+        },
+        _ => SExpr::Symbol(word.to_string()),
+    }
+}
+
+/// A parser over the characters of the input.  It keeps the lists it is in the middle of on a
+/// stack of its own rather than recursing, like [`SExp::parse`], so that deeply nested input
+/// can't overflow the call stack.
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+    }
+
+    fn parse_expr(&mut self) -> Result<SExpr, Error> {
+        // The lists being read, innermost last, each with the number of quotes in front of it.
+        let mut stack: Vec<(Vec<SExpr>, usize)> = Vec::new();
+        // The number of quotes in front of the next expression.
+        let mut quotes = 0;
+
+        loop {
+            self.skip_whitespace();
+            let mut sexpr = match self.chars.next() {
+                Some('(') => {
+                    stack.push((Vec::new(), quotes));
+                    quotes = 0;
+                    continue;
+                }
+                Some(')') if quotes == 0 => match stack.pop() {
+                    Some((list, list_quotes)) => {
+                        quotes = list_quotes;
+                        SExpr::List(list)
+                    }
+                    None => return Err(IncorrectCloseDelimiter),
+                },
+                Some(')') => return Err(IncorrectCloseDelimiter),
+                Some('\'') => {
+                    quotes += 1;
+                    continue;
+                }
+                Some('"') => self.parse_string()?,
+                Some(ch) => {
+                    let mut word = ch.to_string();
+                    while let Some(ch) = self.chars.next_if(|&ch| !is_delimiter(ch)) {
+                        word.push(ch);
+                    }
+                    parse_atom(&word)
+                }
+                None => return Err(UnexpectedEof),
+            };
+
+            for _ in 0..quotes {
+                sexpr = SExpr::Quote(Box::new(sexpr));
+            }
+            quotes = 0;
+
+            match stack.last_mut() {
+                Some((list, _)) => list.push(sexpr),
+                None => return Ok(sexpr),
+            }
+        }
+    }
+
+    /// Reads the rest of a string literal, after the opening quote.
+    fn parse_string(&mut self) -> Result<SExpr, Error> {
+        let mut string = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(SExpr::Str(string)),
+                Some('\\') => string.push(match self.chars.next() {
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some(_) => return Err(InvalidEscape),
+                    None => return Err(UnterminatedStringLiteral),
+                }),
+                Some(ch) => string.push(ch),
+                None => return Err(UnterminatedStringLiteral),
+            }
+        }
+    }
+}
+
+impl SExpr {
+    /// Deserialize a `SExpr`.
+    /// # Errors
+    /// When reaches unexpected EOF, an invalid close delimiter or an unknown escape
+    pub fn parse(string: &str) -> Result<SExpr, Error> {
+        let mut parser = Parser {
+            chars: string.chars().peekable(),
+        };
+        let sexpr = parser.parse_expr()?;
+        parser.skip_whitespace();
+        match parser.chars.peek() {
+            None => Ok(sexpr),
+            Some(_) => Err(ExpectedEof),
+        }
+    }
+
+    /// Serializes an atom, or the opening of a list or a quote.
+    fn encode_head(&self, out: &mut String) -> Result<(), Error> {
+        match self {
+            SExpr::Symbol(s) => {
+                // A symbol has to read back as the same symbol, and not as a number or anything
+                // else.
+                if s.is_empty() || s.chars().any(is_delimiter) || parse_atom(s) != *self {
+                    return Err(InvalidSymbol);
+                }
+                out.push_str(s);
+            }
+            SExpr::Int(i) => out.push_str(&i.to_string()),
+            SExpr::Float(f) => {
+                if !f.is_finite() {
+                    return Err(NoReprForFloat);
+                }
+                // Debug keeps the decimal point, so that whole floats aren't read back as
+                // integers.
+                out.push_str(&format!("{:?}", f));
+            }
+            SExpr::Str(s) => {
+                out.push('"');
+                for ch in s.chars() {
+                    match ch {
+                        '"' => out.push_str("\\\""),
+                        '\\' => out.push_str("\\\\"),
+                        '\n' => out.push_str("\\n"),
+                        '\t' => out.push_str("\\t"),
+                        '\r' => out.push_str("\\r"),
+                        _ => out.push(ch),
+                    }
+                }
+                out.push('"');
+            }
+            SExpr::Quote(_) => out.push('\''),
+            SExpr::List(_) => out.push('('),
+        }
+        Ok(())
+    }
+
+    /// Serialize a `SExpr` on one line, with a stack of its own rather than recursing.
+    ///
+    /// If a limit is given, stops as soon as more than `limit` characters have been written, and
+    /// returns whether it finished.  The parts that weren't written haven't been checked for
+    /// errors either.
+    fn encode(&self, out: &mut String, limit: Option<usize>) -> Result<bool, Error> {
+        /// What is left to write, last first.
+        enum Part<'a> {
+            SExpr(&'a SExpr),
+            Text(&'static str),
+        }
+
+        let start = out.len();
+        let mut parts = vec![Part::SExpr(self)];
+        while let Some(part) = parts.pop() {
+            match part {
+                Part::SExpr(sexpr) => {
+                    sexpr.encode_head(out)?;
+                    match sexpr {
+                        SExpr::Quote(quoted) => parts.push(Part::SExpr(quoted)),
+                        SExpr::List(list) => {
+                            parts.push(Part::Text(")"));
+                            for (i, sexpr) in list.iter().enumerate().rev() {
+                                parts.push(Part::SExpr(sexpr));
+                                if i > 0 {
+                                    parts.push(Part::Text(" "));
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Part::Text(text) => out.push_str(text),
+            }
+
+            // Counting characters is only needed once there are more bytes than the limit.
+            if let Some(limit) = limit {
+                if out.len() - start > limit && out[start..].chars().count() > limit {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Encode a `SExpr` as a String, on one line.
+    /// # Errors
+    /// If a float is `NaN` or infinite, or a symbol couldn't be read back as the same symbol
+    pub fn buffer_encode(&self) -> Result<String, Error> {
+        let mut out = String::new();
+        self.encode(&mut out, None)?;
+        Ok(out)
+    }
+
+    /// Converts to a borrowed `SExp`, allocating its lists in `arena`.  `SExp` only has floats and
+    /// strings, so integers become floats, symbols become strings, and `'x` becomes `(quote x)`.
+    pub fn to_sexp<'a>(&'a self, arena: &'a Arena<Vec<SExp<'a>>>) -> SExp<'a> {
+        match self {
+            SExpr::Symbol(s) | SExpr::Str(s) => SExp::Str(s),
+            SExpr::Int(i) => SExp::F64(*i as f64),
+            SExpr::Float(f) => SExp::F64(*f),
+            SExpr::Quote(sexpr) => {
+                SExp::List(arena.alloc(vec![SExp::Str("quote"), sexpr.to_sexp(arena)]))
+            }
+            SExpr::List(list) => {
+                SExp::List(arena.alloc(list.iter().map(|sexpr| sexpr.to_sexp(arena)).collect()))
+            }
+        }
+    }
+}
+
+/// Lists and quotes are taken apart with a stack, rather than by recursing into each of them, so
+/// that dropping a deeply nested `SExpr` can't overflow the call stack.
+impl Drop for SExpr {
+    fn drop(&mut self) {
+        fn take_children(sexpr: &mut SExpr, stack: &mut Vec<SExpr>) {
+            match sexpr {
+                SExpr::List(list) => stack.append(list),
+                SExpr::Quote(quoted) => {
+                    stack.push(mem::replace(&mut **quoted, SExpr::List(Vec::new())));
+                }
+                _ => {}
+            }
+        }
+
+        let mut stack = Vec::new();
+        take_children(self, &mut stack);
+        while let Some(mut sexpr) = stack.pop() {
+            take_children(&mut sexpr, &mut stack);
+        }
+    }
+}
+
+/// `SExp` doesn't tell symbols from strings, so every `Str` becomes a string, and every `F64` a
+/// float.
+impl<'a> From<&SExp<'a>> for SExpr {
+    fn from(sexp: &SExp<'a>) -> SExpr {
+        match *sexp {
+            SExp::F64(f) => SExpr::Float(f),
+            SExp::Str(s) => SExpr::Str(s.to_string()),
+            SExp::List(list) => SExpr::List(list.iter().map(SExpr::from).collect()),
+        }
+    }
+}
+
+/// Prints `SExpr`s across several lines.  A list that fits in the rest of the line is printed on
+/// it; otherwise its first element follows the opening parenthesis, and each of the others is on
+/// a line of its own, indented past the parenthesis.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct PrettyPrinter {
+    /// The number of spaces each level of nesting is indented by.
+    pub indent: usize,
+
+    /// The number of characters a line should fit in, where possible.
+    pub width: usize,
+}
+
+impl Default for PrettyPrinter {
+    fn default() -> PrettyPrinter {
+        PrettyPrinter {
+            indent: 2,
+            width: 80,
+        }
+    }
+}
+
+impl PrettyPrinter {
+    /// Prints a `SExpr`, without a newline at the end.
+    /// # Errors
+    /// If a float is `NaN` or infinite, or a symbol couldn't be read back as the same symbol
+    pub fn print(&self, sexpr: &SExpr) -> Result<String, Error> {
+        /// What is left to print, last first.
+        enum Part<'a> {
+            /// A `SExpr` that starts at a column, and is followed by a number of closing
+            /// parentheses on the same line.
+            SExpr(&'a SExpr, usize, usize),
+
+            /// A closing parenthesis.
+            Close,
+
+            /// A new line, indented to a column.
+            Line(usize),
+        }
+
+        let mut out = String::new();
+        let mut parts = vec![Part::SExpr(sexpr, 0, 0)];
+        while let Some(part) = parts.pop() {
+            let (sexpr, column, closing) = match part {
+                Part::SExpr(sexpr, column, closing) => (sexpr, column, closing),
+                Part::Close => {
+                    out.push(')');
+                    continue;
+                }
+                Part::Line(column) => {
+                    out.push('\n');
+                    out.push_str(&" ".repeat(column));
+                    continue;
+                }
+            };
+
+            // Print it on one line if it fits, along with the parentheses after it.
+            let start = out.len();
+            let limit = self.width.checked_sub(column + closing);
+            if let Some(true) = limit
+                .map(|limit| sexpr.encode(&mut out, Some(limit)))
+                .transpose()?
+            {
+                continue;
+            }
+            out.truncate(start);
+
+            match sexpr {
+                SExpr::List(list) if !list.is_empty() => {
+                    out.push('(');
+                    parts.push(Part::Close);
+                    let last = list.len() - 1;
+                    for (i, sexpr) in list.iter().enumerate().rev() {
+                        let closing = if i == last { closing + 1 } else { 0 };
+                        if i == 0 {
+                            parts.push(Part::SExpr(sexpr, column + 1, closing));
+                        } else {
+                            parts.push(Part::SExpr(sexpr, column + self.indent, closing));
+                            parts.push(Part::Line(column + self.indent));
+                        }
+                    }
+                }
+                SExpr::Quote(quoted) => {
+                    out.push('\'');
+                    parts.push(Part::SExpr(quoted, column + 1, closing));
+                }
+                // Atoms can't be split, so they run past the end of the line.
+                _ => {
+                    sexpr.encode(&mut out, None)?;
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SExpr::*;
+    use super::*;
+    use crate::{ParseContext, SEXP_STRING_IN, SEXP_STRUCT};
+
+    fn symbol(s: &str) -> SExpr {
+        Symbol(s.to_string())
+    }
+
+    fn string(s: &str) -> SExpr {
+        Str(s.to_string())
+    }
+
+    #[test]
+    fn test_sexpr_decode() {
+        assert_eq!(
+            Ok(List(vec![
+                List(vec![
+                    symbol("data"),
+                    string("quoted data"),
+                    Int(123),
+                    Float(4.5)
+                ]),
+                List(vec![
+                    symbol("data"),
+                    List(vec![
+                        symbol("!@#"),
+                        List(vec![Float(4.5)]),
+                        string("(more"),
+                        string("data)"),
+                    ]),
+                ]),
+            ])),
+            SExpr::parse(SEXP_STRING_IN)
+        );
+
+        assert_eq!(
+            Ok(List(vec![
+                Quote(Box::new(symbol("a"))),
+                Quote(Box::new(List(vec![Int(-1), Float(2.0), symbol("-")]))),
+                string("say \"hi\"\n\t\\"),
+            ])),
+            SExpr::parse(r#"('a '(-1 2.0 -) "say \"hi\"\n\t\\")"#)
+        );
+
+        assert_eq!(Ok(symbol("inf")), SExpr::parse(" inf "));
+        assert_eq!(Err(InvalidEscape), SExpr::parse(r#""\q""#));
+        assert_eq!(Err(UnterminatedStringLiteral), SExpr::parse(r#"("a\""#));
+        assert_eq!(Err(UnexpectedEof), SExpr::parse("(a '"));
+        assert_eq!(Err(IncorrectCloseDelimiter), SExpr::parse(")"));
+        assert_eq!(Err(ExpectedEof), SExpr::parse("(a) b"));
+    }
+
+    #[test]
+    fn test_sexpr_encode() {
+        let sexpr = SExpr::parse(r#"('a '(-1 2.0 1e21 -) "say \"hi\"\n")"#).unwrap();
+        let encoded = sexpr.buffer_encode().unwrap();
+        assert_eq!(r#"('a '(-1 2.0 1e21 -) "say \"hi\"\n")"#, encoded);
+        assert_eq!(Ok(sexpr), SExpr::parse(&encoded));
+
+        assert_eq!(Err(InvalidSymbol), symbol("a b").buffer_encode());
+        assert_eq!(Err(InvalidSymbol), symbol("12").buffer_encode());
+        assert_eq!(Err(InvalidSymbol), symbol("").buffer_encode());
+        assert_eq!(Err(NoReprForFloat), Float(f64::NAN).buffer_encode());
+    }
+
+    #[test]
+    fn test_sexp_conversion() {
+        let sexpr = SExpr::from(&SEXP_STRUCT);
+        assert_eq!(
+            List(vec![
+                List(vec![
+                    string("data"),
+                    string("quoted data"),
+                    Float(123.),
+                    Float(4.5)
+                ]),
+                List(vec![
+                    string("data"),
+                    List(vec![
+                        string("!@#"),
+                        List(vec![Float(4.5)]),
+                        string("(more"),
+                        string("data)"),
+                    ]),
+                ]),
+            ]),
+            sexpr
+        );
+
+        let arena = Arena::new();
+        assert_eq!(SEXP_STRUCT, sexpr.to_sexp(&arena));
+
+        let ctx = &mut ParseContext::new("(a (quote b))");
+        let quoted = SExp::parse(ctx).unwrap();
+        let sexpr = SExpr::parse("(a 'b)").unwrap();
+        let arena = Arena::new();
+        assert_eq!(quoted, sexpr.to_sexp(&arena));
+        assert_eq!(
+            SExp::List(&[
+                SExp::Str("a"),
+                SExp::List(&[SExp::Str("quote"), SExp::Str("b")])
+            ]),
+            sexpr.to_sexp(&arena)
+        );
+    }
+
+    #[test]
+    fn test_pretty_print() {
+        let sexpr = SExpr::parse(SEXP_STRING_IN).unwrap();
+        let printer = PrettyPrinter {
+            indent: 2,
+            width: 40,
+        };
+        assert_eq!(
+            "((data \"quoted data\" 123 4.5)\n  (data (!@# (4.5) \"(more\" \"data)\")))",
+            printer.print(&sexpr).unwrap()
+        );
+
+        let printer = PrettyPrinter {
+            indent: 2,
+            width: 30,
+        };
+        assert_eq!(
+            "((data \"quoted data\" 123 4.5)\n  (data\n    (!@#\n      (4.5)\n      \"(more\"\n      \"data)\")))",
+            printer.print(&sexpr).unwrap()
+        );
+
+        let printer = PrettyPrinter {
+            indent: 4,
+            width: 20,
+        };
+        assert_eq!(
+            "((data\n     \"quoted data\"\n     123\n     4.5)\n    (data\n        (!@#\n            (4.5)\n            \"(more\"\n            \"data)\")))",
+            printer.print(&sexpr).unwrap()
+        );
+
+        assert_eq!(
+            Ok(sexpr.buffer_encode().unwrap()),
+            PrettyPrinter::default().print(&sexpr)
+        );
+        let printed = printer.print(&sexpr).unwrap();
+        assert_eq!(Ok(sexpr), SExpr::parse(&printed));
+    }
+
+    #[test]
+    fn test_deep_nesting() {
+        const DEPTH: usize = 200_000;
+        let nested = format!("{}x{}", "(".repeat(DEPTH), ")".repeat(DEPTH));
+
+        let sexpr = SExpr::parse(&nested).unwrap();
+        let mut depth = 0;
+        let mut inner = &sexpr;
+        while let List(list) = inner {
+            depth += 1;
+            inner = &list[0];
+        }
+        assert_eq!(depth, DEPTH);
+        assert_eq!(Ok(nested.clone()), sexpr.buffer_encode());
+        assert_eq!(Ok(nested), PrettyPrinter::default().print(&sexpr));
+        drop(sexpr);
+
+        let quoted = format!("{}x", "'".repeat(DEPTH));
+        let sexpr = SExpr::parse(&quoted).unwrap();
+        assert_eq!(Ok(quoted), sexpr.buffer_encode());
+    }
+
+    #[test]
+    fn test_pretty_print_counts_closing_parentheses() {
+        // "(b c)" fits on the second line by itself, but not with the parentheses that close the
+        // lists around it.
+        let sexpr = SExpr::parse("(a ((b c)))").unwrap();
+        let printer = PrettyPrinter {
+            indent: 2,
+            width: 9,
+        };
+        let printed = printer.print(&sexpr).unwrap();
+        assert_eq!("(a\n  ((b\n     c)))", printed);
+        assert!(printed.lines().all(|line| line.chars().count() <= 9));
+        assert_eq!(Ok(sexpr), SExpr::parse(&printed));
+    }
+}
@@ -2,6 +2,10 @@
 
 extern crate ring;
 
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
 use ring::digest::{digest, SHA256};
 
 fn main() {
@@ -10,7 +14,11 @@ fn main() {
 
 fn sha_256(input: &str) -> String {
     let result = digest(&SHA256, input.as_bytes());
-    result.as_ref().iter().map(|b| format!("{:x}", b)).collect()
+    result
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
 }
 
 #[test]
@@ -18,3 +26,13 @@ fn test_rosetta() {
     let expected_hash = "764faf5c61ac315f1497f9dfa542713965b785e5cc2f707d6468d7d1124cdfcf";
     assert_eq!(sha_256("Rosetta code"), expected_hash);
 }
+
+#[cfg(test)]
+mod tests {
+    test_hash! {
+        super::sha_256,
+        "" => "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        "abc" => "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        "Rosetta code" => "764faf5c61ac315f1497f9dfa542713965b785e5cc2f707d6468d7d1124cdfcf",
+    }
+}
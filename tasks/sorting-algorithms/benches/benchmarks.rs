@@ -0,0 +1,61 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sorting_algorithms::integers::{counting_sort, radix_sort};
+use sorting_algorithms::radix::{lsd_radix_sort, msd_radix_sort};
+use sorting_algorithms::{par_merge_sort, Sorter, Visitor};
+
+/// The number of elements sorted by each algorithm, unless it is impractical for that many.
+const LEN: usize = 1000;
+
+/// Benchmarks every sorter on the same random input.
+struct Bench<'a> {
+    criterion: &'a mut Criterion,
+    input: Vec<i32>,
+}
+
+impl<'a> Visitor for Bench<'a> {
+    fn visit<S: Sorter>(&mut self, sorter: S) {
+        let len = S::MAX_LEN.map_or(LEN, |max_len| max_len.min(LEN));
+        let input = &self.input[..len];
+        self.criterion.bench_function(S::NAME, |b| {
+            b.iter_batched_ref(
+                || input.to_vec(),
+                |v| sorter.sort(black_box(v)),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+}
+
+fn benchmark(c: &mut Criterion) {
+    let input = StdRng::seed_from_u64(0)
+        .sample_iter(rand::distributions::Standard)
+        .take(LEN)
+        .map(|n: i16| i32::from(n))
+        .collect::<Vec<_>>();
+
+    for (name, sort) in [
+        ("Counting sort", counting_sort as fn(&mut [i32])),
+        ("Radix sort", radix_sort),
+        ("LSD radix sort", lsd_radix_sort),
+        ("MSD radix sort", msd_radix_sort),
+        ("Parallel merge sort", par_merge_sort),
+    ] {
+        c.bench_function(name, |b| {
+            b.iter_batched_ref(
+                || input.clone(),
+                |v| sort(black_box(v)),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    sorting_algorithms::visit_all(&mut Bench {
+        criterion: c,
+        input,
+    });
+}
+
+criterion_group!(benches, benchmark);
+criterion_main!(benches);
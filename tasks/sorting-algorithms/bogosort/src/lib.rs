@@ -0,0 +1,45 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+extern crate rand;
+
+use std::cmp::Ordering;
+
+use rand::prelude::*;
+
+pub fn bogo_sort<T: Ord>(v: &mut [T]) {
+    bogo_sort_by(v, T::cmp);
+}
+
+/// Like [`bogo_sort`], but orders elements with a comparator.
+pub fn bogo_sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut rng = thread_rng();
+    while !is_sorted(v, &mut compare) {
+        v.shuffle(&mut rng);
+    }
+}
+
+// helper function that checks for ascending order
+fn is_sorted<T, F>(v: &[T], compare: &mut F) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if v.len() > 1 {
+        for i in 0..(v.len() - 1) {
+            if compare(&v[i], &v[i + 1]) == Ordering::Greater {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    // The sort is random, so it could take a very long time!
+    test_sort!(super::bogo_sort, #[ignore]);
+}
@@ -0,0 +1,37 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+use std::cmp::Ordering;
+
+/// Progress through the slice and 'bubble' elements up until they are in order.
+pub fn bubble_sort<T: PartialOrd>(v: &mut [T]) {
+    bubble_sort_by(v, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+/// Like [`bubble_sort`], but orders elements with a comparator.
+pub fn bubble_sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    (1..v.len() + 1).rev().all(|length| {
+        let mut changes = 0;
+
+        for index in 0..length - 1 {
+            if compare(&v[index], &v[index + 1]) == Ordering::Greater {
+                changes += 1;
+                v.swap(index, index + 1);
+            }
+        }
+
+        // Continue to iterate if any 'bubble-ing' took place
+        changes > 0
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    test_sort!(super::bubble_sort);
+    test_stable_sort!(super::bubble_sort);
+    test_sort_floats!(super::bubble_sort);
+}
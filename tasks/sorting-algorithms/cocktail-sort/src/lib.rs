@@ -0,0 +1,47 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+use std::cmp::Ordering;
+
+// Progress through the slice and 'bubble' elements up and down until they are in order.
+pub fn cocktail_sort<T: PartialOrd>(v: &mut [T]) {
+    cocktail_sort_by(v, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+/// Like [`cocktail_sort`], but orders elements with a comparator.
+pub fn cocktail_sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    (1..v.len() + 1).rev().all(|length| {
+        let mut swapped: bool = false;
+        // bubble up
+        for index in 0..length - 1 {
+            if compare(&v[index], &v[index + 1]) == Ordering::Greater {
+                swapped = true;
+                v.swap(index, index + 1);
+            }
+        }
+        // break if no swap occured before bubbling down
+        if !swapped {
+            return false;
+        }
+        // bubble down
+        for index in (0..length - 1).rev() {
+            if compare(&v[index], &v[index + 1]) == Ordering::Greater {
+                swapped = true;
+                v.swap(index, index + 1);
+            }
+        }
+        // Continue to iterate if any swapping took place
+        swapped
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    test_sort!(super::cocktail_sort);
+    test_stable_sort!(super::cocktail_sort);
+    test_sort_floats!(super::cocktail_sort);
+}
@@ -1,33 +1,6 @@
-#[cfg(test)]
-#[macro_use]
-extern crate meta;
+extern crate cocktail_sort;
 
-// Progress through the slice and 'bubble' elements up and down until they are in order.
-fn cocktail_sort<T: PartialOrd>(v: &mut [T]) {
-    (1..v.len() + 1).rev().all(|length| {
-        let mut swapped: bool = false;
-        // bubble up
-        for index in 0..length - 1 {
-            if v[index] > v[index + 1] {
-                swapped = true;
-                v.swap(index, index + 1);
-            }
-        }
-        // break if no swap occured before bubbling down
-        if !swapped {
-            return false;
-        }
-        // bubble down
-        for index in (0..length - 1).rev() {
-            if v[index] > v[index + 1] {
-                swapped = true;
-                v.swap(index, index + 1);
-            }
-        }
-        // Continue to iterate if any swapping took place
-        swapped
-    });
-}
+use cocktail_sort::cocktail_sort;
 
 fn main() {
     let mut numbers = [4i32, 65, 2, -31, 0, 99, 2, 83, 782, 1];
@@ -35,8 +8,3 @@ fn main() {
     cocktail_sort(&mut numbers);
     println!("After: {:?}", numbers);
 }
-
-#[cfg(test)]
-mod tests {
-    test_sort!(super::cocktail_sort);
-}
@@ -0,0 +1,37 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+use std::cmp::Ordering;
+
+pub fn comb_sort<T: PartialOrd>(v: &mut [T]) {
+    comb_sort_by(v, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+/// Like [`comb_sort`], but orders elements with a comparator.
+pub fn comb_sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = v.len();
+    let mut gap: usize = v.len();
+    let mut swapped: bool = true;
+    while gap > 1 || swapped {
+        if gap > 1 {
+            gap = (gap as f32 / 1.25) as usize;
+        }
+        swapped = false;
+        for i in 0..len - gap {
+            if compare(&v[i], &v[i + gap]) == Ordering::Greater {
+                swapped = true;
+                v.swap(i, i + gap);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_sort!(super::comb_sort);
+    test_sort_floats!(super::comb_sort);
+}
@@ -0,0 +1,96 @@
+use std::convert::TryFrom;
+
+/// Sorts integers that all lie between `min` and `max`, inclusive.
+///
+/// It takes memory proportional to the size of the range, so wide ranges can need gigabytes.
+///
+/// # Panics
+///
+/// If an integer is outside of the range.
+#[allow(clippy::needless_range_loop)]
+pub fn counting_sort(array: &mut [i32], min: i32, max: i32) {
+    // nothing to do for arrays shorter than 2
+    if array.len() < 2 {
+        return;
+    }
+
+    // we count occurences of values, doing the arithmetic in i64 so that even the range from
+    // i32::MIN to i32::MAX doesn't overflow
+    let (min, max) = (i64::from(min), i64::from(max));
+    let size = usize::try_from(max - min + 1).expect("max is less than min");
+    let mut count = vec![0; size];
+
+    for &e in array.iter() {
+        count[(i64::from(e) - min) as usize] += 1;
+    }
+
+    // then we write values back, sorted
+    let mut index = 0;
+    for value in 0..count.len() {
+        for _ in 0..count[value] {
+            array[index] = (value as i64 + min) as i32;
+            index += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate meta;
+
+    fn check_sort(array: &mut [i32], min: i32, max: i32) {
+        let mut expected = array.to_vec();
+        expected.sort_unstable();
+
+        super::counting_sort(array, min, max);
+
+        meta::test_utils::check_sorted(array);
+        assert_eq!(array, &expected[..]);
+    }
+
+    #[test]
+    fn rosetta_vector() {
+        let numbers = &mut [4i32, 65, 2, -31, 0, 99, 2, 83, 782, 1];
+        check_sort(numbers, -31, 782);
+    }
+
+    #[test]
+    fn one_element_vector() {
+        let numbers = &mut [0i32];
+        check_sort(numbers, 0, 0);
+    }
+
+    #[test]
+    fn repeat_vector() {
+        let numbers = &mut [1i32, 1, 1, 1, 1];
+        check_sort(numbers, 1, 1);
+    }
+
+    #[test]
+    fn worst_case_vector() {
+        let numbers = &mut [20i32, 10, 0, -1, -5];
+        check_sort(numbers, -5, 20);
+    }
+
+    #[test]
+    fn already_sorted_vector() {
+        let numbers = &mut [-1i32, 0, 3, 6, 99];
+        check_sort(numbers, -1, 99);
+    }
+
+    #[test]
+    fn extreme_values() {
+        let numbers = &mut [i32::MAX, i32::MAX - 3, i32::MAX - 1, i32::MAX];
+        check_sort(numbers, i32::MAX - 3, i32::MAX);
+
+        let numbers = &mut [i32::MIN + 2, i32::MIN, i32::MIN + 1, i32::MIN];
+        check_sort(numbers, i32::MIN, i32::MIN + 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bad_min() {
+        let numbers = &mut [-1i32, 0, 3, 6, 99];
+        check_sort(numbers, 2, 99);
+    }
+}
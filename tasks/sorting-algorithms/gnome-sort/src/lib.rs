@@ -0,0 +1,39 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+use std::cmp::Ordering;
+
+pub fn gnome_sort<T: PartialOrd>(v: &mut [T]) {
+    gnome_sort_by(v, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+/// Like [`gnome_sort`], but orders elements with a comparator.
+pub fn gnome_sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = v.len();
+    let mut i: usize = 1;
+    let mut j: usize = 2;
+    while i < len {
+        if compare(&v[i - 1], &v[i]) != Ordering::Greater {
+            i = j;
+            j += 1;
+        } else {
+            v.swap(i - 1, i);
+            i -= 1;
+            if i == 0 {
+                i = j;
+                j += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_sort!(super::gnome_sort);
+    test_stable_sort!(super::gnome_sort);
+    test_sort_floats!(super::gnome_sort);
+}
@@ -1,25 +1,6 @@
-#[cfg(test)]
-#[macro_use]
-extern crate meta;
+extern crate gnome_sort;
 
-fn gnome_sort<T: PartialOrd>(v: &mut [T]) {
-    let len = v.len();
-    let mut i: usize = 1;
-    let mut j: usize = 2;
-    while i < len {
-        if v[i - 1] <= v[i] {
-            i = j;
-            j += 1;
-        } else {
-            v.swap(i - 1, i);
-            i -= 1;
-            if i == 0 {
-                i = j;
-                j += 1;
-            }
-        }
-    }
-}
+use gnome_sort::gnome_sort;
 
 fn main() {
     let mut numbers = [4i32, 65, 2, -31, 0, 99, 2, 83, 782, 1];
@@ -27,8 +8,3 @@ fn main() {
     gnome_sort(&mut numbers);
     println!("After: {:?}", numbers);
 }
-
-#[cfg(test)]
-mod tests {
-    test_sort!(super::gnome_sort);
-}
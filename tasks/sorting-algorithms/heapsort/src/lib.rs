@@ -0,0 +1,92 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+use std::cmp::Ordering;
+
+/// This is ported from the Dart heap sort implementation
+pub fn heap_sort<T: Ord>(a: &mut [T]) {
+    heap_sort_by(a, T::cmp);
+}
+
+/// Like [`heap_sort`], but orders elements with a comparator.
+pub fn heap_sort_by<T, F>(a: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let count = a.len();
+
+    if count == 0 {
+        return;
+    }
+
+    // first place 'a' in max-heap order
+    heapify(a, count, &mut compare);
+
+    let mut end = count - 1;
+    while end > 0 {
+        // swap the root (maximum value) of the heap with the
+        // last element of the heap
+        a.swap(0, end);
+
+        // put the heap back in max-heap order
+        sift_down(a, 0, end - 1, &mut compare);
+
+        // decrement the size of the heap so that the previous
+        // max value will stay in its proper place
+        end -= 1;
+    }
+}
+
+fn heapify<T, F>(a: &mut [T], count: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if count < 2 {
+        return;
+    }
+
+    // start is assigned the index in 'a' of the last parent node
+    let mut start: i32 = (count as i32 - 2) / 2; // binary heap
+
+    while start >= 0 {
+        // sift down the node at index 'start' to the proper place
+        // such that all nodes below the 'start' index are in heap order
+        sift_down(a, start as usize, count - 1, compare);
+        start -= 1;
+    }
+}
+
+fn sift_down<T, F>(a: &mut [T], start: usize, end: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // end represents the limit of how far down the heap to shift
+    let mut root = start;
+
+    // while the root has at least one child
+    while (root * 2 + 1) <= end {
+        // root*2+1 points to the left child
+        let mut child: usize = root * 2 + 1;
+
+        // if the chile has a sibling and the child's value is less that its sibling's...
+        if child < end && compare(&a[child], &a[child + 1]) == Ordering::Less {
+            // .. then point to the right child instead
+            child += 1;
+        }
+
+        // out of max-heap order
+        if compare(&a[root], &a[child]) == Ordering::Less {
+            a.swap(root, child);
+            // repeat to continue shifting down the child now
+            root = child;
+        } else {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_sort!(super::heap_sort);
+}
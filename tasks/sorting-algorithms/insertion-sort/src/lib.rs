@@ -0,0 +1,32 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+use std::cmp::Ordering;
+
+pub fn insertion_sort<T>(arr: &mut [T])
+where
+    T: Ord,
+{
+    insertion_sort_by(arr, T::cmp);
+}
+
+/// Like [`insertion_sort`], but orders elements with a comparator.
+pub fn insertion_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in 1..arr.len() {
+        let mut j = i;
+        while j > 0 && compare(&arr[j], &arr[j - 1]) == Ordering::Less {
+            arr.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_sort!(super::insertion_sort);
+    test_stable_sort!(super::insertion_sort);
+}
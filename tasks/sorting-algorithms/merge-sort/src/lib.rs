@@ -0,0 +1,260 @@
+//! This is an idiomatic-but-slow implementation. A more efficient implementation
+//! would use `unsafe` to avoid allocating so many temporary vectors.
+//!
+//! [`par_merge_sort_by`] is the faster variant: it allocates a single buffer, sorts the halves of
+//! large slices on different threads, and leaves small slices to an insertion sort.
+
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+use std::cmp::Ordering;
+use std::thread;
+
+/// Slices at most this long are sorted with an insertion sort.
+const INSERTION_THRESHOLD: usize = 32;
+
+/// Slices shorter than this are sorted on a single thread, since starting a thread would take
+/// longer than sorting them.
+const PARALLEL_THRESHOLD: usize = 4096;
+
+pub fn merge_sort<E: PartialOrd + Clone>(arr: &[E]) -> Vec<E> {
+    if arr.len() <= 1 {
+        return arr.to_vec();
+    }
+    let midpoint = arr.len() / 2;
+    let left = merge_sort(&arr[0..midpoint]);
+    let right = merge_sort(&arr[midpoint..]);
+    merge(&left[..], &right[..])
+}
+
+fn merge<E: PartialOrd + Clone>(left: &[E], right: &[E]) -> Vec<E> {
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut i = 0;
+    let mut j = 0;
+    while i < left.len() && j < right.len() {
+        if left[i] <= right[j] {
+            merged.push(left[i].clone());
+            i += 1;
+        } else {
+            merged.push(right[j].clone());
+            j += 1;
+        }
+    }
+    while i < left.len() {
+        merged.push(left[i].clone());
+        i += 1;
+    }
+    while j < right.len() {
+        merged.push(right[j].clone());
+        j += 1;
+    }
+    merged
+}
+
+/// Sorts a slice in place with a comparator, keeping equal elements in their original order.
+///
+/// Elements can't be cloned here, so the merge sort is run on their indices instead, and the
+/// elements are then moved into place by following the cycles of the resulting permutation.
+pub fn merge_sort_by<E, F>(arr: &mut [E], mut compare: F)
+where
+    F: FnMut(&E, &E) -> Ordering,
+{
+    let indices = (0..arr.len()).collect::<Vec<_>>();
+    let mut order = merge_sort_indices(&indices, &mut |&a: &usize, &b: &usize| {
+        compare(&arr[a], &arr[b])
+    });
+
+    // `order[i]` is the index of the element that belongs at `i`.
+    for start in 0..order.len() {
+        let mut current = start;
+        while order[current] != current {
+            let next = order[current];
+            order[current] = current;
+            if next == start {
+                break;
+            }
+            arr.swap(current, next);
+            current = next;
+        }
+    }
+}
+
+fn merge_sort_indices<F>(arr: &[usize], compare: &mut F) -> Vec<usize>
+where
+    F: FnMut(&usize, &usize) -> Ordering,
+{
+    if arr.len() <= 1 {
+        return arr.to_vec();
+    }
+    let midpoint = arr.len() / 2;
+    let left = merge_sort_indices(&arr[0..midpoint], compare);
+    let right = merge_sort_indices(&arr[midpoint..], compare);
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if compare(&left[i], &right[j]) != Ordering::Greater {
+            merged.push(left[i]);
+            i += 1;
+        } else {
+            merged.push(right[j]);
+            j += 1;
+        }
+    }
+    merged.extend_from_slice(&left[i..]);
+    merged.extend_from_slice(&right[j..]);
+    merged
+}
+
+/// Like [`merge_sort_by`], but sorts large slices on several threads.
+pub fn par_merge_sort<E: Ord + Clone + Send + Sync>(arr: &mut [E]) {
+    par_merge_sort_by(arr, E::cmp);
+}
+
+/// Sorts a slice with a comparator, keeping equal elements in their original order, using up to
+/// as many threads as the machine can run in parallel.
+///
+/// Each half of the slice is sorted on its own thread, down to a few thousand elements, and the
+/// halves are then merged. The slice is copied into a buffer once, and the two take turns being
+/// the source and the destination of the merges, so no other memory is allocated.
+pub fn par_merge_sort_by<E, F>(arr: &mut [E], compare: F)
+where
+    E: Clone + Send + Sync,
+    F: Fn(&E, &E) -> Ordering + Sync,
+{
+    if arr.len() <= INSERTION_THRESHOLD {
+        insertion_sort(arr, &compare);
+        return;
+    }
+
+    // Each level of splitting doubles the number of threads.
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let depth = threads.next_power_of_two().trailing_zeros();
+
+    let mut buffer = arr.to_vec();
+    sort_into(arr, &mut buffer, &compare, depth);
+}
+
+/// Sorts `arr`, using `buffer` as scratch space. Both must start with the same elements.
+///
+/// The halves of `buffer` are sorted first, with the halves of `arr` as their scratch space, and
+/// then merged into `arr`.
+fn sort_into<E, F>(arr: &mut [E], buffer: &mut [E], compare: &F, depth: u32)
+where
+    E: Clone + Send + Sync,
+    F: Fn(&E, &E) -> Ordering + Sync,
+{
+    if arr.len() <= INSERTION_THRESHOLD {
+        insertion_sort(arr, compare);
+        return;
+    }
+
+    let len = arr.len();
+    let midpoint = len / 2;
+    {
+        let (arr_left, arr_right) = arr.split_at_mut(midpoint);
+        let (buffer_left, buffer_right) = buffer.split_at_mut(midpoint);
+
+        if depth > 0 && len >= PARALLEL_THRESHOLD {
+            thread::scope(|scope| {
+                scope.spawn(|| sort_into(buffer_left, arr_left, compare, depth - 1));
+                sort_into(buffer_right, arr_right, compare, depth - 1);
+            });
+        } else {
+            sort_into(buffer_left, arr_left, compare, 0);
+            sort_into(buffer_right, arr_right, compare, 0);
+        }
+    }
+
+    let (left, right) = buffer.split_at(midpoint);
+    merge_into(left, right, arr, compare);
+}
+
+/// Merges two sorted slices into `merged`, which is as long as both together.
+fn merge_into<E: Clone, F>(left: &[E], right: &[E], merged: &mut [E], compare: &F)
+where
+    F: Fn(&E, &E) -> Ordering,
+{
+    let (mut i, mut j) = (0, 0);
+    for slot in merged.iter_mut() {
+        let take_left = j == right.len()
+            || (i < left.len() && compare(&left[i], &right[j]) != Ordering::Greater);
+        if take_left {
+            *slot = left[i].clone();
+            i += 1;
+        } else {
+            *slot = right[j].clone();
+            j += 1;
+        }
+    }
+}
+
+fn insertion_sort<E, F>(arr: &mut [E], compare: &F)
+where
+    F: Fn(&E, &E) -> Ordering,
+{
+    for i in 1..arr.len() {
+        let mut j = i;
+        while j > 0 && compare(&arr[j], &arr[j - 1]) == Ordering::Less {
+            arr.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_sort, merge_sort_by, par_merge_sort, par_merge_sort_by};
+
+    #[test]
+    fn sorted() {
+        let arr = [1i32, 2, 3, 4, 6, 8];
+        assert_eq!(merge_sort(&arr), arr.to_vec());
+    }
+
+    #[test]
+    fn reverse() {
+        let arr = [8i32, 6, 4, 3, 2, 1];
+        assert_eq!(merge_sort(&arr), vec![1i32, 2, 3, 4, 6, 8]);
+    }
+
+    #[test]
+    fn random() {
+        let arr = [12i32, 54, 2, 93, 13, 43, 15, 299, 234];
+        assert_eq!(
+            merge_sort(&arr),
+            vec![2i32, 12, 13, 15, 43, 54, 93, 234, 299]
+        );
+    }
+
+    #[test]
+    fn in_place() {
+        let mut arr = [(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd'), (1, 'e')];
+        merge_sort_by(&mut arr, |a, b| a.0.cmp(&b.0));
+        assert_eq!(arr, [(1, 'b'), (1, 'e'), (2, 'd'), (3, 'a'), (3, 'c')]);
+    }
+
+    test_sort!(par_merge_sort);
+    test_stable_sort!(par_merge_sort);
+    test_sort_by!(par_merge_sort_by);
+
+    #[test]
+    fn parallel() {
+        // Long enough to be split between threads several times.
+        let mut rng = meta::test_utils::test_rng();
+        let mut arr = meta::test_utils::random_vec::<u16>(&mut rng, 100_000);
+        let original = arr.clone();
+
+        par_merge_sort_by(&mut arr, |a, b| (a % 1000).cmp(&(b % 1000)));
+        meta::test_utils::check_sort_by(&original, &arr, |a: &u16, b: &u16| {
+            (a % 1000).cmp(&(b % 1000))
+        });
+
+        // Equal elements must keep their order across the halves sorted on different threads.
+        let mut keyed =
+            meta::test_utils::keyed(&meta::test_utils::random_vec::<u8>(&mut rng, 100_000));
+        par_merge_sort(&mut keyed);
+        meta::test_utils::check_stable(&keyed);
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+use std::cmp::Ordering;
+
+pub fn pancake_sort<T: Ord>(v: &mut [T]) {
+    pancake_sort_by(v, T::cmp);
+}
+
+/// Like [`pancake_sort`], but orders elements with a comparator.
+pub fn pancake_sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = v.len();
+    // trivial case -- no flips
+    if len < 2 {
+        return;
+    }
+    for i in (0..len).rev() {
+        // find index of the maximum element within `v[0..i]` (inclusive)
+        let max_index = v
+            .iter()
+            .take(i + 1)
+            .enumerate()
+            .max_by(|&(_, a), &(_, b)| compare(a, b))
+            .map(|(idx, _)| idx)
+            .expect("already checked to be non-empty");
+        // if `max_index` is not where it's supposed to be
+        // do two flips to move it to `i`
+        if max_index != i {
+            flip(v, max_index);
+            flip(v, i);
+        }
+    }
+}
+
+// function to flip a section of a mutable collection from 0..num (inclusive)
+fn flip<E>(v: &mut [E], num: usize) {
+    v[0..num + 1].reverse();
+}
+
+#[cfg(test)]
+mod tests {
+    test_sort!(super::pancake_sort);
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+use std::cmp::Ordering;
+
+/// We use an [in-place quick sort].
+///
+/// [in-place quick sort]: http://en.wikipedia.org/wiki/Quicksort#In-place_version
+pub fn quick_sort<T: Ord>(v: &mut [T]) {
+    quick_sort_by(v, T::cmp);
+}
+
+/// Like [`quick_sort`], but orders elements with a comparator.
+pub fn quick_sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort(v, &mut compare);
+}
+
+fn sort<T, F>(v: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = v.len();
+    if len < 2 {
+        return;
+    }
+
+    let pivot_index = partition(v, compare);
+
+    // Sort the left side
+    sort(&mut v[0..pivot_index], compare);
+
+    // Sort the right side
+    sort(&mut v[pivot_index + 1..len], compare);
+}
+
+/// Reorders the slice with values lower than the pivot at the left side,
+/// and values bigger than it at the right side.
+/// Also returns the store index.
+#[allow(clippy::needless_range_loop)]
+fn partition<T, F>(v: &mut [T], compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = v.len();
+    let pivot_index = len / 2;
+
+    v.swap(pivot_index, len - 1);
+
+    let mut store_index = 0;
+    for i in 0..(len - 1) {
+        if compare(&v[i], &v[len - 1]) != Ordering::Greater {
+            v.swap(i, store_index);
+            store_index += 1;
+        }
+    }
+
+    v.swap(store_index, len - 1);
+    store_index
+}
+
+#[cfg(test)]
+mod tests {
+    test_sort!(super::quick_sort);
+}
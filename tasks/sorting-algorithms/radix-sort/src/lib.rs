@@ -0,0 +1,428 @@
+//! Radix sorts, which order keys one byte at a time instead of comparing them.
+//!
+//! A key is anything that implements [`RadixKey`]: the integers, the floats and byte strings. The
+//! [`lsd_radix_sort`] goes from the least significant byte to the most significant one, and needs
+//! every key to have the same length. The [`msd_radix_sort`] goes the other way, and also sorts
+//! keys of different lengths, such as strings.
+
+use std::cmp::Ordering;
+
+/// Slices at most this long are sorted with an insertion sort, which is faster than counting the
+/// bytes of so few keys.
+const INSERTION_THRESHOLD: usize = 32;
+
+/// The number of buckets in a pass of the MSD radix sort: one for keys that have ended, and one
+/// for each byte.
+const MSD_BUCKETS: usize = 257;
+
+/// A key that can be sorted one byte at a time.
+///
+/// Keys are ordered by their bytes, most significant first, and a key that is a prefix of another
+/// one comes before it.
+pub trait RadixKey {
+    /// The number of bytes in the key.
+    fn key_len(&self) -> usize;
+
+    /// The byte at position `i`, counting from the most significant byte, where `i` is less than
+    /// `key_len()`.
+    fn key_byte(&self, i: usize) -> u8;
+}
+
+/// A key whose length is the same for every value of the type.
+pub trait FixedRadixKey: RadixKey {
+    const KEY_LEN: usize;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {$(
+        impl RadixKey for $t {
+            fn key_len(&self) -> usize {
+                Self::KEY_LEN
+            }
+
+            fn key_byte(&self, i: usize) -> u8 {
+                (*self >> (8 * (Self::KEY_LEN - 1 - i))) as u8
+            }
+        }
+
+        impl FixedRadixKey for $t {
+            const KEY_LEN: usize = std::mem::size_of::<$t>();
+        }
+    )*};
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+
+/// Flipping the sign bit of a two's complement integer makes its bytes sort in numeric order.
+macro_rules! impl_signed {
+    ($($t:ty => $unsigned:ty),*) => {$(
+        impl RadixKey for $t {
+            fn key_len(&self) -> usize {
+                Self::KEY_LEN
+            }
+
+            fn key_byte(&self, i: usize) -> u8 {
+                let flipped = (*self as $unsigned) ^ (1 << (<$unsigned>::BITS - 1));
+                flipped.key_byte(i)
+            }
+        }
+
+        impl FixedRadixKey for $t {
+            const KEY_LEN: usize = std::mem::size_of::<$t>();
+        }
+    )*};
+}
+
+impl_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+/// Floats are ordered like [`f64::total_cmp`]: the bits of negative floats are all flipped, so
+/// that larger magnitudes come first, and the sign bit of the others is set, so that they come
+/// after every negative float. Negative NaNs come first, and positive NaNs last.
+macro_rules! impl_float {
+    ($($t:ty => $bits:ty),*) => {$(
+        impl RadixKey for $t {
+            fn key_len(&self) -> usize {
+                Self::KEY_LEN
+            }
+
+            fn key_byte(&self, i: usize) -> u8 {
+                let bits = self.to_bits();
+                let sign = 1 << (<$bits>::BITS - 1);
+                let key = if bits & sign == 0 { bits | sign } else { !bits };
+                key.key_byte(i)
+            }
+        }
+
+        impl FixedRadixKey for $t {
+            const KEY_LEN: usize = std::mem::size_of::<$t>();
+        }
+    )*};
+}
+
+impl_float!(f32 => u32, f64 => u64);
+
+impl RadixKey for [u8] {
+    fn key_len(&self) -> usize {
+        self.len()
+    }
+
+    fn key_byte(&self, i: usize) -> u8 {
+        self[i]
+    }
+}
+
+impl RadixKey for Vec<u8> {
+    fn key_len(&self) -> usize {
+        self.len()
+    }
+
+    fn key_byte(&self, i: usize) -> u8 {
+        self[i]
+    }
+}
+
+/// Strings are sorted by their UTF-8 bytes, which is the same as sorting them by their characters.
+impl RadixKey for str {
+    fn key_len(&self) -> usize {
+        self.len()
+    }
+
+    fn key_byte(&self, i: usize) -> u8 {
+        self.as_bytes()[i]
+    }
+}
+
+impl RadixKey for String {
+    fn key_len(&self) -> usize {
+        self.len()
+    }
+
+    fn key_byte(&self, i: usize) -> u8 {
+        self.as_bytes()[i]
+    }
+}
+
+impl<T: RadixKey + ?Sized> RadixKey for &T {
+    fn key_len(&self) -> usize {
+        (**self).key_len()
+    }
+
+    fn key_byte(&self, i: usize) -> u8 {
+        (**self).key_byte(i)
+    }
+}
+
+/// Compares two keys byte by byte, starting at the byte `from`.
+fn compare_keys<T: RadixKey + ?Sized>(a: &T, b: &T, from: usize) -> Ordering {
+    let (a_len, b_len) = (a.key_len(), b.key_len());
+    for i in from..a_len.min(b_len) {
+        match a.key_byte(i).cmp(&b.key_byte(i)) {
+            Ordering::Equal => continue,
+            ordering => return ordering,
+        }
+    }
+    a_len.cmp(&b_len)
+}
+
+/// Sorts keys whose first `from` bytes are all the same.
+fn insertion_sort<T: RadixKey>(v: &mut [T], from: usize) {
+    for i in 1..v.len() {
+        let mut j = i;
+        while j > 0 && compare_keys(&v[j], &v[j - 1], from) == Ordering::Less {
+            v.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
+
+/// Least significant digit radix sort, a byte at a time.
+///
+/// Each pass is a stable counting sort of the keys by one of their bytes, from the least
+/// significant to the most significant. Passes where every key has the same byte are skipped. The
+/// keys are copied between the slice and a buffer of the same size.
+pub fn lsd_radix_sort<T: FixedRadixKey + Clone>(v: &mut [T]) {
+    if v.len() <= INSERTION_THRESHOLD {
+        insertion_sort(v, 0);
+        return;
+    }
+
+    let mut buffer = v.to_vec();
+    let mut in_buffer = false;
+
+    for i in (0..T::KEY_LEN).rev() {
+        let (source, destination): (&[T], &mut [T]) = if in_buffer {
+            (&buffer, v)
+        } else {
+            (v, &mut buffer)
+        };
+
+        let mut counts = [0; 256];
+        for key in source.iter() {
+            counts[key.key_byte(i) as usize] += 1;
+        }
+        if counts.contains(&source.len()) {
+            continue;
+        }
+
+        let mut positions = [0; 256];
+        for byte in 1..256 {
+            positions[byte] = positions[byte - 1] + counts[byte - 1];
+        }
+        for key in source.iter() {
+            let position = &mut positions[key.key_byte(i) as usize];
+            destination[*position] = key.clone();
+            *position += 1;
+        }
+
+        in_buffer = !in_buffer;
+    }
+
+    if in_buffer {
+        v.clone_from_slice(&buffer);
+    }
+}
+
+/// Most significant digit radix sort, a byte at a time.
+///
+/// The keys are sorted in place into a bucket for each value of their first byte, as an
+/// [American flag sort], and then each bucket is sorted by the next byte. Keys that have no more
+/// bytes go in a bucket of their own, before the others. Buckets of at most a few keys are
+/// finished with an insertion sort. The sort is not stable.
+///
+/// [American flag sort]: https://en.wikipedia.org/wiki/American_flag_sort
+pub fn msd_radix_sort<T: RadixKey>(v: &mut [T]) {
+    msd_radix_sort_from(v, 0);
+}
+
+fn msd_radix_sort_from<T: RadixKey>(v: &mut [T], depth: usize) {
+    if v.len() <= INSERTION_THRESHOLD {
+        insertion_sort(v, depth);
+        return;
+    }
+
+    let bucket = |key: &T| {
+        if depth < key.key_len() {
+            key.key_byte(depth) as usize + 1
+        } else {
+            0
+        }
+    };
+
+    let mut counts = [0; MSD_BUCKETS];
+    for key in v.iter() {
+        counts[bucket(key)] += 1;
+    }
+
+    let mut starts = [0; MSD_BUCKETS];
+    for b in 1..MSD_BUCKETS {
+        starts[b] = starts[b - 1] + counts[b - 1];
+    }
+
+    // Swap each key into the next free place of its bucket, until every bucket is full.
+    let mut next = starts;
+    for b in 0..MSD_BUCKETS {
+        let end = starts[b] + counts[b];
+        while next[b] < end {
+            let target = bucket(&v[next[b]]);
+            if target != b {
+                v.swap(next[b], next[target]);
+            }
+            next[target] += 1;
+        }
+    }
+
+    // The keys in the first bucket have ended, so they are all equal.
+    for b in 1..MSD_BUCKETS {
+        let start = starts[b];
+        msd_radix_sort_from(&mut v[start..start + counts[b]], depth + 1);
+    }
+}
+
+/// Sorts integers with an LSD radix sort.
+pub fn radix_sort(data: &mut [i32]) {
+    lsd_radix_sort(data);
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate meta;
+    extern crate rand;
+    use self::meta::test_utils::test_rng;
+    use self::rand::Rng;
+
+    use super::{lsd_radix_sort, msd_radix_sort, radix_sort};
+
+    fn check_numbers(numbers: &mut [i32]) {
+        let mut correct = numbers.to_vec();
+        correct.sort_unstable();
+        radix_sort(numbers);
+        assert_eq!(correct, numbers);
+    }
+
+    #[test]
+    fn test_rosetta_vector() {
+        check_numbers(&mut [170, 45, 75, -90, -802, 24, 2, 66, -17, 2]);
+    }
+
+    #[test]
+    fn test_empty_vector() {
+        check_numbers(&mut []);
+    }
+
+    #[test]
+    fn test_one_element_vector() {
+        check_numbers(&mut [0i32]);
+    }
+
+    #[test]
+    fn test_repeat_vector() {
+        check_numbers(&mut [1i32, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_already_sorted_vector() {
+        check_numbers(&mut [-1i32, 0, 3, 6, 99]);
+    }
+
+    #[test]
+    fn test_random_numbers() {
+        let mut rng = test_rng();
+        let mut numbers: Vec<i32> = (0..500).map(|_| rng.gen()).collect();
+        check_numbers(numbers.as_mut_slice());
+    }
+
+    /// Sorts the keys with both radix sorts, and checks them against the standard library.
+    fn check_both<T, F>(keys: &[T], mut compare: F)
+    where
+        T: super::FixedRadixKey + Clone + PartialEq + ::std::fmt::Debug,
+        F: FnMut(&T, &T) -> ::std::cmp::Ordering,
+    {
+        let mut correct = keys.to_vec();
+        correct.sort_by(&mut compare);
+
+        let mut lsd = keys.to_vec();
+        lsd_radix_sort(&mut lsd);
+        assert_eq!(lsd, correct);
+
+        let mut msd = keys.to_vec();
+        msd_radix_sort(&mut msd);
+        assert_eq!(msd, correct);
+    }
+
+    #[test]
+    fn test_integers() {
+        let mut rng = test_rng();
+
+        // Short inputs are sorted by insertion sort, long ones byte by byte.
+        for &len in &[10, 1000] {
+            let bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            check_both(&bytes, Ord::cmp);
+
+            let unsigned: Vec<u64> = (0..len).map(|_| rng.gen()).collect();
+            check_both(&unsigned, Ord::cmp);
+
+            let mut signed: Vec<i64> = (0..len).map(|_| rng.gen()).collect();
+            signed.extend_from_slice(&[i64::MIN, -1, 0, 1, i64::MAX]);
+            check_both(&signed, Ord::cmp);
+
+            let small: Vec<i16> = (0..len).map(|_| rng.gen_range(-3..3)).collect();
+            check_both(&small, Ord::cmp);
+        }
+    }
+
+    #[test]
+    fn test_floats() {
+        let mut rng = test_rng();
+
+        let mut floats: Vec<f64> = (0..1000).map(|_| (rng.gen::<f64>() - 0.5) * 1e6).collect();
+        floats.extend_from_slice(&[
+            0.0,
+            -0.0,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::MIN_POSITIVE,
+            f64::NAN,
+            -f64::NAN,
+        ]);
+
+        // NaN isn't equal to itself, so compare the bits.
+        let mut correct = floats.clone();
+        correct.sort_by(f64::total_cmp);
+        let bits = |v: &[f64]| v.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+
+        let mut lsd = floats.clone();
+        lsd_radix_sort(&mut lsd);
+        assert_eq!(bits(&lsd), bits(&correct));
+
+        let mut msd = floats;
+        msd_radix_sort(&mut msd);
+        assert_eq!(bits(&msd), bits(&correct));
+
+        let singles: Vec<f32> = (0..1000).map(|_| rng.gen::<f32>() - 0.5).collect();
+        check_both(&singles, f32::total_cmp);
+    }
+
+    #[test]
+    fn test_byte_strings() {
+        let mut rng = test_rng();
+
+        // Strings from a small alphabet share long prefixes, and some are prefixes of others.
+        let mut strings: Vec<Vec<u8>> = (0..1000)
+            .map(|_| {
+                let len = rng.gen_range(0..8);
+                (0..len).map(|_| rng.gen_range(b'a'..b'd')).collect()
+            })
+            .collect();
+        let mut correct = strings.clone();
+        correct.sort();
+        msd_radix_sort(&mut strings);
+        assert_eq!(strings, correct);
+
+        let mut words = vec!["house", "art", "", "hotel", "a", "airplane", "ho", "beach"];
+        msd_radix_sort(&mut words);
+        assert_eq!(
+            words,
+            ["", "a", "airplane", "art", "beach", "ho", "hotel", "house"]
+        );
+    }
+}
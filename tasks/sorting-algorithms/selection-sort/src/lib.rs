@@ -0,0 +1,37 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+use std::cmp::Ordering;
+
+pub fn selection_sort<T: Ord>(v: &mut [T]) {
+    selection_sort_by(v, T::cmp);
+}
+
+/// Like [`selection_sort`], but orders elements with a comparator.
+#[allow(clippy::needless_range_loop)]
+pub fn selection_sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if v.is_empty() {
+        return;
+    }
+
+    let len = v.len();
+
+    for j in 0..len - 1 {
+        let mut min_index = j;
+        for i in j + 1..len {
+            if compare(&v[i], &v[min_index]) != Ordering::Greater {
+                min_index = i;
+            }
+        }
+        v.swap(j, min_index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_sort!(super::selection_sort);
+}
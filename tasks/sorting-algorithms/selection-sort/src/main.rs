@@ -1,25 +1,6 @@
-#[cfg(test)]
-#[macro_use]
-extern crate meta;
+extern crate selection_sort;
 
-#[allow(clippy::needless_range_loop)]
-fn selection_sort<T: Ord>(v: &mut [T]) {
-    if v.is_empty() {
-        return;
-    }
-
-    let len = v.len();
-
-    for j in 0..len - 1 {
-        let mut min_index = j;
-        for i in j + 1..len {
-            if v[i] <= v[min_index] {
-                min_index = i;
-            }
-        }
-        v.swap(j, min_index);
-    }
-}
+use selection_sort::selection_sort;
 
 fn main() {
     // Sort numbers
@@ -36,8 +17,3 @@ fn main() {
     selection_sort(&mut strings);
     println!("After: {:?}", strings);
 }
-
-#[cfg(test)]
-mod tests {
-    test_sort!(super::selection_sort);
-}
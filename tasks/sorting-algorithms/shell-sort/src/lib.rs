@@ -0,0 +1,36 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+use std::cmp::Ordering;
+
+pub fn shell_sort<T: Ord>(v: &mut [T]) {
+    shell_sort_by(v, T::cmp);
+}
+
+/// Like [`shell_sort`], but orders elements with a comparator.
+///
+/// Elements are moved with swaps rather than copied into a temporary, so they don't need to be
+/// `Copy`.
+pub fn shell_sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut gap = v.len() / 2;
+    let len = v.len();
+    while gap > 0 {
+        for i in gap..len {
+            let mut j = i;
+            while j >= gap && compare(&v[j - gap], &v[j]) == Ordering::Greater {
+                v.swap(j, j - gap);
+                j -= gap;
+            }
+        }
+        gap /= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_sort!(super::shell_sort);
+}
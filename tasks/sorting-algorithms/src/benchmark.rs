@@ -0,0 +1,480 @@
+//! Running every sorter over inputs of different sizes and shapes, and reporting how they did.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::instrument;
+use crate::{integers, radix, Sorter, Visitor};
+
+/// The shape of an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Distribution {
+    /// Values chosen uniformly at random.
+    Random,
+
+    /// Values in ascending order.
+    Sorted,
+
+    /// Values in descending order.
+    Reversed,
+
+    /// Values chosen at random from only a few possibilities, so that most are repeated.
+    FewUnique,
+
+    /// Values that rise up to the middle of the input, then fall back down.
+    OrganPipe,
+}
+
+impl Distribution {
+    pub const ALL: [Distribution; 5] = [
+        Distribution::Random,
+        Distribution::Sorted,
+        Distribution::Reversed,
+        Distribution::FewUnique,
+        Distribution::OrganPipe,
+    ];
+
+    /// The number of different values in a [`Distribution::FewUnique`] input.
+    const FEW: u32 = 8;
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Distribution::Random => "random",
+            Distribution::Sorted => "sorted",
+            Distribution::Reversed => "reversed",
+            Distribution::FewUnique => "few-unique",
+            Distribution::OrganPipe => "organ-pipe",
+        }
+    }
+
+    /// Generates an input of `len` values.
+    pub fn generate<R: Rng>(self, len: usize, rng: &mut R) -> Vec<u32> {
+        let len = len as u32;
+        match self {
+            Distribution::Random => (0..len).map(|_| rng.gen()).collect(),
+            Distribution::Sorted => (0..len).collect(),
+            Distribution::Reversed => (0..len).rev().collect(),
+            Distribution::FewUnique => (0..len).map(|_| rng.gen_range(0..Self::FEW)).collect(),
+            Distribution::OrganPipe => (0..len).map(|i| i.min(len - 1 - i)).collect(),
+        }
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for Distribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Distribution::ALL
+            .iter()
+            .cloned()
+            .find(|distribution| distribution.name() == s)
+            .ok_or_else(|| {
+                let names = Distribution::ALL.map(Distribution::name);
+                format!(
+                    "unknown distribution \"{}\", expected one of {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// How a sorter did on one input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Measurement {
+    pub algorithm: &'static str,
+    pub distribution: Distribution,
+    pub len: usize,
+
+    /// The number of comparisons, unless the algorithm doesn't compare elements.
+    pub comparisons: Option<u64>,
+
+    /// The number of swaps, if they could be counted.
+    pub swaps: Option<u64>,
+
+    /// How long the sort took on plain integers, without instrumentation, averaged over several
+    /// runs.
+    pub elapsed: Duration,
+}
+
+/// Timed runs are repeated at least this many times, and until they have taken this long in
+/// total, so that a short sort isn't measured by a single sample of the clock.
+const MIN_RUNS: u32 = 3;
+const MIN_TIME: Duration = Duration::from_millis(20);
+
+/// Returns the average time that a sort takes on copies of the input.
+fn time<T: Clone>(input: &[T], mut sort: impl FnMut(&mut [T])) -> Duration {
+    let mut runs = 0;
+    let mut total = Duration::ZERO;
+    while runs < MIN_RUNS || total < MIN_TIME {
+        let mut values = input.to_vec();
+        let start = Instant::now();
+        sort(&mut values);
+        total += start.elapsed();
+        runs += 1;
+    }
+    total / runs
+}
+
+/// The sorts that aren't [`Sorter`]s, with a function that sorts an input with each of them and
+/// returns the number of comparisons it made, if it compares elements.
+///
+/// Some of the integer sorts only take signed integers, so these all sort the inputs as `i32`.
+/// The values of every distribution fit, except random ones, which are just as random when they
+/// are reinterpreted as signed.
+#[allow(clippy::type_complexity)]
+const OTHER_SORTS: [(&str, fn(&mut [i32]) -> Option<u64>); 5] = [
+    ("Counting sort", |v| {
+        integers::counting_sort(v);
+        None
+    }),
+    ("Radix sort", |v| {
+        integers::radix_sort(v);
+        None
+    }),
+    ("LSD radix sort", |v| {
+        radix::lsd_radix_sort(v);
+        None
+    }),
+    ("MSD radix sort", |v| {
+        radix::msd_radix_sort(v);
+        None
+    }),
+    ("Par. merge sort", |v| {
+        let comparisons = AtomicU64::new(0);
+        crate::par_merge_sort_by(v, |a, b| {
+            comparisons.fetch_add(1, AtomicOrdering::Relaxed);
+            a.cmp(b)
+        });
+        Some(comparisons.into_inner())
+    }),
+];
+
+/// Runs every sorter on every input. Inputs longer than the `MAX_LEN` of a sorter are skipped.
+struct Runner<'a> {
+    inputs: &'a [(Distribution, Vec<u32>)],
+    measurements: Vec<Measurement>,
+}
+
+impl<'a> Visitor for Runner<'a> {
+    fn visit<S: Sorter>(&mut self, sorter: S) {
+        for (distribution, input) in self.inputs {
+            if S::MAX_LEN.is_some_and(|max_len| input.len() > max_len) {
+                continue;
+            }
+
+            let counts = instrument::count(&sorter, input);
+            self.measurements.push(Measurement {
+                algorithm: S::NAME,
+                distribution: *distribution,
+                len: input.len(),
+                comparisons: Some(counts.comparisons),
+                swaps: counts.swaps,
+                elapsed: time(input, |values| sorter.sort(values)),
+            });
+        }
+    }
+}
+
+/// Measures every algorithm on inputs of every size and distribution. The same seed always gives
+/// the same inputs.
+///
+/// The comparison sorts are measured first, in alphabetical order, and then the sorts that only
+/// sort integers or that run on several threads. Those can't be instrumented, so their swaps
+/// aren't counted, and the integer sorts don't compare elements at all.
+pub fn run(sizes: &[usize], distributions: &[Distribution], seed: u64) -> Vec<Measurement> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut inputs = vec![];
+    for &distribution in distributions {
+        for &len in sizes {
+            inputs.push((distribution, distribution.generate(len, &mut rng)));
+        }
+    }
+
+    let mut runner = Runner {
+        inputs: &inputs,
+        measurements: vec![],
+    };
+    crate::visit_all(&mut runner);
+
+    let mut measurements = runner.measurements;
+    for (algorithm, sort) in OTHER_SORTS {
+        for (distribution, input) in &inputs {
+            let input = input.iter().map(|&value| value as i32).collect::<Vec<_>>();
+            let mut values = input.clone();
+            let comparisons = sort(&mut values);
+            debug_assert!(values.windows(2).all(|w| w[0] <= w[1]));
+
+            measurements.push(Measurement {
+                algorithm,
+                distribution: *distribution,
+                len: input.len(),
+                comparisons,
+                swaps: None,
+                elapsed: time(&input, |values| {
+                    sort(values);
+                }),
+            });
+        }
+    }
+    measurements
+}
+
+/// Formats a count, or nothing if it is missing.
+fn count(count: Option<u64>) -> String {
+    count.map_or_else(String::new, |count| count.to_string())
+}
+
+/// Formats measurements as a table with aligned columns. Counts that are missing are left empty.
+pub fn table(measurements: &[Measurement]) -> String {
+    let mut table = format!(
+        "{:<16} {:<12} {:>8} {:>14} {:>14} {:>12}\n",
+        "Algorithm", "Distribution", "Size", "Comparisons", "Swaps", "Time (µs)"
+    );
+    for m in measurements {
+        writeln!(
+            table,
+            "{:<16} {:<12} {:>8} {:>14} {:>14} {:>12.1}",
+            m.algorithm,
+            m.distribution,
+            m.len,
+            count(m.comparisons),
+            count(m.swaps),
+            m.elapsed.as_secs_f64() * 1e6,
+        )
+        .unwrap();
+    }
+    table
+}
+
+/// Formats measurements as CSV, with a header row. Counts that are missing are left empty.
+pub fn csv(measurements: &[Measurement]) -> String {
+    let mut csv = String::from("algorithm,distribution,size,comparisons,swaps,nanoseconds\n");
+    for m in measurements {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{}",
+            m.algorithm,
+            m.distribution,
+            m.len,
+            count(m.comparisons),
+            count(m.swaps),
+            m.elapsed.as_nanos(),
+        )
+        .unwrap();
+    }
+    csv
+}
+
+/// Plots the comparisons made by each algorithm against the size of the input, for one
+/// distribution. Algorithms that don't compare elements are left out.
+///
+/// Both axes are logarithmic, so each algorithm follows a line whose slope is the exponent of its
+/// complexity: 1 for a linear sort, 2 for a quadratic one, and a little more than 1 for
+/// `n log n`. Each algorithm is drawn with a letter, and points that overlap show the one that
+/// comes last.
+pub fn plot(
+    measurements: &[Measurement],
+    distribution: Distribution,
+    width: usize,
+    height: usize,
+) -> String {
+    let mut series = BTreeMap::<&str, Vec<(f64, f64)>>::new();
+    for m in measurements {
+        if let (true, true, Some(comparisons)) =
+            (m.distribution == distribution, m.len > 0, m.comparisons)
+        {
+            let point = ((m.len as f64).log10(), (comparisons.max(1) as f64).log10());
+            series.entry(m.algorithm).or_default().push(point);
+        }
+    }
+
+    let points = series.values().flatten();
+    let (x_min, x_max) = bounds(points.clone().map(|&(x, _)| x));
+    let (y_min, y_max) = bounds(points.map(|&(_, y)| y));
+    let scale = |value: f64, min: f64, max: f64, cells: usize| {
+        if max > min {
+            ((value - min) / (max - min) * (cells - 1) as f64).round() as usize
+        } else {
+            0
+        }
+    };
+
+    let mut grid = vec![vec![' '; width]; height];
+    for ((_, points), letter) in series.iter().zip('a'..='z') {
+        for &(x, y) in points {
+            let column = scale(x, x_min, x_max, width);
+            let row = height - 1 - scale(y, y_min, y_max, height);
+            grid[row][column] = letter;
+        }
+    }
+
+    let mut plot = format!("Comparisons by size, {} input (log-log)\n", distribution);
+    for (i, row) in grid.iter().enumerate() {
+        let label = match i {
+            0 => format!("{:.0}", 10f64.powf(y_max)),
+            i if i == height - 1 => format!("{:.0}", 10f64.powf(y_min)),
+            _ => String::new(),
+        };
+        writeln!(
+            plot,
+            "{:>10} |{}",
+            label,
+            row.iter().collect::<String>().trim_end()
+        )
+        .unwrap();
+    }
+    writeln!(plot, "{:>10} +{}", "", "-".repeat(width)).unwrap();
+    let x_min_label = format!("{:.0}", 10f64.powf(x_min));
+    let x_max_label = format!("{:.0}", 10f64.powf(x_max));
+    let padding = width.saturating_sub(x_min_label.len() + x_max_label.len());
+    writeln!(
+        plot,
+        "{:>10}  {}{}{}",
+        "",
+        x_min_label,
+        " ".repeat(padding.max(1)),
+        x_max_label
+    )
+    .unwrap();
+
+    for (algorithm, letter) in series.keys().zip('a'..='z') {
+        writeln!(plot, "{:>10}  {} = {}", "", letter, algorithm).unwrap();
+    }
+    plot
+}
+
+/// Returns the smallest and largest values, or zero for both if there are none.
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values
+        .fold(None, |bounds, value| match bounds {
+            None => Some((value, value)),
+            Some((min, max)) => Some((value.min(min), value.max(max))),
+        })
+        .unwrap_or((0.0, 0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::{csv, plot, run, table, Distribution, Measurement, OTHER_SORTS};
+
+    #[test]
+    fn distributions() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let generate = |distribution: Distribution, rng: &mut StdRng| distribution.generate(7, rng);
+
+        assert_eq!(
+            generate(Distribution::Sorted, &mut rng),
+            [0, 1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(
+            generate(Distribution::Reversed, &mut rng),
+            [6, 5, 4, 3, 2, 1, 0]
+        );
+        assert_eq!(
+            generate(Distribution::OrganPipe, &mut rng),
+            [0, 1, 2, 3, 2, 1, 0]
+        );
+        assert!(generate(Distribution::FewUnique, &mut rng)
+            .iter()
+            .all(|&value| value < Distribution::FEW));
+
+        for distribution in Distribution::ALL {
+            assert_eq!(distribution.name().parse(), Ok(distribution));
+        }
+    }
+
+    #[test]
+    fn skips_impractical_sizes() {
+        let measurements = run(&[4, 100], &[Distribution::Random], 0);
+
+        let bogosort = measurements
+            .iter()
+            .filter(|m| m.algorithm == "Bogosort")
+            .map(|m| m.len)
+            .collect::<Vec<_>>();
+        assert_eq!(bogosort, [4]);
+        assert_eq!(measurements.len(), (13 + OTHER_SORTS.len()) * 2 - 1);
+
+        let counting_sort = measurements
+            .iter()
+            .find(|m| m.algorithm == "Counting sort")
+            .unwrap();
+        assert_eq!(
+            (counting_sort.comparisons, counting_sort.swaps),
+            (None, None)
+        );
+    }
+
+    fn measurement(algorithm: &'static str, len: usize, comparisons: u64) -> Measurement {
+        Measurement {
+            algorithm,
+            distribution: Distribution::Random,
+            len,
+            comparisons: Some(comparisons),
+            swaps: Some(1),
+            elapsed: Duration::from_nanos(1500),
+        }
+    }
+
+    #[test]
+    fn formats() {
+        let measurements = [
+            measurement("Linear", 10, 10),
+            measurement("Linear", 1000, 1000),
+            measurement("Quadratic", 10, 100),
+            measurement("Quadratic", 1000, 1000000),
+        ];
+
+        assert_eq!(
+            csv(&measurements[..1]),
+            "algorithm,distribution,size,comparisons,swaps,nanoseconds\n\
+             Linear,random,10,10,1,1500\n"
+        );
+
+        let uncounted = Measurement {
+            comparisons: None,
+            swaps: None,
+            ..measurement("Counting", 10, 0)
+        };
+        assert_eq!(
+            csv(std::slice::from_ref(&uncounted)).lines().nth(1),
+            Some("Counting,random,10,,,1500")
+        );
+        assert_eq!(
+            table(&[uncounted]).lines().nth(1),
+            Some(
+                "Counting         random             10                                        1.5"
+            )
+        );
+
+        assert_eq!(
+            plot(&measurements, Distribution::Random, 5, 3),
+            "Comparisons by size, random input (log-log)\n\
+             \x20  1000000 |    b\n\
+             \x20          |    a\n\
+             \x20       10 |b\n\
+             \x20          +-----\n\
+             \x20           10 1000\n\
+             \x20           a = Linear\n\
+             \x20           b = Quadratic\n"
+        );
+    }
+}
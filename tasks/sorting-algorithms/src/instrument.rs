@@ -0,0 +1,242 @@
+//! Counting the comparisons and swaps that a sort makes, with an instrumented element type.
+
+use std::cell::Cell;
+use std::cmp::Ordering;
+
+use crate::Sorter;
+
+/// The work done by a sort.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counts {
+    pub comparisons: u64,
+
+    /// The number of swaps, if the sorter [counts swaps](Sorter::COUNTS_SWAPS).
+    pub swaps: Option<u64>,
+}
+
+/// The counters shared by the elements of one input.
+#[derive(Debug, Default)]
+pub struct Tally {
+    comparisons: Cell<u64>,
+    moves: Cell<u64>,
+}
+
+impl Tally {
+    pub fn new() -> Tally {
+        Tally::default()
+    }
+
+    /// Wraps values in elements that count their comparisons in this tally.
+    pub fn wrap<T>(&self, values: impl IntoIterator<Item = T>) -> Vec<Counted<'_, T>> {
+        values
+            .into_iter()
+            .map(|value| Counted {
+                value,
+                tally: self,
+                place: Cell::new(0),
+            })
+            .collect()
+    }
+
+    /// Notes where each element is, so that only moves from here on are counted.
+    pub fn settle<T>(&self, elements: &[Counted<'_, T>]) {
+        for element in elements {
+            element.place.set(element.address());
+        }
+    }
+
+    /// Looks for elements that moved since they were last seen, and returns the counts so far. A
+    /// swap moves two elements, so the swaps are half of the moves, if `counts_swaps` is true.
+    pub fn finish<T>(&self, elements: &[Counted<'_, T>], counts_swaps: bool) -> Counts {
+        for element in elements {
+            element.observe();
+        }
+
+        Counts {
+            comparisons: self.comparisons.get(),
+            swaps: counts_swaps.then(|| self.moves.get() / 2),
+        }
+    }
+}
+
+/// An element that counts how many times it is compared, and notices when it is moved.
+///
+/// Sorts move elements without calling any of their methods, so moves can't be counted directly.
+/// Instead, each element remembers where it was when it was last seen, and a move is counted
+/// whenever it is seen somewhere else: when it is compared, and when the sort is finished. This
+/// counts every move of a sorter that compares each element it moves before moving it again. An
+/// element that is moved several times without being compared in between, as by the flips of a
+/// pancake sort, is only counted once, so such sorters don't report swaps.
+#[derive(Debug)]
+pub struct Counted<'a, T> {
+    pub value: T,
+    tally: &'a Tally,
+    place: Cell<usize>,
+}
+
+impl<'a, T> Counted<'a, T> {
+    fn address(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    fn observe(&self) {
+        let address = self.address();
+        if self.place.replace(address) != address {
+            self.tally.moves.set(self.tally.moves.get() + 1);
+        }
+    }
+}
+
+impl<'a, T: Ord> PartialEq for Counted<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a, T: Ord> Eq for Counted<'a, T> {}
+
+impl<'a, T: Ord> PartialOrd for Counted<'a, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, T: Ord> Ord for Counted<'a, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.observe();
+        other.observe();
+        self.tally.comparisons.set(self.tally.comparisons.get() + 1);
+        self.value.cmp(&other.value)
+    }
+}
+
+/// Sorts a copy of the input with instrumented elements, and counts the work the sort did.
+pub fn count<S, T>(sorter: &S, input: &[T]) -> Counts
+where
+    S: Sorter,
+    T: Ord + Clone,
+{
+    let tally = Tally::new();
+    let mut elements = tally.wrap(input.iter().cloned());
+    tally.settle(&elements);
+
+    sorter.sort(&mut elements);
+    debug_assert!(elements.windows(2).all(|w| w[0].value <= w[1].value));
+
+    tally.finish(&elements, S::COUNTS_SWAPS)
+}
+
+#[cfg(test)]
+mod tests {
+    use meta::test_utils::test_rng;
+    use rand::seq::SliceRandom;
+
+    use super::{count, Counts};
+    use crate::{
+        BubbleSort, CocktailSort, GnomeSort, InsertionSort, PancakeSort, SelectionSort, Sorter,
+        Visitor,
+    };
+
+    #[test]
+    fn reversed_insertion_sort() {
+        // Every pair is out of order, so each one is compared and swapped exactly once.
+        let input = (0..10).rev().collect::<Vec<_>>();
+        let expected = Counts {
+            comparisons: 45,
+            swaps: Some(45),
+        };
+
+        assert_eq!(count(&InsertionSort, &input), expected);
+        assert_eq!(count(&BubbleSort, &input).swaps, Some(45));
+
+        // The smallest unsorted element is swapped into place until the middle is reached.
+        assert_eq!(count(&SelectionSort, &input).swaps, Some(5));
+        assert_eq!(count(&PancakeSort, &input).swaps, None);
+    }
+
+    #[test]
+    fn sorted_input() {
+        let input = (0..10).collect::<Vec<_>>();
+
+        assert_eq!(
+            count(&InsertionSort, &input),
+            Counts {
+                comparisons: 9,
+                swaps: Some(0),
+            }
+        );
+
+        // Selection sort always compares every pair, and swaps each element with itself.
+        assert_eq!(
+            count(&SelectionSort, &input),
+            Counts {
+                comparisons: 45,
+                swaps: Some(0),
+            }
+        );
+    }
+
+    #[test]
+    fn adjacent_swaps_fix_one_inversion_each() {
+        let mut rng = test_rng();
+        let mut input = (0..50).collect::<Vec<_>>();
+        input.shuffle(&mut rng);
+        let inversions = inversions(&input);
+
+        assert_eq!(count(&BubbleSort, &input).swaps, Some(inversions));
+        assert_eq!(count(&CocktailSort, &input).swaps, Some(inversions));
+        assert_eq!(count(&GnomeSort, &input).swaps, Some(inversions));
+        assert_eq!(count(&InsertionSort, &input).swaps, Some(inversions));
+    }
+
+    /// The number of pairs of elements that are out of order.
+    fn inversions(input: &[u32]) -> u64 {
+        input
+            .iter()
+            .enumerate()
+            .map(|(i, a)| input[i + 1..].iter().filter(|&b| b < a).count() as u64)
+            .sum()
+    }
+
+    /// Checks that the swaps counted for each sorter have the parity of the permutation that sorts
+    /// the input, which they wouldn't if a swap were missed.
+    struct SwapParity(Vec<Vec<u32>>);
+
+    impl Visitor for SwapParity {
+        fn visit<S: Sorter>(&mut self, sorter: S) {
+            for input in &self.0 {
+                if S::MAX_LEN.is_some_and(|max_len| input.len() > max_len) {
+                    continue;
+                }
+
+                let swaps = match count(&sorter, input).swaps {
+                    Some(swaps) => swaps,
+                    None => continue,
+                };
+
+                assert_eq!(
+                    swaps % 2,
+                    inversions(input) % 2,
+                    "{} on {:?}",
+                    S::NAME,
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn swaps_have_the_parity_of_the_permutation() {
+        let mut rng = test_rng();
+        let inputs = (0..100)
+            .map(|i| {
+                let len = i % 30;
+                let mut input = (0..len).collect::<Vec<_>>();
+                input.shuffle(&mut rng);
+                input
+            })
+            .collect();
+
+        crate::visit_all(&mut SwapParity(inputs));
+    }
+}
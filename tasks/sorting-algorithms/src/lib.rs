@@ -0,0 +1,181 @@
+//! The solutions to the [sorting algorithm] tasks, behind a common [`Sorter`] trait.
+//!
+//! Each comparison sort is a unit struct that implements [`Sorter`], so that code can be written
+//! once for every algorithm, either generically or with a [`Visitor`] passed to [`visit_all`]. The
+//! algorithms that only sort integers are in [`integers`], the radix sorts of other keys are in
+//! [`radix`], and the multi-threaded merge sort is [`par_merge_sort_by`].
+//!
+//! The binary compares the algorithms empirically, with the [`benchmark`] module: it counts the
+//! comparisons and swaps each one makes on inputs of different sizes and shapes.
+//!
+//! ```
+//! use sorting_algorithms::{HeapSort, Sorter};
+//!
+//! let mut words = ["beach", "car", "airplane"];
+//! HeapSort.sort_by_key(&mut words, |word| word.len());
+//! assert_eq!(words, ["car", "beach", "airplane"]);
+//! ```
+//!
+//! [sorting algorithm]: http://rosettacode.org/wiki/Category:Sorting_Algorithms
+
+use std::cmp::Ordering;
+
+pub mod benchmark;
+pub mod instrument;
+
+pub use merge_sort::{par_merge_sort, par_merge_sort_by};
+
+/// Whether a sort keeps elements that compare equal in the order they had in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stability {
+    Stable,
+    Unstable,
+}
+
+/// A comparison sort.
+pub trait Sorter {
+    /// The name of the algorithm, such as "Bubble sort".
+    const NAME: &'static str;
+
+    /// Whether the algorithm is stable.
+    const STABILITY: Stability;
+
+    /// The length of the longest input that the algorithm sorts in a reasonable time, if it is
+    /// impractical for inputs of a thousand elements or more.
+    const MAX_LEN: Option<usize> = None;
+
+    /// Whether the algorithm compares each element that it moves before moving it again, so that
+    /// [`instrument::count`] can count its swaps. Algorithms that move elements several times in
+    /// a row, like the flips of a pancake sort, or the swap of a heap's new root to the end of a
+    /// heapsort, don't.
+    const COUNTS_SWAPS: bool = true;
+
+    /// Sorts a slice with a comparator, like [`slice::sort_by`].
+    fn sort_by<T, F>(&self, v: &mut [T], compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Sorts a slice in ascending order, like [`slice::sort`].
+    fn sort<T: Ord>(&self, v: &mut [T]) {
+        self.sort_by(v, T::cmp);
+    }
+
+    /// Sorts a slice by the keys that a function extracts from its elements, like
+    /// [`slice::sort_by_key`].
+    fn sort_by_key<T, K, F>(&self, v: &mut [T], mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(v, |a, b| f(a).cmp(&f(b)));
+    }
+}
+
+/// Something to do with every [`Sorter`], given to [`visit_all`].
+///
+/// [`Sorter`] has generic methods, so sorters can't be collected as trait objects. A visitor is
+/// called with each of them in turn instead.
+pub trait Visitor {
+    fn visit<S: Sorter>(&mut self, sorter: S);
+}
+
+macro_rules! sorters {
+    ($(
+        $(#[$attr:meta])*
+        $sorter:ident => $function:path, $name:expr, $stability:ident
+            $(, max_len = $max_len:expr)? $(, counts_swaps = $counts_swaps:expr)?;
+    )*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+            pub struct $sorter;
+
+            impl Sorter for $sorter {
+                const NAME: &'static str = $name;
+                const STABILITY: Stability = Stability::$stability;
+                $(const MAX_LEN: Option<usize> = Some($max_len);)?
+                $(const COUNTS_SWAPS: bool = $counts_swaps;)?
+
+                fn sort_by<T, F>(&self, v: &mut [T], compare: F)
+                where
+                    F: FnMut(&T, &T) -> Ordering,
+                {
+                    $function(v, compare);
+                }
+            }
+        )*
+
+        /// Calls the visitor with every sorter, in alphabetical order.
+        pub fn visit_all<V: Visitor>(visitor: &mut V) {
+            $(visitor.visit($sorter);)*
+        }
+    };
+}
+
+sorters! {
+    /// Shuffles the slice until it happens to be sorted.
+    Bogosort => bogosort::bogo_sort_by, "Bogosort", Unstable, max_len = 8, counts_swaps = false;
+
+    /// Swaps adjacent elements that are out of order until there are none left.
+    BubbleSort => bubble_sort::bubble_sort_by, "Bubble sort", Stable;
+
+    /// A bubble sort that alternates between passes up and down the slice.
+    CocktailSort => cocktail_sort::cocktail_sort_by, "Cocktail sort", Stable;
+
+    /// A bubble sort that compares elements a shrinking gap apart.
+    CombSort => comb_sort::comb_sort_by, "Comb sort", Unstable;
+
+    /// Moves each element back until it meets a smaller one, one swap at a time.
+    GnomeSort => gnome_sort::gnome_sort_by, "Gnome sort", Stable;
+
+    /// Builds a max-heap, then repeatedly moves its root to the end of the slice.
+    HeapSort => heapsort::heap_sort_by, "Heapsort", Unstable, counts_swaps = false;
+
+    /// Inserts each element into the sorted prefix before it.
+    InsertionSort => insertion_sort::insertion_sort_by, "Insertion sort", Stable;
+
+    /// Sorts each half of the slice, then merges them.
+    MergeSort => merge_sort::merge_sort_by, "Merge sort", Stable, counts_swaps = false;
+
+    /// Flips the largest unsorted element to the front, then to its place.
+    PancakeSort => pancake_sort::pancake_sort_by, "Pancake sort", Unstable, counts_swaps = false;
+
+    /// Partitions the slice around a pivot, then sorts each side.
+    QuickSort => quicksort::quick_sort_by, "Quicksort", Unstable, counts_swaps = false;
+
+    /// Swaps the smallest unsorted element into place.
+    SelectionSort => selection_sort::selection_sort_by, "Selection sort", Unstable;
+
+    /// An insertion sort over elements a shrinking gap apart.
+    ShellSort => shell_sort::shell_sort_by, "Shell sort", Unstable;
+
+    /// Sorts the first two thirds, the last two thirds, then the first two thirds again.
+    StoogeSort => stooge_sort::stoogesort_by, "Stooge sort", Unstable, max_len = 1000;
+}
+
+/// Sorts that work on the values of integers rather than by comparing them.
+pub mod integers {
+    /// The widest range of values that [`counting_sort`] counts, which takes 8 MiB of counts.
+    pub const COUNTING_SORT_MAX_RANGE: u64 = 1 << 20;
+
+    /// Sorts integers by counting how many times each value between the smallest and the largest
+    /// occurs, which takes time and memory proportional to the difference between them. If the
+    /// difference is more than [`COUNTING_SORT_MAX_RANGE`], they are sorted with [`radix_sort`]
+    /// instead.
+    pub fn counting_sort(v: &mut [i32]) {
+        if let (Some(&min), Some(&max)) = (v.iter().min(), v.iter().max()) {
+            if ((i64::from(max) - i64::from(min)) as u64) < COUNTING_SORT_MAX_RANGE {
+                ::counting_sort::counting_sort(v, min, max);
+            } else {
+                radix_sort(v);
+            }
+        }
+    }
+
+    pub use radix_sort::radix_sort;
+}
+
+/// Radix sorts of integers, floats and byte strings.
+pub mod radix {
+    pub use radix_sort::{lsd_radix_sort, msd_radix_sort, FixedRadixKey, RadixKey};
+}
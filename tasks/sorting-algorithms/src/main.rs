@@ -0,0 +1,71 @@
+use sorting_algorithms::benchmark::{self, Distribution};
+use structopt::clap::arg_enum;
+use structopt::StructOpt;
+
+arg_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Format {
+        Table,
+        Csv
+    }
+}
+
+/// Counts the comparisons and swaps that each sorting algorithm makes, and times them
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The sizes of the inputs, separated by commas
+    #[structopt(short, long, use_delimiter = true, default_value = "10,100,1000")]
+    sizes: Vec<usize>,
+
+    /// The shapes of the inputs: random, sorted, reversed, few-unique or organ-pipe. Defaults to
+    /// all of them
+    #[structopt(short, long = "distribution", number_of_values = 1)]
+    distributions: Vec<Distribution>,
+
+    /// The format of the output
+    #[structopt(
+        long,
+        possible_values = &Format::variants(),
+        case_insensitive = true,
+        default_value = "table"
+    )]
+    format: Format,
+
+    /// Also plot the comparisons against the size of the input for each distribution
+    #[structopt(long)]
+    plot: bool,
+
+    /// The seed of the random inputs
+    #[structopt(long, default_value = "0")]
+    seed: u64,
+}
+
+/// The size of the plots, in characters.
+const PLOT_WIDTH: usize = 60;
+const PLOT_HEIGHT: usize = 20;
+
+fn main() {
+    let opt = Opt::from_args();
+    let distributions = if opt.distributions.is_empty() {
+        Distribution::ALL.to_vec()
+    } else {
+        opt.distributions
+    };
+
+    let measurements = benchmark::run(&opt.sizes, &distributions, opt.seed);
+
+    match opt.format {
+        Format::Table => print!("{}", benchmark::table(&measurements)),
+        Format::Csv => print!("{}", benchmark::csv(&measurements)),
+    }
+
+    if opt.plot {
+        for &distribution in &distributions {
+            println!();
+            print!(
+                "{}",
+                benchmark::plot(&measurements, distribution, PLOT_WIDTH, PLOT_HEIGHT)
+            );
+        }
+    }
+}
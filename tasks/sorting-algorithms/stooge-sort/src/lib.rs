@@ -0,0 +1,47 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
+use std::cmp::Ordering;
+
+pub fn stoogesort<E>(a: &mut [E])
+where
+    E: PartialOrd,
+{
+    stoogesort_by(a, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+/// Like [`stoogesort`], but orders elements with a comparator.
+pub fn stoogesort_by<E, F>(a: &mut [E], mut compare: F)
+where
+    F: FnMut(&E, &E) -> Ordering,
+{
+    sort(a, &mut compare);
+}
+
+fn sort<E, F>(a: &mut [E], compare: &mut F)
+where
+    F: FnMut(&E, &E) -> Ordering,
+{
+    if a.is_empty() {
+        return;
+    }
+
+    let len = a.len();
+
+    if compare(a.first().unwrap(), a.last().unwrap()) == Ordering::Greater {
+        a.swap(0, len - 1);
+    }
+    if len > 2 {
+        let t = len / 3;
+        sort(&mut a[..len - t], compare);
+        sort(&mut a[t..], compare);
+        sort(&mut a[..len - t], compare);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_sort!(super::stoogesort, large_len = 100);
+    test_sort_floats!(super::stoogesort, large_len = 100);
+}
@@ -0,0 +1,135 @@
+//! Runs every sorting algorithm through the same tests.
+
+use sorting_algorithms::integers::{counting_sort, radix_sort};
+use sorting_algorithms::radix::{lsd_radix_sort, msd_radix_sort};
+use sorting_algorithms::{par_merge_sort, Sorter, Stability, Visitor};
+
+/// Generates the tests of a sorter, in a module of their own. Stable sorters are also checked for
+/// stability, and every sorter is checked to declare the stability it is tested for.
+macro_rules! test_sorter {
+    ($module:ident => $sorter:ident, $stability:ident $(, large_len = $large_len:expr)? $(, #[$attr:meta])*) => {
+        mod $module {
+            use std::cmp::Ordering;
+
+            use meta::test_utils::check_sort_by;
+            use sorting_algorithms::{$sorter, Sorter, Stability};
+
+            const LARGE_LEN: usize = test_sorter!(@large_len $($large_len)?);
+
+            fn sort<T: Ord>(v: &mut [T]) {
+                $sorter.sort(v);
+            }
+
+            fn sort_by<T, F>(v: &mut [T], compare: F)
+            where
+                F: FnMut(&T, &T) -> Ordering,
+            {
+                $sorter.sort_by(v, compare);
+            }
+
+            fn sort_floats(v: &mut [f64]) {
+                $sorter.sort_by(v, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            }
+
+            meta::test_sort!(sort, large_len = LARGE_LEN $(, #[$attr])*);
+            meta::test_sort_by!(sort_by, large_len = LARGE_LEN $(, #[$attr])*);
+            meta::test_sort_floats!(sort_floats, large_len = LARGE_LEN $(, #[$attr])*);
+
+            $(#[$attr])*
+            #[test]
+            fn by_key() {
+                let original = ["beach", "car", "airplane", "art", "house"];
+                let mut words = original;
+                $sorter.sort_by_key(&mut words, |word| word.len());
+                check_sort_by(&original, &words, |a, b| a.len().cmp(&b.len()));
+            }
+
+            #[test]
+            fn declared_stability() {
+                assert_eq!($sorter::STABILITY, Stability::$stability);
+            }
+
+            test_sorter!(@$stability $(#[$attr])*);
+        }
+    };
+    (@large_len) => {
+        meta::test_utils::LARGE_INPUT_LEN
+    };
+    (@large_len $large_len:expr) => {
+        $large_len
+    };
+    (@Stable $(#[$attr:meta])*) => {
+        fn stable_sort(v: &mut [meta::test_utils::Keyed]) {
+            sort(v);
+        }
+
+        meta::test_stable_sort!(stable_sort, large_len = LARGE_LEN $(, #[$attr])*);
+    };
+    (@Unstable $(#[$attr:meta])*) => {};
+}
+
+// The sort is random, so it could take a very long time!
+test_sorter!(bogosort => Bogosort, Unstable, large_len = 8, #[ignore]);
+test_sorter!(bubble_sort => BubbleSort, Stable);
+test_sorter!(cocktail_sort => CocktailSort, Stable);
+test_sorter!(comb_sort => CombSort, Unstable);
+test_sorter!(gnome_sort => GnomeSort, Stable);
+test_sorter!(heap_sort => HeapSort, Unstable);
+test_sorter!(insertion_sort => InsertionSort, Stable);
+test_sorter!(merge_sort => MergeSort, Stable);
+test_sorter!(pancake_sort => PancakeSort, Unstable);
+test_sorter!(quick_sort => QuickSort, Unstable);
+test_sorter!(selection_sort => SelectionSort, Unstable);
+test_sorter!(shell_sort => ShellSort, Unstable);
+test_sorter!(stooge_sort => StoogeSort, Unstable, large_len = 100);
+
+/// Collects the name and stability of every sorter.
+#[derive(Default)]
+struct Names(Vec<(&'static str, Stability)>);
+
+impl Visitor for Names {
+    fn visit<S: Sorter>(&mut self, _: S) {
+        self.0.push((S::NAME, S::STABILITY));
+    }
+}
+
+#[test]
+fn visit_all() {
+    let mut names = Names::default();
+    sorting_algorithms::visit_all(&mut names);
+
+    assert_eq!(names.0.len(), 13);
+    assert!(names.0.contains(&("Merge sort", Stability::Stable)));
+    assert!(names.0.contains(&("Quicksort", Stability::Unstable)));
+}
+
+#[test]
+fn integer_sorts() {
+    let mut rng = meta::test_utils::test_rng();
+
+    for sort in [
+        counting_sort,
+        radix_sort,
+        lsd_radix_sort,
+        msd_radix_sort,
+        par_merge_sort,
+    ] {
+        let mut numbers = meta::test_utils::random_vec::<i16>(&mut rng, 1000)
+            .into_iter()
+            .map(i32::from)
+            .collect::<Vec<_>>();
+        let original = numbers.clone();
+        sort(&mut numbers);
+        meta::test_utils::check_sort(&original, &numbers);
+
+        let mut empty = [];
+        sort(&mut empty);
+
+        let mut extremes = [i32::MAX, 0, i32::MIN, -1, i32::MAX, i32::MIN + 1];
+        sort(&mut extremes);
+        assert_eq!(
+            extremes,
+            [i32::MIN, i32::MIN + 1, -1, 0, i32::MAX, i32::MAX]
+        );
+    }
+}
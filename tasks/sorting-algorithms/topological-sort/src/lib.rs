@@ -0,0 +1,499 @@
+//! Topological sorting of dependency graphs, with Kahn's algorithm.
+//!
+//! Ties are always broken in the order of the nodes, so the same graph is always sorted the same
+//! way. Graphs with cycles can't be sorted, and every cycle is reported instead.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::iter::FromIterator;
+
+/// A directed graph, where an edge from `a` to `b` means that `a` must come before `b`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph<N: Ord> {
+    /// The nodes that must come after each node.
+    successors: BTreeMap<N, BTreeSet<N>>,
+}
+
+impl<N: Ord> Default for Graph<N> {
+    fn default() -> Self {
+        Graph {
+            successors: BTreeMap::new(),
+        }
+    }
+}
+
+impl<N: Ord + Clone> Graph<N> {
+    pub fn new() -> Self {
+        Graph::default()
+    }
+
+    /// Adds a node without any edges, if it isn't in the graph yet.
+    pub fn add_node(&mut self, node: N) {
+        self.successors.entry(node).or_default();
+    }
+
+    /// Adds an edge that makes `before` come before `after`, and both nodes if they aren't in the
+    /// graph yet.
+    pub fn add_edge(&mut self, before: N, after: N) {
+        self.add_node(after.clone());
+        self.successors.entry(before).or_default().insert(after);
+    }
+
+    /// The nodes of the graph, in order.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.successors.keys()
+    }
+
+    /// The number of edges that end at each node.
+    fn in_degrees(&self) -> BTreeMap<&N, usize> {
+        let mut in_degrees = self
+            .successors
+            .keys()
+            .map(|node| (node, 0))
+            .collect::<BTreeMap<_, _>>();
+        for after in self.successors.values().flatten() {
+            *in_degrees.get_mut(after).unwrap() += 1;
+        }
+        in_degrees
+    }
+
+    /// Sorts the nodes so that each one comes after every node that has an edge to it. When
+    /// several nodes could come next, the smallest one does.
+    pub fn sort(&self) -> Result<Vec<N>, CycleError<N>> {
+        let mut in_degrees = self.in_degrees();
+        let mut ready = in_degrees
+            .iter()
+            .filter(|&(_, &degree)| degree == 0)
+            .map(|(&node, _)| node)
+            .collect::<BTreeSet<_>>();
+
+        let mut sorted = Vec::with_capacity(self.successors.len());
+        while let Some(node) = ready.pop_first() {
+            sorted.push(node.clone());
+
+            for after in &self.successors[node] {
+                let degree = in_degrees.get_mut(after).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.insert(after);
+                }
+            }
+        }
+
+        if sorted.len() == self.successors.len() {
+            Ok(sorted)
+        } else {
+            Err(self.cycle_error())
+        }
+    }
+
+    /// Groups the nodes into levels: the first level holds the nodes without edges to them, and
+    /// each following level holds the nodes whose edges all come from earlier levels. The nodes of
+    /// a level don't depend on each other, so they could be processed in parallel. Each level is
+    /// in order.
+    pub fn levels(&self) -> Result<Vec<Vec<N>>, CycleError<N>> {
+        let mut in_degrees = self.in_degrees();
+        let mut level = in_degrees
+            .iter()
+            .filter(|&(_, &degree)| degree == 0)
+            .map(|(&node, _)| node)
+            .collect::<Vec<_>>();
+
+        let mut levels = vec![];
+        let mut count = 0;
+        while !level.is_empty() {
+            let mut next = BTreeSet::new();
+            for node in &level {
+                for after in &self.successors[*node] {
+                    let degree = in_degrees.get_mut(after).unwrap();
+                    *degree -= 1;
+                    if *degree == 0 {
+                        next.insert(after);
+                    }
+                }
+            }
+
+            count += level.len();
+            levels.push(level.into_iter().cloned().collect());
+            level = next.into_iter().collect();
+        }
+
+        if count == self.successors.len() {
+            Ok(levels)
+        } else {
+            Err(self.cycle_error())
+        }
+    }
+
+    /// Finds every cycle in the graph.
+    ///
+    /// Nodes on cycles that share nodes are reported together, as one [`Cycle`]: the strongly
+    /// connected components of the graph with more than one node, or with an edge from a node to
+    /// itself. The cycles are in the order of their smallest nodes.
+    pub fn cycles(&self) -> Vec<Cycle<N>> {
+        let mut tarjan = Tarjan {
+            graph: self,
+            indices: BTreeMap::new(),
+            low_links: BTreeMap::new(),
+            stack: vec![],
+            on_stack: BTreeSet::new(),
+            components: vec![],
+        };
+        for node in self.successors.keys() {
+            if !tarjan.indices.contains_key(node) {
+                tarjan.visit(node);
+            }
+        }
+
+        let mut cycles = tarjan
+            .components
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || self.successors[component[0]].contains(component[0])
+            })
+            .map(|mut component| {
+                component.sort();
+                let example = self.shortest_cycle(&component);
+                Cycle {
+                    nodes: component.into_iter().cloned().collect(),
+                    example,
+                }
+            })
+            .collect::<Vec<_>>();
+        cycles.sort_by(|a, b| a.nodes[0].cmp(&b.nodes[0]));
+        cycles
+    }
+
+    fn cycle_error(&self) -> CycleError<N> {
+        CycleError {
+            cycles: self.cycles(),
+        }
+    }
+
+    /// Finds the shortest cycle through the smallest node of a strongly connected component, with
+    /// a breadth-first search that stays inside the component.
+    fn shortest_cycle(&self, component: &[&N]) -> Vec<N> {
+        let start = component[0];
+        let mut parents = BTreeMap::<&N, &N>::new();
+        let mut queue = VecDeque::from(vec![start]);
+
+        while let Some(node) = queue.pop_front() {
+            for after in &self.successors[node] {
+                if after == start {
+                    let mut path = vec![node.clone()];
+                    let mut current = node;
+                    while current != start {
+                        current = parents[current];
+                        path.push(current.clone());
+                    }
+                    path.reverse();
+                    return path;
+                }
+
+                if component.binary_search(&after).is_ok() && !parents.contains_key(after) {
+                    parents.insert(after, node);
+                    queue.push_back(after);
+                }
+            }
+        }
+
+        unreachable!("every node of a strongly connected component is on a cycle")
+    }
+}
+
+impl<'a> Graph<&'a str> {
+    /// Parses a list of dependencies in the format of the Rosetta Code task: each line names a
+    /// node, followed by the nodes it depends on, separated by whitespace. Nodes that depend on
+    /// themselves are ignored, and empty lines are skipped.
+    ///
+    /// The listing may start with a header underlined by a rule of `=` characters, as in the
+    /// task, in which case the header and the rule are skipped.
+    pub fn parse(input: &'a str) -> Self {
+        let is_rule = |line: &str| line.chars().all(|c| c == '=' || c.is_whitespace());
+        let lines = input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+        let has_header = lines.len() > 1 && is_rule(lines[1]);
+
+        let mut graph = Graph::new();
+        for line in lines.into_iter().skip(if has_header { 2 } else { 0 }) {
+            if is_rule(line) {
+                continue;
+            }
+
+            let mut names = line.split_whitespace();
+            let node = names.next().unwrap();
+            graph.add_node(node);
+            for dependency in names.filter(|&dependency| dependency != node) {
+                graph.add_edge(dependency, node);
+            }
+        }
+        graph
+    }
+}
+
+impl<N: Ord + Clone> FromIterator<(N, N)> for Graph<N> {
+    /// Builds a graph from its edges.
+    fn from_iter<I: IntoIterator<Item = (N, N)>>(edges: I) -> Self {
+        let mut graph = Graph::new();
+        graph.extend(edges);
+        graph
+    }
+}
+
+impl<N: Ord + Clone> Extend<(N, N)> for Graph<N> {
+    fn extend<I: IntoIterator<Item = (N, N)>>(&mut self, edges: I) {
+        for (before, after) in edges {
+            self.add_edge(before, after);
+        }
+    }
+}
+
+/// Strongly connected components with Tarjan's algorithm.
+///
+/// The depth-first search keeps its own stack instead of recursing, so that long chains of
+/// dependencies can't overflow the call stack.
+struct Tarjan<'g, N: Ord> {
+    graph: &'g Graph<N>,
+    indices: BTreeMap<&'g N, usize>,
+    low_links: BTreeMap<&'g N, usize>,
+    stack: Vec<&'g N>,
+    on_stack: BTreeSet<&'g N>,
+    components: Vec<Vec<&'g N>>,
+}
+
+impl<'g, N: Ord> Tarjan<'g, N> {
+    /// Numbers a node the first time it is reached, and puts it on the stack.
+    fn enter(&mut self, node: &'g N) {
+        let index = self.indices.len();
+        self.indices.insert(node, index);
+        self.low_links.insert(node, index);
+        self.stack.push(node);
+        self.on_stack.insert(node);
+    }
+
+    fn visit(&mut self, root: &'g N) {
+        let graph = self.graph;
+
+        // The nodes being visited, each with the successors it has yet to look at.
+        self.enter(root);
+        let mut path = vec![(root, graph.successors[root].iter())];
+
+        while let Some((node, successors)) = path.last_mut() {
+            let node = *node;
+            if let Some(after) = successors.next() {
+                if !self.indices.contains_key(after) {
+                    self.enter(after);
+                    path.push((after, graph.successors[after].iter()));
+                } else if self.on_stack.contains(after) {
+                    let low_link = self.indices[after].min(self.low_links[node]);
+                    self.low_links.insert(node, low_link);
+                }
+                continue;
+            }
+
+            path.pop();
+            if let Some(&(parent, _)) = path.last() {
+                let low_link = self.low_links[node].min(self.low_links[parent]);
+                self.low_links.insert(parent, low_link);
+            }
+
+            if self.low_links[node] == self.indices[node] {
+                let mut component = vec![];
+                loop {
+                    let member = self.stack.pop().unwrap();
+                    self.on_stack.remove(member);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+}
+
+/// Nodes that depend on each other, directly or not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N> {
+    /// Every node on the cycle, in order. Several cycles may go through them.
+    pub nodes: Vec<N>,
+
+    /// One of the shortest cycles through the smallest node, starting at it. Each node comes
+    /// before the next one, and the last node comes before the first.
+    pub example: Vec<N>,
+}
+
+impl<N: fmt::Display> fmt::Display for Cycle<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for node in &self.example {
+            write!(f, "{} -> ", node)?;
+        }
+        write!(f, "{}", self.example[0])?;
+
+        if self.nodes.len() > self.example.len() {
+            let nodes = self
+                .nodes
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            write!(f, " (among {})", nodes.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The error returned when a graph with cycles is sorted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<N> {
+    pub cycles: Vec<Cycle<N>>,
+}
+
+impl<N: fmt::Display> fmt::Display for CycleError<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "found {} cycle(s):", self.cycles.len())?;
+        for cycle in &self.cycles {
+            write!(f, "\n  {}", cycle)?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: fmt::Debug + fmt::Display> Error for CycleError<N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cycle, Graph};
+
+    /// The libraries of the Rosetta Code task.
+    const LIBRARIES: &str = include_str!("../libraries.txt");
+
+    #[test]
+    fn sort_libraries() {
+        let graph = Graph::parse(LIBRARIES);
+        let sorted = graph.sort().unwrap();
+
+        assert_eq!(
+            sorted,
+            [
+                "ieee",
+                "dware",
+                "dw02",
+                "dw05",
+                "dw06",
+                "dw07",
+                "gtech",
+                "dw01",
+                "dw04",
+                "std",
+                "ramlib",
+                "std_cell_lib",
+                "synopsys",
+                "des_system_lib",
+                "dw03",
+            ]
+        );
+
+        for line in LIBRARIES.lines().skip(2) {
+            let mut names = line.split_whitespace();
+            let node = names.next().unwrap();
+            let position = sorted.iter().position(|&n| n == node).unwrap();
+            for dependency in names.filter(|&d| d != node) {
+                assert!(sorted[..position].contains(&dependency));
+            }
+        }
+    }
+
+    #[test]
+    fn header_is_skipped() {
+        let graph = Graph::parse(LIBRARIES);
+
+        assert!(LIBRARIES.starts_with("LIBRARY          LIBRARY DEPENDENCIES\n======="));
+        assert_eq!(graph.nodes().count(), 15);
+        assert!(graph
+            .nodes()
+            .all(|node| !node.starts_with("LIBRARY") && !node.starts_with('=')));
+        assert_eq!(
+            graph,
+            Graph::parse(LIBRARIES.splitn(3, '\n').last().unwrap())
+        );
+        assert_eq!(Graph::parse("a b\nc\n==\n").nodes().count(), 3);
+    }
+
+    #[test]
+    fn levels() {
+        let graph = Graph::parse(LIBRARIES);
+
+        assert_eq!(
+            graph.levels().unwrap(),
+            [
+                vec!["ieee", "std", "synopsys"],
+                vec!["dware", "gtech", "ramlib", "std_cell_lib"],
+                vec!["dw01", "dw02", "dw05", "dw06", "dw07"],
+                vec!["des_system_lib", "dw03", "dw04"],
+            ]
+        );
+    }
+
+    #[test]
+    fn every_cycle_is_reported() {
+        let mut graph = Graph::parse(LIBRARIES);
+        // dw01 -> dw04 -> dw01, and a separate cycle between two new nodes.
+        graph.add_edge("dw04", "dw01");
+        graph.extend(vec![("x", "y"), ("y", "x"), ("self", "self")]);
+
+        let error = graph.sort().unwrap_err();
+        assert_eq!(graph.levels().unwrap_err(), error);
+        assert_eq!(
+            error.cycles,
+            [
+                Cycle {
+                    nodes: vec!["dw01", "dw04"],
+                    example: vec!["dw01", "dw04"],
+                },
+                Cycle {
+                    nodes: vec!["self"],
+                    example: vec!["self"],
+                },
+                Cycle {
+                    nodes: vec!["x", "y"],
+                    example: vec!["x", "y"],
+                },
+            ]
+        );
+        assert_eq!(
+            error.to_string(),
+            "found 3 cycle(s):\n  dw01 -> dw04 -> dw01\n  self -> self\n  x -> y -> x"
+        );
+    }
+
+    #[test]
+    fn shortest_example_cycle() {
+        let graph = vec![(1, 2), (2, 3), (3, 4), (4, 1), (2, 1)]
+            .into_iter()
+            .collect::<Graph<_>>();
+
+        let cycles = graph.cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].nodes, [1, 2, 3, 4]);
+        assert_eq!(cycles[0].example, [1, 2]);
+        assert_eq!(cycles[0].to_string(), "1 -> 2 -> 1 (among 1, 2, 3, 4)");
+    }
+
+    #[test]
+    fn long_cycle() {
+        // Deep enough to overflow the stack if the search recursed.
+        const LEN: u32 = 100_000;
+        let graph = (0..LEN)
+            .map(|node| (node, (node + 1) % LEN))
+            .collect::<Graph<_>>();
+
+        let cycles = graph.sort().unwrap_err().cycles;
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].nodes.len(), LEN as usize);
+        assert_eq!(cycles[0].example, (0..LEN).collect::<Vec<_>>());
+    }
+}
@@ -1,3 +1,7 @@
+#[cfg(test)]
+#[macro_use]
+extern crate meta;
+
 const ASCII_A: u8 = b'A';
 
 fn main() {
@@ -87,3 +91,14 @@ fn test_empty_string_enc_dec() {
     let dec = vigenere("", "", false);
     assert_eq!("", dec);
 }
+
+#[cfg(test)]
+mod tests {
+    // The cipher discards everything but letters, so only uppercase text survives a round trip.
+    test_cipher! {
+        encrypt: |text| super::vigenere(text, "LEMON", true),
+        decrypt: |text| super::vigenere(text, "LEMON", false),
+        alphabet: "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+        vectors: ["ATTACKATDAWN" => "LXFOPVEFRNHR"],
+    }
+}
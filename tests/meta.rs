@@ -1,12 +1,17 @@
 #[macro_use]
 extern crate lazy_static;
+extern crate mediawiki;
 extern crate meta;
+extern crate serde_json;
 extern crate toml;
 
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use meta::local;
+use mediawiki::Source;
+use meta::local::{self, SourceKind};
+use meta::{Mismatch, TaskIndex, TaskStatus};
+use serde_json::json;
 use toml::Value;
 
 lazy_static! {
@@ -42,3 +47,173 @@ fn tasks_are_sorted() {
 fn parse_local_tasks() {
     local::parse_tasks(&*MANIFEST_PATH).unwrap();
 }
+
+#[test]
+fn local_sources_are_ordered_by_target() {
+    let tasks = local::parse_tasks(&*MANIFEST_PATH).unwrap();
+
+    for task in &tasks {
+        let files = task.source.files();
+        let crate_root = task.manifest_path.parent().unwrap();
+
+        // Library first, then the main binary, then modules and the other targets, each sorted by
+        // path.
+        for pair in files.windows(2) {
+            assert!(
+                (pair[0].kind, &pair[0].path) < (pair[1].kind, &pair[1].path),
+                "{}: {} is listed before {}",
+                task.package_name,
+                pair[0].relative_path.display(),
+                pair[1].relative_path.display(),
+            );
+        }
+        assert!(
+            files
+                .iter()
+                .filter(|file| file.kind == SourceKind::Lib)
+                .count()
+                <= 1
+        );
+        assert!(
+            files
+                .iter()
+                .filter(|file| file.kind == SourceKind::Main)
+                .count()
+                <= 1
+        );
+
+        // Files of crates nested inside the task, such as `tasks/bitmap/read-a-ppm-file`, belong
+        // to those crates.
+        for file in files {
+            let nested = file
+                .path
+                .ancestors()
+                .skip(1)
+                .take_while(|dir| *dir != crate_root)
+                .find(|dir| dir.join("Cargo.toml").exists());
+            assert!(
+                nested.is_none(),
+                "{}: {} belongs to the crate at {}",
+                task.package_name,
+                file.relative_path.display(),
+                nested.unwrap().display(),
+            );
+        }
+    }
+}
+
+/// A fake wiki, on which "FizzBuzz" was renamed to "Fizz buzz", "Hello world/Text" was deleted,
+/// and every other local task exists.
+struct RenamingWiki {
+    local_titles: Vec<String>,
+}
+
+impl Source for RenamingWiki {
+    fn query(&self, params: &[(&str, &str)]) -> mediawiki::Result<String> {
+        let param = |name| params.iter().find(|(k, _)| *k == name).map(|(_, v)| *v);
+
+        let response = match (param("cmtitle"), param("prop")) {
+            (Some("Category:Programming Tasks"), _) => json!({ "query": { "categorymembers":
+                self.local_titles
+                    .iter()
+                    .filter(|title| *title != "FizzBuzz" && *title != "Hello world/Text")
+                    .map(|title| json!({ "pageid": 1, "title": title }))
+                    .collect::<Vec<_>>()
+            }}),
+            (Some(_), _) => json!({ "query": { "categorymembers": [] } }),
+            (None, Some("info")) => {
+                assert_eq!(param("titles"), Some("FizzBuzz|Hello world/Text"));
+                json!({ "query": {
+                    "redirects": [{ "from": "FizzBuzz", "to": "Fizz buzz" }],
+                    "pages": {
+                        "-1": { "title": "Hello world/Text", "missing": "" },
+                        "10": { "pageid": 10, "title": "Fizz buzz" },
+                    },
+                }})
+            }
+            (None, Some("revisions")) => {
+                assert_eq!(param("titles"), Some("Fizz buzz"));
+                json!({ "query": { "pages": { "10": { "pageid": 10, "title": "Fizz buzz" } } } })
+            }
+            _ => panic!("unexpected query: {:?}", params),
+        };
+
+        Ok(response.to_string())
+    }
+}
+
+#[test]
+fn renamed_and_missing_tasks() {
+    let local_titles = local::parse_tasks(&*MANIFEST_PATH)
+        .unwrap()
+        .into_iter()
+        .map(|task| task.title)
+        .collect();
+
+    let task_index = TaskIndex::with_source(
+        env!("CARGO_MANIFEST_DIR"),
+        Box::new(RenamingWiki { local_titles }),
+    )
+    .unwrap();
+
+    assert_eq!(
+        task_index.mismatches(),
+        [
+            Mismatch {
+                package_name: "fizzbuzz".to_owned(),
+                title: "FizzBuzz".to_owned(),
+                status: TaskStatus::Renamed {
+                    to: "Fizz buzz".to_owned()
+                },
+            },
+            Mismatch {
+                package_name: "hello-world-text".to_owned(),
+                title: "Hello world/Text".to_owned(),
+                status: TaskStatus::Missing,
+            },
+        ]
+    );
+
+    // The renamed task is still matched to its local implementation.
+    let tasks = task_index
+        .fetch_tasks(&["Fizz buzz".to_owned()])
+        .collect::<meta::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].local().unwrap().package_name, "fizzbuzz");
+    assert_eq!(*tasks[0].status(), TaskStatus::Draft);
+}
+
+/// A wiki with a single task, "K-d tree", that can also be reached through a redirect.
+struct SingleTaskWiki;
+
+impl Source for SingleTaskWiki {
+    fn query(&self, params: &[(&str, &str)]) -> mediawiki::Result<String> {
+        let titles = params.iter().find(|(k, _)| *k == "titles").unwrap().1;
+
+        let response = match titles {
+            "K-d tree" | "K-D tree" => json!({ "query": {
+                "redirects": [{ "from": "K-D tree", "to": "K-d tree" }],
+                "pages": { "7": { "pageid": 7, "title": "K-d tree", "revisions": [{ "*":
+                    "=={{header|Rust}}==\n<lang rust>fn main() {}</lang>\n"
+                }]}},
+            }}),
+            _ => json!({ "query": { "pages": { "-1": { "title": titles, "missing": "" } } } }),
+        };
+
+        Ok(response.to_string())
+    }
+}
+
+#[test]
+fn fetch_single_task() {
+    let task = meta::fetch_remote_task(&SingleTaskWiki, "K-D tree")
+        .unwrap()
+        .unwrap();
+    assert_eq!(task.title(), "K-d tree");
+    assert_eq!(task.source().as_deref(), Some("fn main() {}"));
+
+    assert!(meta::fetch_remote_task(&SingleTaskWiki, "No such task")
+        .unwrap()
+        .is_none());
+}